@@ -45,7 +45,7 @@ const VOLATILE_ENV: bool = true;
 #[test]
 fn it_can_produce_micro_blocks() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -156,7 +156,7 @@ fn it_can_produce_micro_blocks() {
 #[test]
 fn it_can_produce_macro_blocks() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -190,7 +190,7 @@ fn it_can_produce_macro_blocks() {
 #[test]
 fn it_can_produce_election_blocks() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -229,7 +229,7 @@ fn it_can_produce_election_blocks() {
 fn it_can_produce_a_chain_with_txns() {
     let time = Arc::new(OffsetTime::new());
     let env = if VOLATILE_ENV {
-        VolatileEnvironment::new(10).unwrap()
+        VolatileEnvironment::new(11).unwrap()
     } else {
         let tmp_dir = tempdir().expect("Could not create temporal directory");
         let tmp_dir = tmp_dir.path().to_str().unwrap();
@@ -276,7 +276,7 @@ fn it_can_produce_a_chain_with_txns() {
 #[test]
 fn it_can_revert_unpark_transactions() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -380,7 +380,7 @@ fn it_can_revert_unpark_transactions() {
 #[test]
 fn it_can_revert_create_staker_transaction() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -477,7 +477,7 @@ fn it_can_revert_create_staker_transaction() {
 #[test]
 fn it_can_revert_failed_transactions() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -622,7 +622,7 @@ fn it_can_revert_failed_transactions() {
 #[test]
 fn it_can_revert_failed_vesting_contract_transaction() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -784,7 +784,7 @@ fn it_can_revert_failed_vesting_contract_transaction() {
 #[test]
 fn it_can_revert_reactivate_transaction() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -885,7 +885,7 @@ fn it_can_revert_reactivate_transaction() {
 #[test]
 fn it_can_consume_all_validator_deposit() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -1091,7 +1091,7 @@ fn it_can_consume_all_validator_deposit() {
 #[test]
 fn it_can_revert_failed_delete_validator() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -1254,7 +1254,7 @@ fn it_can_revert_failed_delete_validator() {
 fn it_can_revert_basic_and_create_contracts_txns() {
     let mut rng = test_rng(false);
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -58,6 +58,14 @@ pub enum BlockchainError {
     NoValidatorsFound,
     #[error("Invalid epoch ID")]
     InvalidEpoch,
+    #[error("State at block {0} is not available")]
+    StateNotAvailable(u32),
+    #[error("Accounts tree is incomplete")]
+    AccountsIncomplete,
+    #[error("Balance history is not enabled")]
+    BalanceHistoryDisabled,
+    #[error("Validator history is not enabled")]
+    ValidatorHistoryDisabled,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
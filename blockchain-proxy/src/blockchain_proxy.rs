@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use futures::stream::BoxStream;
-use parking_lot::{RwLock, RwLockReadGuard};
+use parking_lot::{RwLock, RwLockReadGuard, RwLockUpgradableReadGuard};
 
 use nimiq_block::{Block, MacroBlock};
 #[cfg(feature = "full")]
@@ -83,6 +83,28 @@ impl BlockchainProxy {
             }
         }
     }
+
+    /// Returns a guard that prevents the blockchain from being modified while it is held.
+    /// In contrast to a read lock, the holder can still read lock the blockchain without risking
+    /// a deadlock with a writer that is waiting for the lock.
+    pub fn pin(&self) -> BlockchainPin {
+        match self {
+            #[cfg(feature = "full")]
+            BlockchainProxy::Full(blockchain) => BlockchainPin::Full(blockchain.upgradable_read()),
+            BlockchainProxy::Light(light_blockchain) => {
+                BlockchainPin::Light(light_blockchain.upgradable_read())
+            }
+        }
+    }
+}
+
+/// Upgradable read lock on one of our blockchain types, see [`BlockchainProxy::pin`].
+pub enum BlockchainPin<'a> {
+    #[cfg(feature = "full")]
+    /// Upgradable read lock on a Full Blockchain
+    Full(RwLockUpgradableReadGuard<'a, Blockchain>),
+    /// Upgradable read lock on a Light Blockchain
+    Light(RwLockUpgradableReadGuard<'a, LightBlockchain>),
 }
 
 /// The `BlockchainReadProxy` implements `AbstractBlockchain` and allows to access common blockchain functions.
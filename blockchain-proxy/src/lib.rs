@@ -1,5 +1,5 @@
 //! This crate contains an abstraction over multiple types of blockchains.
 
-pub use blockchain_proxy::{BlockchainProxy, BlockchainReadProxy};
+pub use blockchain_proxy::{BlockchainPin, BlockchainProxy, BlockchainReadProxy};
 
 pub(crate) mod blockchain_proxy;
@@ -0,0 +1,160 @@
+use std::borrow::Cow;
+use std::convert::TryInto;
+use std::io;
+
+use nimiq_database::cursor::ReadCursor;
+use nimiq_database::{
+    Database, DatabaseFlags, Environment, ReadTransaction, Transaction, WriteTransaction,
+};
+use nimiq_database_value::{AsDatabaseBytes, FromDatabaseValue};
+use nimiq_keys::Address;
+use nimiq_primitives::coin::Coin;
+
+/// The balance of an address right after the block with the given number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceEntry {
+    pub block_number: u32,
+    pub balance: Coin,
+}
+
+impl AsDatabaseBytes for BalanceEntry {
+    fn as_database_bytes(&self) -> Cow<[u8]> {
+        // The block number is stored in big-endian, such that the entries of an address are
+        // ordered by block number.
+        let bytes = [
+            &self.block_number.to_be_bytes()[..],
+            &u64::from(self.balance).to_be_bytes()[..],
+        ]
+        .concat();
+        Cow::Owned(bytes)
+    }
+}
+
+impl FromDatabaseValue for BalanceEntry {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(BalanceEntry {
+            block_number: u32::from_be_bytes(bytes[..4].try_into().unwrap()),
+            balance: Coin::from_u64_unchecked(u64::from_be_bytes(bytes[4..12].try_into().unwrap())),
+        })
+    }
+}
+
+/// An optional index of the balances of addresses over time. For every block, it stores the new
+/// balance of each address that was touched by the block. Entries are only recorded while the
+/// accounts tree is complete, so the history of an address starts at the block the index was
+/// enabled or, for nodes that sync the accounts tree in chunks, the node finished syncing.
+#[derive(Debug)]
+pub struct BalanceHistoryStore {
+    env: Environment,
+    // A database of balance entries indexed by address, ordered by block number.
+    balance_db: Database,
+}
+
+impl BalanceHistoryStore {
+    const BALANCE_DB_NAME: &'static str = "BalanceHistory";
+
+    pub fn new(env: Environment) -> Self {
+        let balance_db = env.open_database_with_flags(
+            Self::BALANCE_DB_NAME.to_string(),
+            DatabaseFlags::DUPLICATE_KEYS | DatabaseFlags::DUP_FIXED_SIZE_VALUES,
+        );
+        BalanceHistoryStore { env, balance_db }
+    }
+
+    /// Records the balance of the given address after the given block.
+    pub fn put_balance(
+        &self,
+        txn: &mut WriteTransaction,
+        address: &Address,
+        block_number: u32,
+        balance: Coin,
+    ) {
+        txn.put(
+            &self.balance_db,
+            address,
+            &BalanceEntry {
+                block_number,
+                balance,
+            },
+        );
+    }
+
+    /// Removes the balance of the given address recorded for the given block, if any. Since
+    /// blocks are reverted from the head, this is always the last entry of the address.
+    pub fn remove_balance(&self, txn: &mut WriteTransaction, address: &Address, block_number: u32) {
+        if let Some(last) = self.get_last_entry(address, Some(txn)) {
+            if last.block_number == block_number {
+                txn.remove_item(&self.balance_db, address, &last);
+            }
+        }
+    }
+
+    /// Returns the recorded balances of the given address after the blocks in the given range,
+    /// ordered by block number. Only blocks that touched the address have an entry; the balance
+    /// at any other block is the one of the preceding entry. The first entry is the last one at
+    /// or before `from_block`, such that the balance at the start of the range is known.
+    pub fn get_balance_history(
+        &self,
+        address: &Address,
+        from_block: u32,
+        to_block: u32,
+        txn_option: Option<&Transaction>,
+    ) -> Vec<BalanceEntry> {
+        let read_txn: ReadTransaction;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = ReadTransaction::new(&self.env);
+                &read_txn
+            }
+        };
+
+        let mut entries = vec![];
+
+        let mut cursor = txn.cursor(&self.balance_db);
+        let mut entry = cursor.seek_key::<Address, BalanceEntry>(address);
+
+        let mut preceding = None;
+
+        while let Some(balance_entry) = entry {
+            if balance_entry.block_number > to_block {
+                break;
+            }
+            if balance_entry.block_number > from_block {
+                entries.extend(preceding.take());
+                entries.push(balance_entry);
+            } else {
+                preceding = Some(balance_entry);
+            }
+            entry = cursor
+                .next_duplicate::<Address, BalanceEntry>()
+                .map(|(_, v)| v);
+        }
+        entries.extend(preceding);
+
+        entries
+    }
+
+    /// Returns the last recorded balance entry of the given address.
+    fn get_last_entry(
+        &self,
+        address: &Address,
+        txn_option: Option<&Transaction>,
+    ) -> Option<BalanceEntry> {
+        let read_txn: ReadTransaction;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = ReadTransaction::new(&self.env);
+                &read_txn
+            }
+        };
+
+        let mut cursor = txn.cursor(&self.balance_db);
+        cursor.seek_key::<Address, BalanceEntry>(address)?;
+        cursor.last_duplicate::<BalanceEntry>()
+    }
+}
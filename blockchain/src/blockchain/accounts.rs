@@ -1,13 +1,28 @@
+use std::collections::BTreeSet;
+
+use crate::balance_history_store::BalanceEntry;
 use crate::blockchain_state::BlockchainState;
 use crate::Blockchain;
-use nimiq_account::{Accounts, BlockState};
+use nimiq_account::{Account, Accounts, BlockState, Staker};
 use nimiq_account::{BlockLogger, TransactionOperationReceipt};
 use nimiq_block::{Block, BlockError, SkipBlockInfo};
-use nimiq_blockchain_interface::PushError;
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, PushError};
+use nimiq_database::volatile::VolatileEnvironment;
 use nimiq_database::ReadTransaction;
 use nimiq_database::WriteTransaction;
-use nimiq_primitives::{key_nibbles::KeyNibbles, trie::trie_proof::TrieProof};
-use nimiq_transaction::extended_transaction::ExtendedTransaction;
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_primitives::{key_nibbles::KeyNibbles, policy::Policy, trie::trie_proof::TrieProof};
+use nimiq_transaction::{
+    extended_transaction::ExtendedTransaction, inherent::Inherent, ExecutedTransaction,
+};
+
+/// The accounts rebuilt at a past block, together with the head they were rebuilt from.
+pub(crate) struct HistoricAccounts {
+    head_hash: Blake2bHash,
+    block_number: u32,
+    accounts: Accounts,
+}
 
 /// Implements methods to handle the accounts.
 impl Blockchain {
@@ -37,13 +52,30 @@ impl Blockchain {
                 };
 
                 // Check if the receipts contain an error.
-                if let Err(e) = receipts {
-                    return Err(PushError::AccountsError(e));
-                }
+                let receipts = receipts.map_err(PushError::AccountsError)?;
 
-                // Macro blocks are final and receipts for the previous batch are no longer necessary
-                // as rebranching across this block is not possible.
-                self.chain_store.clear_receipts(txn);
+                self.put_balance_history(
+                    accounts,
+                    txn,
+                    macro_block.header.block_number,
+                    &[],
+                    &inherents,
+                );
+                self.put_validator_history(txn, block, block_logger);
+
+                // Macro blocks are final and rebranching across this block is not possible.
+                // We still keep the receipts of the batch this block finalizes (including the
+                // ones of this block), such that historic state can be rebuilt. Older receipts
+                // are no longer necessary.
+                self.chain_store
+                    .put_receipts(txn, macro_block.header.block_number, &receipts);
+                self.chain_store.prune_receipts(
+                    txn,
+                    macro_block
+                        .header
+                        .block_number
+                        .saturating_sub(Policy::blocks_per_batch()),
+                );
 
                 // Store the transactions and the inherents into the History tree.
                 let mut total_tx_size = 0;
@@ -112,6 +144,15 @@ impl Blockchain {
                 self.chain_store
                     .put_receipts(txn, micro_block.header.block_number, &receipts);
 
+                self.put_balance_history(
+                    accounts,
+                    txn,
+                    micro_block.header.block_number,
+                    &body.transactions,
+                    &inherents,
+                );
+                self.put_validator_history(txn, block, block_logger);
+
                 // Store the transactions and the inherents into the History tree.
                 let mut total_tx_size = 0;
                 if state.can_verify_history {
@@ -191,6 +232,14 @@ impl Blockchain {
             panic!("Failed to revert {block} - {e:?}");
         }
 
+        // Remove the balances recorded for this block from the balance history.
+        if let Some(balance_history_store) = &self.balance_history_store {
+            for address in Self::touched_addresses(&body.transactions, &inherents) {
+                balance_history_store.remove_balance(txn, &address, block.block_number());
+            }
+        }
+        self.remove_validator_history(txn, block, &inherents);
+
         // Remove the transactions from the History tree. For this you only need to calculate the
         // number of transactions that you want to remove.
         let num_txs = body.transactions.len() + inherents.len();
@@ -202,6 +251,214 @@ impl Blockchain {
         Ok(total_size)
     }
 
+    /// Records the balances of the addresses touched by the given transactions and inherents in
+    /// the balance history, if it is enabled. Balances can only be read if the accounts tree is
+    /// complete, so nothing is recorded while syncing the accounts tree in chunks.
+    pub(crate) fn put_balance_history(
+        &self,
+        accounts: &Accounts,
+        txn: &mut WriteTransaction,
+        block_number: u32,
+        transactions: &[ExecutedTransaction],
+        inherents: &[Inherent],
+    ) {
+        let balance_history_store = match &self.balance_history_store {
+            Some(balance_history_store) => balance_history_store,
+            None => return,
+        };
+
+        if !accounts.is_complete(Some(txn)) {
+            return;
+        }
+
+        for address in Self::touched_addresses(transactions, inherents) {
+            let balance = accounts.get_complete(&address, Some(txn)).balance();
+            balance_history_store.put_balance(txn, &address, block_number, balance);
+        }
+    }
+
+    /// Returns the addresses whose balance may be changed by the given transactions and inherents.
+    fn touched_addresses(
+        transactions: &[ExecutedTransaction],
+        inherents: &[Inherent],
+    ) -> BTreeSet<Address> {
+        let mut addresses = BTreeSet::new();
+        for transaction in transactions {
+            let transaction = transaction.get_raw_transaction();
+            addresses.insert(transaction.sender.clone());
+            addresses.insert(transaction.recipient.clone());
+        }
+        for inherent in inherents {
+            addresses.insert(inherent.target().clone());
+        }
+        addresses
+    }
+
+    /// Returns the balances of the given address right after the blocks in the given range, as
+    /// recorded in the balance history. Fails if the balance history isn't enabled.
+    pub fn get_balance_history(
+        &self,
+        address: &Address,
+        from_block: u32,
+        to_block: u32,
+    ) -> Result<Vec<BalanceEntry>, BlockchainError> {
+        let balance_history_store = self
+            .balance_history_store
+            .as_ref()
+            .ok_or(BlockchainError::BalanceHistoryDisabled)?;
+
+        Ok(balance_history_store.get_balance_history(address, from_block, to_block, None))
+    }
+
+    /// Returns the account at the given address as it was right after the block at the given
+    /// height. Only the state within the current batch can be rebuilt.
+    pub fn get_account_at(
+        &self,
+        address: &Address,
+        block_number: u32,
+    ) -> Result<Account, BlockchainError> {
+        self.with_accounts_at(block_number, |accounts, txn| {
+            accounts.get_complete(address, Some(txn))
+        })
+    }
+
+    /// Returns the staker with the given address as it was right after the block at the given
+    /// height. Only the state within the current batch can be rebuilt.
+    pub fn get_staker_at(
+        &self,
+        address: &Address,
+        block_number: u32,
+    ) -> Result<Option<Staker>, BlockchainError> {
+        self.with_accounts_at(block_number, |accounts, txn| {
+            let staking_contract =
+                match accounts.get_complete(&Policy::STAKING_CONTRACT_ADDRESS, Some(txn)) {
+                    Account::Staking(staking_contract) => staking_contract,
+                    _ => return Err(BlockchainError::InconsistentState),
+                };
+            let data_store = accounts.data_store(&Policy::STAKING_CONTRACT_ADDRESS);
+            Ok(staking_contract.get_staker(&data_store.read(txn), address))
+        })?
+    }
+
+    /// Rebuilds the accounts as they were right after the block at the given height and passes
+    /// them to the given function. The actual state is never modified and pushing blocks isn't
+    /// blocked meanwhile, but rebuilding the state takes time proportional to the size of the
+    /// accounts tree.
+    ///
+    /// To bound that work, the state is only rebuilt within the current batch, only one state is
+    /// rebuilt at a time and the last rebuilt state is reused as long as the head doesn't change.
+    fn with_accounts_at<T, F: FnOnce(&Accounts, &ReadTransaction) -> T>(
+        &self,
+        block_number: u32,
+        f: F,
+    ) -> Result<T, BlockchainError> {
+        // The head is read within the transaction, since the stored head can be ahead of
+        // the head of the blockchain while a block is being pushed.
+        let read_txn = ReadTransaction::new(&self.env);
+        let head_hash = self
+            .chain_store
+            .get_head(Some(&read_txn))
+            .ok_or(BlockchainError::BlockNotFound)?;
+        let head_number = self
+            .chain_store
+            .get_chain_info(&head_hash, false, Some(&read_txn))?
+            .head
+            .block_number();
+        if block_number > head_number || block_number < Policy::last_macro_block(head_number) {
+            return Err(BlockchainError::StateNotAvailable(block_number));
+        }
+
+        let mut historic_accounts = self.historic_accounts.lock();
+        let is_cached = historic_accounts.as_ref().map_or(false, |historic| {
+            historic.head_hash == head_hash && historic.block_number == block_number
+        });
+        if !is_cached {
+            // Free the previous state before rebuilding another one.
+            *historic_accounts = None;
+            let accounts = self.rebuild_accounts_at(&read_txn, head_number, block_number)?;
+            *historic_accounts = Some(HistoricAccounts {
+                head_hash,
+                block_number,
+                accounts,
+            });
+        }
+        drop(read_txn);
+
+        let accounts = &historic_accounts.as_ref().unwrap().accounts;
+        let txn = ReadTransaction::new(&accounts.env);
+
+        Ok(f(accounts, &txn))
+    }
+
+    /// Copies the accounts tree as seen by `read_txn` into a volatile environment and reverts
+    /// the blocks from `head_number` down to (excluding) `block_number` on that copy.
+    ///
+    /// Only micro blocks are reverted, since macro blocks finalize batches and epochs, which
+    /// can't be reverted. So `block_number` must not be before the last macro block.
+    pub(crate) fn rebuild_accounts_at(
+        &self,
+        read_txn: &ReadTransaction,
+        head_number: u32,
+        block_number: u32,
+    ) -> Result<Accounts, BlockchainError> {
+        if !self.state().accounts.is_complete(Some(read_txn)) {
+            return Err(BlockchainError::AccountsIncomplete);
+        }
+
+        let env = VolatileEnvironment::new(1).map_err(|e| {
+            error!(error = ?e, "Failed to create environment to rebuild historic state");
+            BlockchainError::StateNotAvailable(block_number)
+        })?;
+        let accounts = Accounts::new(env.clone());
+        let mut txn = WriteTransaction::new(&env);
+        txn.copy_database(
+            accounts.tree.database(),
+            read_txn,
+            self.state().accounts.tree.database(),
+        );
+
+        let mut block_logger = BlockLogger::empty();
+        for revert_number in ((block_number + 1)..=head_number).rev() {
+            let block = self
+                .chain_store
+                .get_block_at(revert_number, true, Some(read_txn))?;
+            let micro_block = match block {
+                Block::Micro(ref micro_block) => micro_block,
+                Block::Macro(_) => return Err(BlockchainError::StateNotAvailable(block_number)),
+            };
+            let body = micro_block
+                .body
+                .as_ref()
+                .ok_or(BlockchainError::BlockBodyNotFound)?;
+            let receipts = self
+                .chain_store
+                .get_receipts(revert_number, Some(read_txn))
+                .ok_or(BlockchainError::StateNotAvailable(block_number))?;
+            let block_state = BlockState::new(block.block_number(), block.timestamp());
+
+            let skip_block_info = SkipBlockInfo::from_micro_block(micro_block);
+            let inherents =
+                self.create_slash_inherents(&body.fork_proofs, skip_block_info, Some(read_txn));
+
+            let result = accounts.revert(
+                &mut txn,
+                &body.get_raw_transactions(),
+                &inherents,
+                &block_state,
+                receipts,
+                &mut block_logger,
+            );
+            if let Err(e) = result {
+                error!(block_number = revert_number, error = ?e, "Failed to rebuild historic state");
+                return Err(BlockchainError::InconsistentState);
+            }
+        }
+
+        txn.commit();
+
+        Ok(accounts)
+    }
+
     pub fn get_accounts_proof(&self, keys: Vec<&KeyNibbles>) -> Option<TrieProof> {
         let txn = ReadTransaction::new(&self.env);
 
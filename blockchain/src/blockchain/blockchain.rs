@@ -1,4 +1,7 @@
+use std::collections::HashSet;
 use std::sync::Arc;
+
+use parking_lot::Mutex;
 use tokio::sync::broadcast::{channel as broadcast, Sender as BroadcastSender};
 
 use nimiq_account::{Accounts, BlockLog};
@@ -7,6 +10,7 @@ use nimiq_blockchain_interface::{BlockchainError, BlockchainEvent, ChainInfo, Fo
 use nimiq_database::{Environment, ReadTransaction, WriteTransaction};
 use nimiq_genesis::NetworkInfo;
 use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
 use nimiq_primitives::trie::TrieItem;
 use nimiq_primitives::{coin::Coin, networks::NetworkId, policy::Policy, slots::Validators};
 use nimiq_utils::time::OffsetTime;
@@ -14,8 +18,9 @@ use nimiq_utils::time::OffsetTime;
 #[cfg(feature = "metrics")]
 use crate::chain_metrics::BlockchainMetrics;
 use crate::{
+    balance_history_store::BalanceHistoryStore, blockchain::accounts::HistoricAccounts,
     blockchain_state::BlockchainState, chain_store::ChainStore, history::HistoryStore,
-    reward::genesis_parameters,
+    reward::genesis_parameters, validator_history_store::ValidatorHistoryStore,
 };
 
 const BROADCAST_MAX_CAPACITY: usize = 256;
@@ -41,6 +46,12 @@ pub struct Blockchain {
     pub chain_store: ChainStore,
     /// The history store is a database containing all of the history trees and transactions.
     pub history_store: HistoryStore,
+    /// The balance history store is an optional database containing the balances of all addresses
+    /// over time. It is only present if enabled in the config.
+    pub balance_history_store: Option<BalanceHistoryStore>,
+    /// The validator history store is an optional database containing the blocks produced and
+    /// the punishments received by each validator. It is only present if enabled in the config.
+    pub validator_history_store: Option<ValidatorHistoryStore>,
     /// The current state of the blockchain.
     pub state: BlockchainState,
     /// A reference to a "function" to test whether a given transaction is known and valid.
@@ -52,6 +63,9 @@ pub struct Blockchain {
     pub(crate) genesis_supply: Coin,
     /// The timestamp at the genesis block. This is needed to calculate the rewards.
     pub(crate) genesis_timestamp: u64,
+    /// The accounts rebuilt for the last query of a past state. The lock makes sure that only one
+    /// past state is rebuilt at a time.
+    pub(crate) historic_accounts: Mutex<Option<HistoricAccounts>>,
 }
 
 /// Contains various blockchain configuration knobs
@@ -61,6 +75,19 @@ pub struct BlockchainConfig {
     /// Maximum number of epochs (other than the current one) that the ChainStore will store fully.
     /// Epochs older than this number will be pruned.
     pub max_epochs_stored: u32,
+    /// Maximum number of finished epochs whose full history is kept if `keep_history` is set.
+    /// The history of older epochs is pruned, except for the transactions involving one of the
+    /// `history_retained_addresses`. The history trees are kept, so the history roots of pruned
+    /// epochs stay available. `None` keeps the full history of all epochs.
+    pub history_max_epochs: Option<u32>,
+    /// Addresses whose transactions are never pruned from the history.
+    pub history_retained_addresses: HashSet<Address>,
+    /// Flag indicating if the balances of all addresses should be indexed by block number. This
+    /// requires an additional database.
+    pub index_balance_history: bool,
+    /// Flag indicating if the produced blocks, skip blocks and slashes of all validators should be
+    /// indexed. This requires an additional database.
+    pub index_validator_history: bool,
 }
 
 impl Default for BlockchainConfig {
@@ -68,6 +95,10 @@ impl Default for BlockchainConfig {
         Self {
             keep_history: true,
             max_epochs_stored: Policy::MIN_EPOCHS_STORED,
+            history_max_epochs: None,
+            history_retained_addresses: HashSet::new(),
+            index_balance_history: false,
+            index_validator_history: false,
         }
     }
 }
@@ -116,6 +147,7 @@ impl Blockchain {
                 network_id,
                 genesis_block,
                 head_hash,
+                true,
             )?,
             None => Blockchain::init(
                 env,
@@ -130,6 +162,34 @@ impl Blockchain {
         })
     }
 
+    /// Loads the blockchain stored in the given environment without checking that the accounts
+    /// tree matches the head. This is only meant for repairing a database whose accounts tree is
+    /// behind its head, the returned blockchain must not be used to push blocks.
+    pub(crate) fn load_unverified(
+        env: Environment,
+        config: BlockchainConfig,
+        network_id: NetworkId,
+        time: Arc<OffsetTime>,
+    ) -> Result<Self, BlockchainError> {
+        let chain_store = ChainStore::new(env.clone());
+        let history_store = HistoryStore::new(env.clone());
+        let head_hash = chain_store
+            .get_head(None)
+            .ok_or(BlockchainError::FailedLoadingMainChain)?;
+
+        Blockchain::load(
+            env,
+            config,
+            chain_store,
+            history_store,
+            time,
+            network_id,
+            NetworkInfo::from_network_id(network_id).genesis_block(),
+            head_hash,
+            false,
+        )
+    }
+
     /// Loads a blockchain from given inputs.
     fn load(
         env: Environment,
@@ -140,6 +200,7 @@ impl Blockchain {
         network_id: NetworkId,
         genesis_block: Block,
         head_hash: Blake2bHash,
+        verify_accounts: bool,
     ) -> Result<Self, BlockchainError> {
         // Check that the correct genesis block is stored.
         let genesis_info = chain_store.get_chain_info(&genesis_block.hash(), false, None);
@@ -163,7 +224,7 @@ impl Blockchain {
         let accounts = Accounts::new(env.clone());
 
         // Verify accounts hash if the tree is complete or changes only happened in the complete part.
-        if let Some(accounts_hash) = accounts.get_root_hash(None) {
+        if let Some(accounts_hash) = accounts.get_root_hash(None).filter(|_| verify_accounts) {
             if main_chain.head.state_root() != &accounts_hash {
                 log::error!(
                     "Main chain's head state root: {:?}, Account state root: {:?}",
@@ -236,6 +297,13 @@ impl Blockchain {
             }
         };
 
+        let balance_history_store = config
+            .index_balance_history
+            .then(|| BalanceHistoryStore::new(env.clone()));
+        let validator_history_store = config
+            .index_validator_history
+            .then(|| ValidatorHistoryStore::new(env.clone()));
+
         let (tx, _rx) = broadcast(BROADCAST_MAX_CAPACITY);
         let (tx_fork, _rx_fork) = broadcast(BROADCAST_MAX_CAPACITY);
         let (tx_log, _rx_log) = broadcast(BROADCAST_MAX_CAPACITY);
@@ -250,6 +318,8 @@ impl Blockchain {
             log_notifier: tx_log,
             chain_store,
             history_store,
+            balance_history_store,
+            validator_history_store,
             state: BlockchainState {
                 accounts,
                 main_chain,
@@ -267,6 +337,7 @@ impl Blockchain {
             metrics: Arc::new(BlockchainMetrics::default()),
             genesis_supply,
             genesis_timestamp,
+            historic_accounts: Mutex::new(None),
         })
     }
 
@@ -300,6 +371,13 @@ impl Blockchain {
         chain_store.set_head(&mut txn, &head_hash);
         txn.commit();
 
+        let balance_history_store = config
+            .index_balance_history
+            .then(|| BalanceHistoryStore::new(env.clone()));
+        let validator_history_store = config
+            .index_validator_history
+            .then(|| ValidatorHistoryStore::new(env.clone()));
+
         let (tx, _rx) = broadcast(BROADCAST_MAX_CAPACITY);
         let (tx_fork, _rx_fork) = broadcast(BROADCAST_MAX_CAPACITY);
         let (tx_log, _rx_log) = broadcast(BROADCAST_MAX_CAPACITY);
@@ -314,6 +392,8 @@ impl Blockchain {
             log_notifier: tx_log,
             chain_store,
             history_store,
+            balance_history_store,
+            validator_history_store,
             state: BlockchainState {
                 accounts,
                 macro_info: main_chain.clone(),
@@ -331,6 +411,7 @@ impl Blockchain {
             metrics: Arc::new(BlockchainMetrics::default()),
             genesis_supply,
             genesis_timestamp,
+            historic_accounts: Mutex::new(None),
         })
     }
 
@@ -229,6 +229,14 @@ impl Blockchain {
                 this.metrics.note_invalid_block();
                 return Err(PushError::AccountsError(e));
             }
+
+            this.put_balance_history(
+                &this.state.accounts,
+                &mut txn,
+                block_numbers[i],
+                &block_transactions[i],
+                &block_inherents[i],
+            );
         }
         this.state.accounts.finalize_batch(&mut txn);
 
@@ -286,6 +294,11 @@ impl Blockchain {
             return Err(PushError::InvalidBlock(BlockError::InvalidHistoryRoot));
         }
 
+        // Prune the history according to the retention policy.
+        if macro_block.is_election_block() {
+            this.prune_history(macro_block.block_number(), &mut txn);
+        }
+
         // Give up database transactions and push lock before creating notifications.
         txn.commit();
 
@@ -0,0 +1,478 @@
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use nimiq_account::{Accounts, BlockLogger};
+use nimiq_blockchain_interface::{BlockchainError, ChainInfo, PushError};
+use nimiq_database::{Environment, ReadTransaction, Transaction, WriteTransaction};
+use nimiq_hash::Blake2bHash;
+use nimiq_primitives::{networks::NetworkId, policy::Policy};
+use nimiq_utils::time::OffsetTime;
+
+use crate::{chain_store::ChainStore, history::HistoryStore, Blockchain, BlockchainConfig};
+
+/// An inconsistency between the stores of a blockchain database.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    #[error("Database doesn't contain a chain head")]
+    MissingHead,
+    #[error("Chain info of block {0} is missing")]
+    MissingChainInfo(Blake2bHash),
+    #[error("No main chain block found at #{0}")]
+    MissingMainChainBlock(u32),
+    #[error("Block #{block_number} ({hash}) isn't marked as part of the main chain")]
+    NotOnMainChain {
+        block_number: u32,
+        hash: Blake2bHash,
+    },
+    #[error("Main chain successor of block #{block_number} ({hash}) doesn't point to its child")]
+    BrokenSuccessorLink {
+        block_number: u32,
+        hash: Blake2bHash,
+    },
+    #[error("Height index doesn't resolve block #{block_number} to {hash}")]
+    HeightIndexMismatch {
+        block_number: u32,
+        hash: Blake2bHash,
+    },
+    #[error("Accounts tree root {actual} doesn't match state root {expected} of head block #{block_number}")]
+    AccountsRootMismatch {
+        block_number: u32,
+        expected: Blake2bHash,
+        actual: Blake2bHash,
+    },
+    #[error("History tree root {actual:?} of epoch {epoch_number} doesn't match history root {expected} of block #{block_number}")]
+    HistoryRootMismatch {
+        block_number: u32,
+        epoch_number: u32,
+        expected: Blake2bHash,
+        actual: Option<Blake2bHash>,
+    },
+}
+
+/// The result of checking the integrity of a blockchain database.
+#[derive(Clone, Debug)]
+pub struct IntegrityReport {
+    /// The hash and number of the head block, if the database contains one.
+    pub head: Option<(Blake2bHash, u32)>,
+    /// Whether the accounts tree is complete. The state root can only be checked if it is.
+    pub accounts_complete: bool,
+    /// The inconsistencies that were found.
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    /// Returns true if no inconsistencies were found.
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Errors that can occur while rolling back a database to a consistent state.
+#[derive(Debug, Error)]
+pub enum IntegrityError {
+    #[error("Database doesn't contain a chain head")]
+    MissingHead,
+    #[error("Main chain is broken at block #{0}")]
+    BrokenChain(u32),
+    #[error("No block since macro block #{0} matches the accounts tree")]
+    AccountsUnrecoverable(u32),
+    #[error("Reverting block #{0} didn't result in the state of its parent")]
+    RevertFailed(u32),
+    #[error("History of macro block #{0} can't be recovered")]
+    HistoryUnrecoverable(u32),
+    #[error("Blockchain error: {0}")]
+    Blockchain(#[from] BlockchainError),
+    #[error("Push error: {0}")]
+    Push(#[from] PushError),
+}
+
+/// Implements methods to check the consistency of a blockchain database and to repair it.
+/// These work directly on the stores, since a `Blockchain` can't be loaded from an inconsistent
+/// database.
+impl Blockchain {
+    /// Checks that the chain store, the accounts tree and the history store of the given
+    /// environment are consistent with each other:
+    ///  - the main chain links (`on_main_chain`, `main_chain_successor` and the height index)
+    ///    from the head back to the last election block and along the election blocks,
+    ///  - the accounts tree root against the state root of the head,
+    ///  - the history tree roots against the history roots of the head and the election blocks.
+    ///
+    /// Nodes that don't keep the full history may have an incomplete history tree for the current
+    /// epoch, so the history can be excluded from the check with `verify_history`.
+    pub fn check_integrity(env: &Environment, verify_history: bool) -> IntegrityReport {
+        let chain_store = ChainStore::new(env.clone());
+        let history_store = HistoryStore::new(env.clone());
+        let accounts = Accounts::new(env.clone());
+        let txn = ReadTransaction::new(env);
+
+        let mut report = IntegrityReport {
+            head: None,
+            accounts_complete: accounts.is_complete(Some(&txn)),
+            issues: vec![],
+        };
+
+        let head_hash = match chain_store.get_head(Some(&txn)) {
+            Some(head_hash) => head_hash,
+            None => {
+                report.issues.push(IntegrityIssue::MissingHead);
+                return report;
+            }
+        };
+        let head_info = match chain_store.get_chain_info(&head_hash, false, Some(&txn)) {
+            Ok(head_info) => head_info,
+            Err(_) => {
+                report
+                    .issues
+                    .push(IntegrityIssue::MissingChainInfo(head_hash));
+                return report;
+            }
+        };
+        let head_number = head_info.head.block_number();
+        report.head = Some((head_hash.clone(), head_number));
+
+        // Check the accounts tree against the head.
+        if let Some(accounts_hash) = accounts.get_root_hash(Some(&txn)) {
+            if head_info.head.state_root() != &accounts_hash {
+                report.issues.push(IntegrityIssue::AccountsRootMismatch {
+                    block_number: head_number,
+                    expected: head_info.head.state_root().clone(),
+                    actual: accounts_hash,
+                });
+            }
+        }
+
+        // Check the history tree against the head. The genesis block has no history.
+        if verify_history && head_number > 0 {
+            Self::check_history_root(&history_store, &head_info, &txn, &mut report.issues);
+        }
+
+        // Check the main chain links back to the last election block.
+        let election_number = Policy::last_election_block(head_number);
+        let mut current = (head_hash, head_info);
+        loop {
+            Self::check_main_chain_entry(&chain_store, &current, &txn, &mut report.issues);
+            let block_number = current.1.head.block_number();
+            if block_number <= election_number {
+                break;
+            }
+
+            // Nodes that synced the history only store the macro blocks of past batches, so a
+            // missing predecessor of a macro block is looked up at the previous macro block.
+            let parent_hash = current.1.head.parent_hash().clone();
+            let parent = match chain_store.get_chain_info(&parent_hash, false, Some(&txn)) {
+                Ok(parent_info) => (parent_hash, parent_info),
+                Err(_) if current.1.head.is_macro() => {
+                    let parent_number = Policy::macro_block_before(block_number);
+                    match chain_store.get_chain_info_at(parent_number, false, Some(&txn)) {
+                        Ok(parent_info) => (parent_info.head.hash(), parent_info),
+                        Err(_) => {
+                            report
+                                .issues
+                                .push(IntegrityIssue::MissingMainChainBlock(parent_number));
+                            break;
+                        }
+                    }
+                }
+                Err(_) => {
+                    report
+                        .issues
+                        .push(IntegrityIssue::MissingChainInfo(parent_hash));
+                    break;
+                }
+            };
+
+            if parent.1.main_chain_successor.as_ref() != Some(&current.0) {
+                report.issues.push(IntegrityIssue::BrokenSuccessorLink {
+                    block_number: parent.1.head.block_number(),
+                    hash: parent.0.clone(),
+                });
+            }
+            current = parent;
+        }
+
+        // Check the election blocks back to genesis.
+        let mut election_info = if current.1.head.block_number() == election_number {
+            current.1
+        } else {
+            match chain_store.get_chain_info_at(election_number, false, Some(&txn)) {
+                Ok(election_info) => election_info,
+                Err(_) => {
+                    report
+                        .issues
+                        .push(IntegrityIssue::MissingMainChainBlock(election_number));
+                    return report;
+                }
+            }
+        };
+        while election_info.head.block_number() > 0 {
+            if verify_history && election_info.head.block_number() != head_number {
+                Self::check_history_root(&history_store, &election_info, &txn, &mut report.issues);
+            }
+
+            let parent_election_hash = election_info
+                .head
+                .parent_election_hash()
+                .expect("Election blocks have a parent election hash")
+                .clone();
+            election_info =
+                match chain_store.get_chain_info(&parent_election_hash, false, Some(&txn)) {
+                    Ok(parent_info) => parent_info,
+                    Err(_) => {
+                        report
+                            .issues
+                            .push(IntegrityIssue::MissingChainInfo(parent_election_hash));
+                        break;
+                    }
+                };
+            Self::check_main_chain_entry(
+                &chain_store,
+                &(parent_election_hash, election_info.clone()),
+                &txn,
+                &mut report.issues,
+            );
+        }
+
+        report
+    }
+
+    /// Checks that the given block is marked as part of the main chain and that the height index
+    /// resolves its block number to it.
+    fn check_main_chain_entry(
+        chain_store: &ChainStore,
+        (hash, chain_info): &(Blake2bHash, ChainInfo),
+        txn: &Transaction,
+        issues: &mut Vec<IntegrityIssue>,
+    ) {
+        let block_number = chain_info.head.block_number();
+        if !chain_info.on_main_chain {
+            issues.push(IntegrityIssue::NotOnMainChain {
+                block_number,
+                hash: hash.clone(),
+            });
+        }
+
+        let indexed_hash = chain_store
+            .get_chain_info_at(block_number, false, Some(txn))
+            .ok()
+            .map(|info| info.head.hash());
+        if indexed_hash.as_ref() != Some(hash) {
+            issues.push(IntegrityIssue::HeightIndexMismatch {
+                block_number,
+                hash: hash.clone(),
+            });
+        }
+    }
+
+    /// Checks the history tree of the block's epoch against the block's history root. Past epochs
+    /// whose history isn't stored are skipped.
+    fn check_history_root(
+        history_store: &HistoryStore,
+        chain_info: &ChainInfo,
+        txn: &Transaction,
+        issues: &mut Vec<IntegrityIssue>,
+    ) {
+        let block = &chain_info.head;
+        let epoch_number = block.epoch_number();
+        let history_root = history_store.get_history_tree_root(epoch_number, Some(txn));
+        if history_root.is_none() && block.is_election() {
+            return;
+        }
+
+        if history_root.as_ref() != Some(block.history_root()) {
+            issues.push(IntegrityIssue::HistoryRootMismatch {
+                block_number: block.block_number(),
+                epoch_number,
+                expected: block.history_root().clone(),
+                actual: history_root,
+            });
+        }
+    }
+
+    /// Rolls the database back to the last macro block at or before the current head and returns
+    /// its block number. Macro blocks are final, so the chain can't be rolled back any further.
+    ///
+    /// First, the head is moved back to the most recent block whose state root matches the
+    /// accounts tree, dropping any history beyond it. From there, the remaining micro blocks are
+    /// reverted using their receipts. The history root of the macro block is verified afterwards
+    /// if `config.keep_history` is set. All changes are made within one transaction, so the
+    /// database is left untouched if the rollback fails.
+    pub fn rollback_to_consistent_state(
+        env: Environment,
+        config: BlockchainConfig,
+        network_id: NetworkId,
+        time: Arc<OffsetTime>,
+    ) -> Result<u32, IntegrityError> {
+        let chain_store = ChainStore::new(env.clone());
+        let history_store = HistoryStore::new(env.clone());
+        let accounts = Accounts::new(env.clone());
+        let verify_history = config.keep_history;
+
+        let mut main_chain = Self::collect_main_chain_to_macro(&chain_store, None)?;
+        let macro_number = main_chain.last().unwrap().1.head.block_number();
+
+        // Find the most recent block that matches the accounts tree. If the tree is incomplete,
+        // its root can't be checked and we assume that it matches the head.
+        let consistent_index = match accounts.get_root_hash(None) {
+            Some(accounts_hash) => main_chain
+                .iter()
+                .position(|(_, chain_info)| chain_info.head.state_root() == &accounts_hash)
+                .ok_or(IntegrityError::AccountsUnrecoverable(macro_number))?,
+            None => 0,
+        };
+
+        // The blockchain is loaded without verifying the accounts tree, since the head doesn't
+        // necessarily match it yet. It is only used to revert blocks.
+        let blockchain = Blockchain::load_unverified(env.clone(), config, network_id, time)?;
+        let mut txn = WriteTransaction::new(&env);
+
+        // Move the head back to the consistent block, dropping any history beyond it.
+        Self::move_head_back(
+            &chain_store,
+            &history_store,
+            &mut txn,
+            &mut main_chain,
+            consistent_index,
+        );
+
+        // Revert the remaining micro blocks.
+        let macro_index = main_chain.len() - 1;
+        let num_reverted = macro_index - consistent_index;
+        for i in consistent_index..macro_index {
+            let (_, chain_info) = &main_chain[i];
+            if let Some(ref prev_missing_range) = chain_info.prev_missing_range {
+                blockchain
+                    .state
+                    .accounts
+                    .revert_chunk(&mut txn, prev_missing_range.start.clone())
+                    .map_err(PushError::AccountsError)?;
+            }
+
+            blockchain.revert_accounts(
+                &blockchain.state.accounts,
+                &mut txn,
+                &chain_info.head,
+                &mut BlockLogger::empty(),
+            )?;
+
+            let prev_info = &main_chain[i + 1].1;
+            if let Some(accounts_hash) = blockchain.state.accounts.get_root_hash(Some(&txn)) {
+                if prev_info.head.state_root() != &accounts_hash {
+                    error!(
+                        block = %chain_info.head,
+                        "Inconsistent state after reverting block"
+                    );
+                    txn.abort();
+                    return Err(IntegrityError::RevertFailed(chain_info.head.block_number()));
+                }
+            }
+        }
+
+        Self::move_head_back(
+            &chain_store,
+            &history_store,
+            &mut txn,
+            &mut main_chain,
+            macro_index,
+        );
+
+        let macro_info = &main_chain[macro_index].1;
+        if verify_history
+            && history_store
+                .get_history_tree_root(macro_info.head.epoch_number(), Some(&txn))
+                .as_ref()
+                != Some(macro_info.head.history_root())
+        {
+            txn.abort();
+            return Err(IntegrityError::HistoryUnrecoverable(macro_number));
+        }
+        txn.commit();
+
+        info!(
+            block_number = macro_number,
+            num_reverted, "Rolled back to macro block"
+        );
+
+        Ok(macro_number)
+    }
+
+    /// Collects the main chain from the stored head down to the last macro block, starting with
+    /// the head.
+    pub(crate) fn collect_main_chain_to_macro(
+        chain_store: &ChainStore,
+        txn_option: Option<&Transaction>,
+    ) -> Result<Vec<(Blake2bHash, ChainInfo)>, IntegrityError> {
+        let head_hash = chain_store
+            .get_head(txn_option)
+            .ok_or(IntegrityError::MissingHead)?;
+        let head_info = chain_store
+            .get_chain_info(&head_hash, true, txn_option)
+            .map_err(|_| IntegrityError::MissingHead)?;
+        let macro_number = Policy::last_macro_block(head_info.head.block_number());
+
+        let mut main_chain = vec![(head_hash, head_info)];
+        loop {
+            let (_, chain_info) = main_chain.last().unwrap();
+            let block_number = chain_info.head.block_number();
+            if block_number <= macro_number {
+                break;
+            }
+
+            let parent_hash = chain_info.head.parent_hash().clone();
+            let parent_info = chain_store
+                .get_chain_info(&parent_hash, true, txn_option)
+                .map_err(|_| IntegrityError::BrokenChain(block_number - 1))?;
+            main_chain.push((parent_hash, parent_info));
+        }
+        if !main_chain.last().unwrap().1.head.is_macro() {
+            return Err(IntegrityError::BrokenChain(macro_number));
+        }
+
+        Ok(main_chain)
+    }
+
+    /// Moves the head back to the block at `index` of the main chain collected by
+    /// `collect_main_chain_to_macro`. The blocks after it are removed from the main chain and
+    /// any history beyond it is dropped. The accounts tree isn't touched.
+    pub(crate) fn move_head_back(
+        chain_store: &ChainStore,
+        history_store: &HistoryStore,
+        txn: &mut WriteTransaction,
+        main_chain: &mut [(Blake2bHash, ChainInfo)],
+        index: usize,
+    ) {
+        Self::unset_main_chain(chain_store, txn, &mut main_chain[..index]);
+        let (hash, chain_info) = &mut main_chain[index];
+        chain_info.main_chain_successor = None;
+        chain_store.put_chain_info(txn, hash, chain_info, false);
+        chain_store.set_head(txn, hash);
+
+        let epoch_number = chain_info.head.epoch_number();
+        while history_store.total_len_at_epoch(epoch_number, Some(txn))
+            > chain_info.history_tree_len as usize
+        {
+            if history_store
+                .remove_partial_history(txn, epoch_number, 1)
+                .is_none()
+            {
+                break;
+            }
+        }
+        if chain_info.head.is_election() {
+            history_store.remove_history(txn, epoch_number + 1);
+        }
+    }
+
+    /// Removes the given blocks from the main chain.
+    fn unset_main_chain(
+        chain_store: &ChainStore,
+        txn: &mut WriteTransaction,
+        blocks: &mut [(Blake2bHash, ChainInfo)],
+    ) {
+        for (hash, chain_info) in blocks {
+            chain_info.on_main_chain = false;
+            chain_info.main_chain_successor = None;
+            chain_store.put_chain_info(txn, hash, chain_info, false);
+        }
+    }
+}
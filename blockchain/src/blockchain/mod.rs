@@ -4,8 +4,12 @@ pub mod accounts;
 pub mod blockchain;
 pub mod history_sync;
 pub mod inherents;
+pub mod integrity;
 pub mod push;
+pub mod replay;
 pub mod slots;
+pub mod snapshot;
+pub mod validator_history;
 pub mod verify;
 pub mod wrappers;
 pub mod zkp_sync;
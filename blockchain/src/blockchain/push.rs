@@ -18,7 +18,7 @@ use nimiq_primitives::{
 };
 use nimiq_vrf::VrfSeed;
 
-use crate::{blockchain_state::BlockchainState, Blockchain};
+use crate::{blockchain_state::BlockchainState, rebranch_log::RebranchRecord, Blockchain};
 
 fn send_vec(log_notifier: &BroadcastSender<BlockLog>, logs: Vec<BlockLog>) {
     for log in logs {
@@ -296,6 +296,8 @@ impl Blockchain {
                 // Prune the History Store.
                 this.history_store
                     .remove_history(&mut txn, Policy::epoch_at(block_number).saturating_sub(1));
+            } else {
+                this.prune_history(block_number, &mut txn);
             }
         }
 
@@ -550,6 +552,14 @@ impl Blockchain {
                 .put_chain_info(&mut write_txn, &fork_block.0, &fork_block.1, i == 0);
         }
 
+        // Record the rebranch in the rebranch log.
+        let rebranch_record =
+            RebranchRecord::new(this.time.now(), &ancestor, &revert_chain, &fork_chain);
+        #[cfg(feature = "metrics")]
+        let rebranch_reason = rebranch_record.reason;
+        this.chain_store
+            .put_rebranch_record(&mut write_txn, rebranch_record);
+
         // Commit transaction & update head.
         let new_head_hash = &fork_chain[0].0;
         let new_head_info = &fork_chain[0].1;
@@ -613,7 +623,7 @@ impl Blockchain {
         );
         #[cfg(feature = "metrics")]
         this.metrics
-            .note_rebranch(&reverted_blocks, &adopted_blocks);
+            .note_rebranch(&reverted_blocks, &adopted_blocks, rebranch_reason);
 
         // We do not log errors if there are no listeners
         this.notifier
@@ -669,6 +679,32 @@ impl Blockchain {
         Ok(total_tx_size)
     }
 
+    /// Prunes the history of the epochs that fall out of the configured history retention once
+    /// the election block at the given height is pushed. Epochs that were pruned before are
+    /// skipped.
+    pub(crate) fn prune_history(&self, block_number: u32, txn: &mut WriteTransaction) {
+        let max_epochs = match self.config.history_max_epochs {
+            Some(max_epochs) => max_epochs,
+            None => return,
+        };
+
+        // The epoch of the given election block is finished as well.
+        let last_epoch = match Policy::epoch_at(block_number).checked_sub(max_epochs) {
+            Some(last_epoch) => last_epoch,
+            None => return,
+        };
+        let first_epoch = self.history_store.get_last_pruned_epoch(Some(txn)) + 1;
+
+        for epoch_number in first_epoch..=last_epoch {
+            let num_pruned = self.history_store.prune_history(
+                txn,
+                epoch_number,
+                &self.config.history_retained_addresses,
+            );
+            debug!(epoch_number, num_pruned, "Pruned history");
+        }
+    }
+
     fn detect_forks(&self, txn: &ReadTransaction, block: &MicroBlock, prev_vrf_seed: &VrfSeed) {
         assert!(!block.is_skip_block());
 
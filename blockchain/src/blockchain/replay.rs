@@ -0,0 +1,421 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use thiserror::Error;
+
+use nimiq_account::BlockLogger;
+use nimiq_block::{Block, BlockError};
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, PushError, PushResult};
+use nimiq_database::{Environment, ReadTransaction};
+use nimiq_hash::Blake2bHash;
+use nimiq_primitives::{networks::NetworkId, policy::Policy};
+use nimiq_utils::time::OffsetTime;
+
+use crate::{chain_store::ChainStore, history::HistoryStore, Blockchain, BlockchainConfig};
+
+/// The first difference found between a replayed block and the original execution.
+#[derive(Debug, Error)]
+pub enum ReplayDivergence {
+    #[error("Block #{block_number} ({hash}) was rejected: {error}")]
+    BlockRejected {
+        block_number: u32,
+        hash: Blake2bHash,
+        error: PushError,
+    },
+    #[error("Block #{block_number} ({hash}) wasn't adopted: {result:?}")]
+    BlockNotAdopted {
+        block_number: u32,
+        hash: Blake2bHash,
+        result: PushResult,
+    },
+    #[error(
+        "State root {actual:?} of block #{block_number} doesn't match the original {expected}"
+    )]
+    StateRootMismatch {
+        block_number: u32,
+        expected: Blake2bHash,
+        actual: Option<Blake2bHash>,
+    },
+    #[error(
+        "History root {actual:?} of block #{block_number} doesn't match the original {expected}"
+    )]
+    HistoryRootMismatch {
+        block_number: u32,
+        expected: Blake2bHash,
+        actual: Option<Blake2bHash>,
+    },
+    #[error("Receipts of block #{block_number} ({hash}) don't match the original ones")]
+    ReceiptsMismatch {
+        block_number: u32,
+        hash: Blake2bHash,
+    },
+}
+
+/// The result of replaying a chain.
+#[derive(Debug)]
+pub struct ReplayReport {
+    /// The block number the replay started from.
+    pub start_block: u32,
+    /// The number of the last block that was replayed without divergence.
+    pub last_block: u32,
+    /// The first divergence found, if any. The replay stops at it.
+    pub divergence: Option<ReplayDivergence>,
+}
+
+impl ReplayReport {
+    /// Returns true if all replayed blocks matched the original execution.
+    pub fn is_consistent(&self) -> bool {
+        self.divergence.is_none()
+    }
+}
+
+/// Errors that prevent a chain from being replayed.
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Source database doesn't contain a chain head")]
+    MissingHead,
+    #[error("Source database doesn't contain the full history")]
+    MissingHistory,
+    #[error("Block #{0} isn't stored in the source database")]
+    MissingBlock(u32),
+    #[error("Head of the target database #{0} ({1}) isn't part of the source main chain")]
+    UnknownStart(u32, Blake2bHash),
+    #[error("Blockchain error: {0}")]
+    Blockchain(#[from] BlockchainError),
+}
+
+/// The roots computed when executing a block on top of the current head.
+struct ExecutionResult {
+    state_root: Option<Blake2bHash>,
+    history_root: Option<Blake2bHash>,
+}
+
+/// Implements methods to re-execute the chain of a database, to check that the current code
+/// computes the same state as the one that produced it.
+impl Blockchain {
+    /// Re-executes the main chain of the source environment on the blockchain of the target
+    /// environment, and compares the state root and the history root of every block with the
+    /// original ones. The source must be a history database, and it isn't modified.
+    ///
+    /// Blocks stored in the source are replayed one by one, and their receipts are compared as
+    /// well. The micro blocks of epochs older than `max_epochs_stored` are pruned from the
+    /// source though. Those are replayed up to the next stored macro block at once, by applying
+    /// the extended transactions of the source history like history sync does. This requires the
+    /// history of these epochs not to be pruned.
+    ///
+    /// The replay starts at the head of the target, which must be part of the source main chain.
+    /// A fresh target starts at genesis. To replay from a macro block, use a target database
+    /// that was rolled back to it (see `rollback_to_consistent_state`) or that was replayed up to
+    /// it with `until`.
+    ///
+    /// The replay stops at the first divergence, at block `until` or at the source head.
+    pub fn replay(
+        source_env: &Environment,
+        target_env: Environment,
+        config: BlockchainConfig,
+        network_id: NetworkId,
+        time: Arc<OffsetTime>,
+        until: Option<u32>,
+    ) -> Result<ReplayReport, ReplayError> {
+        let source_chain_store = ChainStore::new(source_env.clone());
+        let source_history_store = HistoryStore::new(source_env.clone());
+        let source_txn = ReadTransaction::new(source_env);
+
+        let source_head_hash = source_chain_store
+            .get_head(Some(&source_txn))
+            .ok_or(ReplayError::MissingHead)?;
+        let source_head = source_chain_store
+            .get_block(&source_head_hash, false, Some(&source_txn))
+            .map_err(|_| ReplayError::MissingHead)?;
+
+        let target = RwLock::new(Blockchain::new(target_env, config, network_id, time)?);
+
+        let start_block = target.read().block_number();
+        let start_hash = target.read().head_hash();
+        let on_source_main_chain = source_chain_store
+            .get_chain_info(&start_hash, false, Some(&source_txn))
+            .map_or(false, |chain_info| chain_info.on_main_chain);
+        if !on_source_main_chain {
+            return Err(ReplayError::UnknownStart(start_block, start_hash));
+        }
+
+        let end_block = until.map_or(source_head.block_number(), |until| {
+            until.min(source_head.block_number())
+        });
+        info!(start_block, end_block, "Replaying chain");
+
+        let mut report = ReplayReport {
+            start_block,
+            last_block: start_block,
+            divergence: None,
+        };
+
+        while report.last_block < end_block {
+            let next_block =
+                source_chain_store.get_block_at(report.last_block + 1, true, Some(&source_txn));
+
+            let (block_number, divergence) = match next_block {
+                Ok(block) => (
+                    block.block_number(),
+                    Self::replay_block(
+                        &target,
+                        &source_chain_store,
+                        &source_history_store,
+                        &source_txn,
+                        block,
+                    ),
+                ),
+                Err(_) => {
+                    // The blocks of pruned epochs aren't stored anymore, except for some macro
+                    // blocks. Replay everything up to the next stored one at once.
+                    let block = Self::next_stored_macro_block(
+                        &source_chain_store,
+                        &source_txn,
+                        report.last_block,
+                        end_block,
+                    )
+                    .ok_or(ReplayError::MissingBlock(report.last_block + 1))?;
+
+                    (
+                        block.block_number(),
+                        Self::replay_batches(&target, &source_history_store, &source_txn, block)?,
+                    )
+                }
+            };
+
+            report.divergence = divergence;
+            if report.divergence.is_some() {
+                break;
+            }
+            report.last_block = block_number;
+
+            if Policy::is_macro_block_at(block_number) {
+                info!(block_number, "Replayed batch");
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Pushes the given block onto the target blockchain and compares the result with the
+    /// original execution. Returns the divergence, if any.
+    fn replay_block(
+        target: &RwLock<Blockchain>,
+        source_chain_store: &ChainStore,
+        source_history_store: &HistoryStore,
+        source_txn: &ReadTransaction,
+        block: Block,
+    ) -> Option<ReplayDivergence> {
+        let block_number = block.block_number();
+        let hash = block.hash();
+
+        // The state root and history root are verified while pushing. If they don't match,
+        // execute the block again without committing, to report the roots we computed.
+        match Blockchain::push(target.upgradable_read(), block.clone()) {
+            Ok(PushResult::Extended) => {}
+            Ok(result) => {
+                return Some(ReplayDivergence::BlockNotAdopted {
+                    block_number,
+                    hash,
+                    result,
+                })
+            }
+            Err(PushError::InvalidBlock(BlockError::AccountsHashMismatch)) => {
+                return Some(ReplayDivergence::StateRootMismatch {
+                    block_number,
+                    expected: block.state_root().clone(),
+                    actual: target.read().execute_block(&block).state_root,
+                })
+            }
+            Err(PushError::InvalidBlock(BlockError::InvalidHistoryRoot)) => {
+                return Some(ReplayDivergence::HistoryRootMismatch {
+                    block_number,
+                    expected: block.history_root().clone(),
+                    actual: target.read().execute_block(&block).history_root,
+                })
+            }
+            Err(PushError::InvalidBlock(BlockError::TransactionExecutionMismatch)) => {
+                return Some(ReplayDivergence::ReceiptsMismatch { block_number, hash })
+            }
+            Err(error) => {
+                return Some(ReplayDivergence::BlockRejected {
+                    block_number,
+                    hash,
+                    error,
+                })
+            }
+        }
+
+        if let Some(divergence) = Self::compare_roots(target, &block) {
+            return Some(divergence);
+        }
+
+        let target = target.read();
+        let txn = target.read_transaction();
+
+        // The source only keeps the receipts of the latest batches, while the executed
+        // transactions are part of the history.
+        let receipts_match = source_chain_store
+            .get_receipts(block_number, Some(source_txn))
+            .map_or(true, |receipts| {
+                target.chain_store.get_receipts(block_number, Some(&txn)) == Some(receipts)
+            });
+        // The history of pruned epochs only contains the retained transactions.
+        let ext_txs_match = block.epoch_number()
+            <= source_history_store.get_last_pruned_epoch(Some(source_txn))
+            || source_history_store.get_block_transactions(block_number, Some(source_txn))
+                == target
+                    .history_store
+                    .get_block_transactions(block_number, Some(&txn));
+        if !receipts_match || !ext_txs_match {
+            return Some(ReplayDivergence::ReceiptsMismatch { block_number, hash });
+        }
+
+        None
+    }
+
+    /// Pushes the given macro block onto the target blockchain together with the extended
+    /// transactions the source history contains up to it, and compares the result with the
+    /// original execution. Returns the divergence, if any.
+    fn replay_batches(
+        target: &RwLock<Blockchain>,
+        source_history_store: &HistoryStore,
+        source_txn: &ReadTransaction,
+        block: Block,
+    ) -> Result<Option<ReplayDivergence>, ReplayError> {
+        let block_number = block.block_number();
+        let epoch_number = block.epoch_number();
+        let hash = block.hash();
+
+        if epoch_number <= source_history_store.get_last_pruned_epoch(Some(source_txn))
+            || source_history_store
+                .get_history_tree_root(epoch_number, Some(source_txn))
+                .is_none()
+        {
+            return Err(ReplayError::MissingHistory);
+        }
+
+        // The history of the epoch may already contain blocks after the given one.
+        let history: Vec<_> = source_history_store
+            .get_epoch_transactions(epoch_number, Some(source_txn))
+            .into_iter()
+            .filter(|ext_tx| ext_tx.block_number <= block_number)
+            .collect();
+
+        // The roots computed by history sync aren't available if they don't match.
+        match Blockchain::push_history_sync(target.upgradable_read(), block.clone(), &history) {
+            Ok(PushResult::Extended) => {}
+            Ok(result) => {
+                return Ok(Some(ReplayDivergence::BlockNotAdopted {
+                    block_number,
+                    hash,
+                    result,
+                }))
+            }
+            Err(PushError::InvalidBlock(BlockError::AccountsHashMismatch)) => {
+                return Ok(Some(ReplayDivergence::StateRootMismatch {
+                    block_number,
+                    expected: block.state_root().clone(),
+                    actual: None,
+                }))
+            }
+            Err(PushError::InvalidBlock(BlockError::InvalidHistoryRoot)) => {
+                return Ok(Some(ReplayDivergence::HistoryRootMismatch {
+                    block_number,
+                    expected: block.history_root().clone(),
+                    actual: None,
+                }))
+            }
+            Err(error) => {
+                return Ok(Some(ReplayDivergence::BlockRejected {
+                    block_number,
+                    hash,
+                    error,
+                }))
+            }
+        }
+
+        Ok(Self::compare_roots(target, &block))
+    }
+
+    /// Compares the state root and the history root of the target blockchain with the ones of
+    /// the given block, which must be its head. Returns the divergence, if any.
+    fn compare_roots(target: &RwLock<Blockchain>, block: &Block) -> Option<ReplayDivergence> {
+        let block_number = block.block_number();
+        let target = target.read();
+        let txn = target.read_transaction();
+
+        // The accounts tree may be incomplete, in which case push couldn't verify the state root.
+        let state_root = target.state.accounts.get_root_hash(Some(&txn));
+        if state_root.as_ref() != Some(block.state_root()) {
+            return Some(ReplayDivergence::StateRootMismatch {
+                block_number,
+                expected: block.state_root().clone(),
+                actual: state_root,
+            });
+        }
+
+        let history_root = target
+            .history_store
+            .get_history_tree_root(block.epoch_number(), Some(&txn));
+        if history_root.as_ref() != Some(block.history_root()) {
+            return Some(ReplayDivergence::HistoryRootMismatch {
+                block_number,
+                expected: block.history_root().clone(),
+                actual: history_root,
+            });
+        }
+
+        None
+    }
+
+    /// Returns the first macro block after the given block number and up to `end_block` that is
+    /// still stored in the source chain store.
+    fn next_stored_macro_block(
+        source_chain_store: &ChainStore,
+        source_txn: &ReadTransaction,
+        block_number: u32,
+        end_block: u32,
+    ) -> Option<Block> {
+        let mut macro_block_number = Policy::macro_block_after(block_number);
+        while macro_block_number <= end_block {
+            if let Ok(block) =
+                source_chain_store.get_block_at(macro_block_number, true, Some(source_txn))
+            {
+                return Some(block);
+            }
+            macro_block_number += Policy::blocks_per_batch();
+        }
+        None
+    }
+
+    /// Executes the given block on top of the current head without verifying or committing it,
+    /// and returns the resulting roots.
+    fn execute_block(&self, block: &Block) -> ExecutionResult {
+        let mut txn = self.write_transaction();
+
+        let result = match self.commit_accounts(
+            &self.state,
+            block,
+            &mut txn,
+            &mut BlockLogger::empty(),
+        ) {
+            Ok(_) => ExecutionResult {
+                state_root: self.state.accounts.get_root_hash(Some(&txn)),
+                history_root: self
+                    .history_store
+                    .get_history_tree_root(block.epoch_number(), Some(&txn)),
+            },
+            Err(error) => {
+                warn!(%block, error = &error as &dyn std::error::Error, "Failed to execute block");
+                ExecutionResult {
+                    state_root: None,
+                    history_root: None,
+                }
+            }
+        };
+
+        txn.abort();
+        result
+    }
+}
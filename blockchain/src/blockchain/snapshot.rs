@@ -0,0 +1,231 @@
+use std::io::{self, Read, Write};
+
+use beserial::{Deserialize, Serialize, SerializingError};
+use thiserror::Error;
+
+use nimiq_account::Accounts;
+use nimiq_block::Block;
+use nimiq_blockchain_interface::BlockchainError;
+use nimiq_database::{snapshot::write_snapshot, Database, Environment, ReadTransaction};
+use nimiq_hash::Blake2bHash;
+use nimiq_primitives::{networks::NetworkId, policy::Policy};
+
+use crate::{chain_store::ChainStore, history::HistoryStore, Blockchain, IntegrityError};
+
+/// Version of the snapshot format. Snapshots of other versions are rejected.
+const SNAPSHOT_VERSION: u8 = 2;
+
+/// Errors that can occur while exporting or importing a database snapshot.
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] SerializingError),
+    #[error("Unsupported snapshot version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Blockchain error: {0}")]
+    Blockchain(#[from] BlockchainError),
+    #[error("Main chain error: {0}")]
+    MainChain(#[from] IntegrityError),
+    #[error("Snapshot is for network {0}")]
+    WrongNetwork(NetworkId),
+    #[error("Database already contains a blockchain")]
+    DatabaseNotEmpty,
+    #[error("Snapshot doesn't match its manifest: {0}")]
+    ManifestMismatch(&'static str),
+    #[error("Snapshot contains the full history: {snapshot}, but the node needs it: {node}")]
+    HistoryMismatch { snapshot: bool, node: bool },
+}
+
+/// Describes the state contained in a snapshot. It is written in front of the snapshot data and
+/// checked against the imported data.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// The network the snapshot was taken on.
+    pub network_id: NetworkId,
+    /// The number of the macro block the snapshot was taken at.
+    pub block_number: u32,
+    /// The hash of the macro block the snapshot was taken at.
+    pub block_hash: Blake2bHash,
+    /// The accounts tree root after the macro block.
+    pub state_root: Blake2bHash,
+    /// Whether the snapshot contains the full history, i.e. whether it was taken on a node
+    /// that keeps the history and doesn't prune it.
+    pub full_history: bool,
+}
+
+/// Returns the databases that are part of a snapshot, in the order they are stored in it.
+fn snapshot_databases<'a>(
+    chain_store: &'a ChainStore,
+    history_store: &'a HistoryStore,
+    accounts: &'a Accounts,
+) -> Vec<(&'static str, &'a Database)> {
+    let mut databases = chain_store.databases().to_vec();
+    databases.extend(history_store.databases());
+    databases.push((Accounts::TREE_DB_NAME, accounts.tree.database()));
+    databases
+}
+
+/// Implements methods to export and import snapshots of the blockchain databases.
+impl Blockchain {
+    /// Writes a snapshot of the chain store, the history store and the accounts tree at the
+    /// last macro block to `writer`.
+    ///
+    /// All data is read within one read transaction, so blocks can be pushed meanwhile. The
+    /// accounts tree is rebuilt at the macro block by reverting the micro blocks after it on a
+    /// copy. The chain and history stores are written as they are, blocks after the macro
+    /// block are removed from the main chain when the snapshot is imported.
+    pub fn export_snapshot<W: Write>(
+        &self,
+        writer: &mut W,
+    ) -> Result<SnapshotManifest, SnapshotError> {
+        let read_txn = ReadTransaction::new(&self.env);
+        let head_hash = self
+            .chain_store
+            .get_head(Some(&read_txn))
+            .ok_or(BlockchainError::BlockNotFound)?;
+        let head_number = self
+            .chain_store
+            .get_chain_info(&head_hash, false, Some(&read_txn))?
+            .head
+            .block_number();
+        let macro_info = self.chain_store.get_chain_info_at(
+            Policy::last_macro_block(head_number),
+            false,
+            Some(&read_txn),
+        )?;
+
+        let accounts =
+            self.rebuild_accounts_at(&read_txn, head_number, macro_info.head.block_number())?;
+        let accounts_txn = ReadTransaction::new(&accounts.env);
+        let state_root = accounts
+            .get_root_hash(Some(&accounts_txn))
+            .ok_or(BlockchainError::AccountsIncomplete)?;
+        if &state_root != macro_info.head.state_root() {
+            error!(
+                block_number = macro_info.head.block_number(),
+                "Rebuilt accounts tree doesn't match the state root of the macro block"
+            );
+            return Err(BlockchainError::InconsistentState.into());
+        }
+
+        let manifest = SnapshotManifest {
+            network_id: self.network_id,
+            block_number: macro_info.head.block_number(),
+            block_hash: macro_info.head.hash(),
+            state_root,
+            full_history: self.config.keep_history && self.config.history_max_epochs.is_none(),
+        };
+
+        Serialize::serialize(&SNAPSHOT_VERSION, writer)?;
+        Serialize::serialize(&manifest, writer)?;
+
+        let databases: Vec<_> =
+            snapshot_databases(&self.chain_store, &self.history_store, &accounts)
+                .into_iter()
+                .map(|(name, db)| {
+                    let txn = if name == Accounts::TREE_DB_NAME {
+                        &*accounts_txn
+                    } else {
+                        &*read_txn
+                    };
+                    (name, txn, db)
+                })
+                .collect();
+        write_snapshot(&databases, writer)?;
+
+        info!(
+            block_number = manifest.block_number,
+            block_hash = %manifest.block_hash,
+            "Exported database snapshot"
+        );
+
+        Ok(manifest)
+    }
+
+    /// Imports a snapshot written by `export_snapshot` into an empty environment. The imported
+    /// data is only committed if it matches the snapshot's manifest. A blockchain created on the
+    /// environment afterwards continues from the snapshot's macro block.
+    ///
+    /// `full_history` specifies whether the importing node keeps the full history. Snapshots
+    /// that differ in this regard are rejected, since the node couldn't serve the history it
+    /// is expected to, or would keep history it is not expected to.
+    pub fn import_snapshot<R: Read>(
+        env: &Environment,
+        network_id: NetworkId,
+        full_history: bool,
+        reader: &mut R,
+    ) -> Result<SnapshotManifest, SnapshotError> {
+        let chain_store = ChainStore::new(env.clone());
+        let history_store = HistoryStore::new(env.clone());
+        let accounts = Accounts::new(env.clone());
+
+        if chain_store.get_head(None).is_some() {
+            return Err(SnapshotError::DatabaseNotEmpty);
+        }
+
+        let version: u8 = Deserialize::deserialize(reader)?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let manifest: SnapshotManifest = Deserialize::deserialize(reader)?;
+        if manifest.network_id != network_id {
+            return Err(SnapshotError::WrongNetwork(manifest.network_id));
+        }
+        if manifest.full_history != full_history {
+            return Err(SnapshotError::HistoryMismatch {
+                snapshot: manifest.full_history,
+                node: full_history,
+            });
+        }
+
+        let databases = snapshot_databases(&chain_store, &history_store, &accounts);
+        let mut txn = env.import_snapshot(&databases, reader)?;
+
+        // Blocks after the macro block the snapshot was taken at are contained in the chain and
+        // history stores, but not in the accounts tree. Move the head back to the macro block.
+        let mut main_chain = Self::collect_main_chain_to_macro(&chain_store, Some(&txn))?;
+        let macro_index = main_chain.len() - 1;
+        Self::move_head_back(
+            &chain_store,
+            &history_store,
+            &mut txn,
+            &mut main_chain,
+            macro_index,
+        );
+
+        // Check the imported data against the manifest before committing it.
+        if chain_store.get_head(Some(&txn)).as_ref() != Some(&manifest.block_hash) {
+            return Err(SnapshotError::ManifestMismatch("head hash"));
+        }
+
+        let head = chain_store
+            .get_block(&manifest.block_hash, false, Some(&txn))
+            .map_err(|_| SnapshotError::ManifestMismatch("head block missing"))?;
+        let head = match head {
+            Block::Macro(ref block) if block.hash() == manifest.block_hash => block,
+            _ => return Err(SnapshotError::ManifestMismatch("head block")),
+        };
+        if head.header.block_number != manifest.block_number {
+            return Err(SnapshotError::ManifestMismatch("block number"));
+        }
+        if head.header.state_root != manifest.state_root {
+            return Err(SnapshotError::ManifestMismatch("state root of head block"));
+        }
+        if accounts.get_root_hash(Some(&txn)).as_ref() != Some(&manifest.state_root) {
+            return Err(SnapshotError::ManifestMismatch("accounts tree root"));
+        }
+
+        txn.commit();
+
+        info!(
+            block_number = manifest.block_number,
+            block_hash = %manifest.block_hash,
+            "Imported database snapshot"
+        );
+
+        Ok(manifest)
+    }
+}
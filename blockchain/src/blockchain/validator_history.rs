@@ -0,0 +1,177 @@
+use std::collections::BTreeSet;
+
+use nimiq_account::{BlockLogger, Log};
+use nimiq_block::{Block, MicroBlock};
+use nimiq_blockchain_interface::BlockchainError;
+use nimiq_database::{Transaction, WriteTransaction};
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_transaction::inherent::Inherent;
+
+use crate::validator_history_store::{ValidatorEvent, ValidatorHistoryEntry};
+use crate::Blockchain;
+
+/// Implements methods to maintain and query the validator history.
+impl Blockchain {
+    /// Records the given block in the validator history, if it is enabled: the validator that
+    /// produced it or, for skip blocks, the validator it replaced, as well as the slashes and parks
+    /// logged by the staking contract while committing it.
+    pub(crate) fn put_validator_history(
+        &self,
+        txn: &mut WriteTransaction,
+        block: &Block,
+        block_logger: &BlockLogger,
+    ) {
+        let validator_history_store = match &self.validator_history_store {
+            Some(validator_history_store) => validator_history_store,
+            None => return,
+        };
+
+        let block_number = block.block_number();
+        let offset = match block {
+            Block::Macro(macro_block) => macro_block.round(),
+            Block::Micro(_) => block_number,
+        };
+
+        if let Some((validator_address, slot)) =
+            self.get_proposer_of(block_number, offset, block.parent_hash(), txn)
+        {
+            let event = if block.is_skip() {
+                ValidatorEvent::Skipped { slot }
+            } else {
+                ValidatorEvent::Produced { slot }
+            };
+            validator_history_store.put_event(
+                txn,
+                &validator_address,
+                &ValidatorHistoryEntry {
+                    block_number,
+                    event,
+                },
+            );
+        }
+
+        for log in block_logger.inherent_logs() {
+            let (validator_address, event) = match log {
+                Log::Slash {
+                    validator_address,
+                    event_block,
+                    slot,
+                    newly_disabled,
+                } => (
+                    validator_address,
+                    ValidatorEvent::Slashed {
+                        event_block: *event_block,
+                        slot: *slot,
+                        newly_disabled: *newly_disabled,
+                    },
+                ),
+                Log::Park {
+                    validator_address,
+                    event_block,
+                } => (
+                    validator_address,
+                    ValidatorEvent::Parked {
+                        event_block: *event_block,
+                    },
+                ),
+                _ => continue,
+            };
+            validator_history_store.put_event(
+                txn,
+                validator_address,
+                &ValidatorHistoryEntry {
+                    block_number,
+                    event,
+                },
+            );
+        }
+    }
+
+    /// Removes the events recorded for the given micro block from the validator history, if it is
+    /// enabled. Only the proposer of the block and the validators hit by its slash inherents can
+    /// have events at it.
+    pub(crate) fn remove_validator_history(
+        &self,
+        txn: &mut WriteTransaction,
+        block: &MicroBlock,
+        inherents: &[Inherent],
+    ) {
+        let validator_history_store = match &self.validator_history_store {
+            Some(validator_history_store) => validator_history_store,
+            None => return,
+        };
+
+        let block_number = block.block_number();
+        let mut validators = BTreeSet::new();
+        if let Some((validator_address, _)) =
+            self.get_proposer_of(block_number, block_number, &block.header.parent_hash, txn)
+        {
+            validators.insert(validator_address);
+        }
+        for inherent in inherents {
+            if let Inherent::Slash { slot } = inherent {
+                validators.insert(slot.validator_address.clone());
+            }
+        }
+
+        for validator_address in validators {
+            validator_history_store.remove_events(txn, &validator_address, block_number);
+        }
+    }
+
+    /// Returns the address and slot number of the proposer of the block at the given block number
+    /// and offset, whose parent is the given block. Returns `None` if the parent isn't stored,
+    /// which is the case for macro blocks adopted during history sync.
+    fn get_proposer_of(
+        &self,
+        block_number: u32,
+        offset: u32,
+        parent_hash: &Blake2bHash,
+        txn: &Transaction,
+    ) -> Option<(Address, u16)> {
+        let parent = self
+            .chain_store
+            .get_block(parent_hash, false, Some(txn))
+            .ok()?;
+        self.get_proposer_at(block_number, offset, parent.seed().entropy(), Some(txn))
+            .ok()
+            .map(|slot| (slot.validator.address, slot.number))
+    }
+
+    /// Returns the blocks in the given range that were produced by the given validator, as
+    /// recorded in the validator history. Fails if the validator history isn't enabled.
+    pub fn get_blocks_by_producer(
+        &self,
+        validator_address: &Address,
+        from_block: u32,
+        to_block: u32,
+    ) -> Result<Vec<ValidatorHistoryEntry>, BlockchainError> {
+        let validator_history_store = self
+            .validator_history_store
+            .as_ref()
+            .ok_or(BlockchainError::ValidatorHistoryDisabled)?;
+
+        Ok(validator_history_store.get_produced_blocks(
+            validator_address,
+            from_block,
+            to_block,
+            None,
+        ))
+    }
+
+    /// Returns the skip blocks that replaced the given validator and the slashes and parks that
+    /// hit it, as recorded in the validator history. Fails if the validator history isn't
+    /// enabled.
+    pub fn get_validator_punishments(
+        &self,
+        validator_address: &Address,
+    ) -> Result<Vec<ValidatorHistoryEntry>, BlockchainError> {
+        let validator_history_store = self
+            .validator_history_store
+            .as_ref()
+            .ok_or(BlockchainError::ValidatorHistoryDisabled)?;
+
+        Ok(validator_history_store.get_punishments(validator_address, 0, u32::MAX, None))
+    }
+}
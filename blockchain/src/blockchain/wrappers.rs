@@ -15,7 +15,7 @@ use std::ops::RangeFrom;
 
 #[cfg(feature = "metrics")]
 use crate::chain_metrics::BlockchainMetrics;
-use crate::{blockchain_state::BlockchainState, Blockchain};
+use crate::{blockchain_state::BlockchainState, rebranch_log::RebranchRecord, Blockchain};
 
 /// Implements several wrapper functions.
 impl Blockchain {
@@ -65,6 +65,11 @@ impl Blockchain {
             .get_chain_info(hash, include_body, txn_option)
     }
 
+    /// Returns the most recent rebranches recorded in the rebranch log, newest first.
+    pub fn get_recent_rebranches(&self, txn_option: Option<&DBTransaction>) -> Vec<RebranchRecord> {
+        self.chain_store.get_rebranch_records(txn_option)
+    }
+
     pub fn get_slot_owner_at(
         &self,
         block_number: u32,
@@ -8,10 +8,13 @@ use prometheus_client::{
     registry::Registry,
 };
 
+use crate::rebranch_log::RebranchReason;
+
 #[derive(Default)]
 pub struct BlockchainMetrics {
     block_push_counts: Family<PushResultLabels, Counter>,
     transactions_counts: Family<TransactionProcessedLabels, Counter>,
+    rebranch_counts: Family<RebranchLabels, Counter>,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
@@ -41,6 +44,17 @@ enum TransactionProcessed {
     Reverted,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct RebranchLabels {
+    reason: RebranchCause,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelValue)]
+enum RebranchCause {
+    SkipBlock,
+    Fork,
+}
+
 impl BlockchainMetrics {
     pub fn register(&self, registry: &mut Registry) {
         registry.register(
@@ -54,6 +68,12 @@ impl BlockchainMetrics {
             "Count of transactions applied/reverted",
             self.transactions_counts.clone(),
         );
+
+        registry.register(
+            "rebranch_counts",
+            "Count of rebranches by reason",
+            self.rebranch_counts.clone(),
+        );
     }
 
     #[inline]
@@ -101,7 +121,16 @@ impl BlockchainMetrics {
         &self,
         reverted_blocks: &Vec<(Blake2bHash, Block)>,
         adopted_blocks: &Vec<(Blake2bHash, Block)>,
+        reason: RebranchReason,
     ) {
+        let reason = match reason {
+            RebranchReason::SkipBlock => RebranchCause::SkipBlock,
+            RebranchReason::Fork => RebranchCause::Fork,
+        };
+        self.rebranch_counts
+            .get_or_create(&RebranchLabels { reason })
+            .inc();
+
         for (_, micro_block) in reverted_blocks {
             if let Some(Micro(micro_body)) = micro_block.body() {
                 self.transactions_counts
@@ -8,6 +8,8 @@ use nimiq_database::{
 use nimiq_hash::Blake2bHash;
 use nimiq_primitives::policy::Policy;
 
+use crate::rebranch_log::{RebranchLog, RebranchRecord};
+
 #[derive(Debug)]
 pub struct ChainStore {
     env: Environment,
@@ -28,6 +30,7 @@ impl ChainStore {
     const RECEIPT_DB_NAME: &'static str = "Receipts";
 
     const HEAD_KEY: &'static str = "head";
+    const REBRANCH_LOG_KEY: &'static str = "rebranch_log";
 
     pub fn new(env: Environment) -> Self {
         let chain_db = env.open_database(Self::CHAIN_DB_NAME.to_string());
@@ -49,6 +52,16 @@ impl ChainStore {
         }
     }
 
+    /// Returns the databases of the chain store together with their names.
+    pub fn databases(&self) -> [(&'static str, &Database); 4] {
+        [
+            (Self::CHAIN_DB_NAME, &self.chain_db),
+            (Self::BLOCK_DB_NAME, &self.block_db),
+            (Self::HEIGHT_IDX_NAME, &self.height_idx),
+            (Self::RECEIPT_DB_NAME, &self.receipt_db),
+        ]
+    }
+
     pub fn clear(&self, txn: &mut WriteTransaction) {
         txn.clear_database(&self.chain_db);
         txn.clear_database(&self.block_db);
@@ -67,6 +80,29 @@ impl ChainStore {
         txn.put(&self.chain_db, ChainStore::HEAD_KEY, hash);
     }
 
+    /// Appends the given record to the rebranch log. Only the most recent rebranches are kept.
+    pub fn put_rebranch_record(&self, txn: &mut WriteTransaction, record: RebranchRecord) {
+        let mut log: RebranchLog = txn
+            .get(&self.chain_db, ChainStore::REBRANCH_LOG_KEY)
+            .unwrap_or_default();
+        log.push(record);
+        txn.put_reserve(&self.chain_db, ChainStore::REBRANCH_LOG_KEY, &log);
+    }
+
+    /// Returns the most recent rebranches, newest first.
+    pub fn get_rebranch_records(&self, txn_option: Option<&Transaction>) -> Vec<RebranchRecord> {
+        let log: Option<RebranchLog> = match txn_option {
+            Some(txn) => txn.get(&self.chain_db, ChainStore::REBRANCH_LOG_KEY),
+            None => {
+                ReadTransaction::new(&self.env).get(&self.chain_db, ChainStore::REBRANCH_LOG_KEY)
+            }
+        };
+
+        let mut records = log.map(|log| log.records).unwrap_or_default();
+        records.reverse();
+        records
+    }
+
     pub fn get_chain_info(
         &self,
         hash: &Blake2bHash,
@@ -584,6 +620,19 @@ impl ChainStore {
         txn.get(&self.receipt_db, &block_height)
     }
 
+    /// Removes the receipts of all blocks up to and including the given height.
+    pub fn prune_receipts(&self, txn: &mut WriteTransaction, block_height: u32) {
+        let mut cursor = txn.write_cursor(&self.receipt_db);
+        let mut pos: Option<(u32, Receipts)> = cursor.first();
+
+        while let Some((height, _)) = pos {
+            if height <= block_height {
+                cursor.remove();
+            }
+            pos = cursor.next();
+        }
+    }
+
     pub fn clear_receipts(&self, txn: &mut WriteTransaction) {
         let mut cursor = txn.write_cursor(&self.receipt_db);
         let mut pos: Option<(u32, Receipts)> = cursor.first();
@@ -0,0 +1,128 @@
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_primitives::account::AccountType;
+use nimiq_transaction::{
+    extended_transaction::{ExtTxData, ExtendedTransaction},
+    inherent::Inherent,
+};
+
+/// The order in which the transactions of an address are returned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HistoryOrder {
+    /// From the most recent transaction to the least recent one.
+    #[default]
+    NewestFirst,
+    /// From the least recent transaction to the most recent one.
+    OldestFirst,
+}
+
+/// Whether a transaction moves funds to or from the queried address.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransferDirection {
+    /// Both incoming and outgoing transactions.
+    #[default]
+    Any,
+    /// Transactions where the address is the recipient. Reward inherents are incoming.
+    Incoming,
+    /// Transactions where the address is the sender.
+    Outgoing,
+}
+
+/// The kind of an extended transaction, derived from the account types it touches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionKind {
+    /// A transaction between basic accounts.
+    Basic,
+    /// A transaction that sends to or from the staking contract.
+    Staking,
+    /// A transaction that creates or redeems an HTLC.
+    Htlc,
+    /// A transaction that creates or redeems a vesting contract.
+    Vesting,
+    /// A reward inherent.
+    Inherent,
+}
+
+impl TransactionKind {
+    /// Returns the kind of the given extended transaction. Staking takes precedence over the
+    /// other contract types, since staking transactions can be funded from any account.
+    pub fn of(ext_tx: &ExtendedTransaction) -> Self {
+        let tx = match &ext_tx.data {
+            ExtTxData::Basic(tx) => tx.get_raw_transaction(),
+            ExtTxData::Inherent(_) => return TransactionKind::Inherent,
+        };
+
+        let types = [tx.sender_type, tx.recipient_type];
+        if types.contains(&AccountType::Staking) {
+            TransactionKind::Staking
+        } else if types.contains(&AccountType::HTLC) {
+            TransactionKind::Htlc
+        } else if types.contains(&AccountType::Vesting) {
+            TransactionKind::Vesting
+        } else {
+            TransactionKind::Basic
+        }
+    }
+}
+
+/// A filter for the transactions of an address, used to page through the history of busy
+/// addresses.
+#[derive(Clone, Debug, Default)]
+pub struct AddressHistoryFilter {
+    /// Only return transactions that come after the transaction with the given hash, in the
+    /// requested order. This is the cursor for the next page.
+    pub start_after: Option<Blake2bHash>,
+    /// The order in which the transactions are returned.
+    pub order: HistoryOrder,
+    /// Only return transactions included at or after this block.
+    pub min_block: Option<u32>,
+    /// Only return transactions included at or before this block.
+    pub max_block: Option<u32>,
+    /// Only return incoming or outgoing transactions.
+    pub direction: TransferDirection,
+    /// Only return transactions of these kinds. All kinds are returned if empty.
+    pub kinds: Vec<TransactionKind>,
+}
+
+impl AddressHistoryFilter {
+    /// Returns true if the given block number is within the requested block range.
+    pub fn contains_block(&self, block_number: u32) -> bool {
+        self.min_block.map_or(true, |min| block_number >= min)
+            && self.max_block.map_or(true, |max| block_number <= max)
+    }
+
+    /// Returns true if no transaction at the given block number or beyond it (in the requested
+    /// order) can be within the requested block range.
+    pub fn is_past_range(&self, block_number: u32) -> bool {
+        match self.order {
+            HistoryOrder::NewestFirst => self.min_block.map_or(false, |min| block_number < min),
+            HistoryOrder::OldestFirst => self.max_block.map_or(false, |max| block_number > max),
+        }
+    }
+
+    /// Returns true if the given extended transaction of the given address passes the filter.
+    pub fn matches(&self, address: &Address, ext_tx: &ExtendedTransaction) -> bool {
+        if !self.contains_block(ext_tx.block_number) {
+            return false;
+        }
+
+        if !self.kinds.is_empty() && !self.kinds.contains(&TransactionKind::of(ext_tx)) {
+            return false;
+        }
+
+        let (incoming, outgoing) = match &ext_tx.data {
+            ExtTxData::Basic(tx) => {
+                let tx = tx.get_raw_transaction();
+                (&tx.recipient == address, &tx.sender == address)
+            }
+            ExtTxData::Inherent(Inherent::Reward { target, .. }) => (target == address, false),
+            ExtTxData::Inherent(_) => (false, false),
+        };
+
+        match self.direction {
+            TransferDirection::Any => incoming || outgoing,
+            TransferDirection::Incoming => incoming,
+            TransferDirection::Outgoing => outgoing,
+        }
+    }
+}
@@ -1,10 +1,10 @@
 use std::cmp;
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use beserial::Serialize;
 use nimiq_database::cursor::{ReadCursor, WriteCursor};
 use nimiq_database::{
-    Database, DatabaseFlags, Environment, ReadTransaction, Transaction, WriteTransaction,
+    Cursor, Database, DatabaseFlags, Environment, ReadTransaction, Transaction, WriteTransaction,
 };
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
@@ -22,7 +22,18 @@ use nimiq_transaction::{
     inherent::Inherent,
 };
 
-use crate::history::{mmr_store::MMRStore, ordered_hash::OrderedHash, HistoryTreeChunk};
+use crate::history::{
+    mmr_store::MMRStore, ordered_hash::OrderedHash, AddressHistoryFilter, HistoryOrder,
+    HistoryTreeChunk,
+};
+
+/// The maximum number of entries of the address database that are scanned for one call of
+/// `get_ext_txs_by_address`.
+const MAX_SCANNED_ADDRESS_ENTRIES: usize = 10_000;
+
+/// The maximum number of transactions returned by one call of `get_ext_txs_by_address`. Larger
+/// maximums requested by the caller are capped to this.
+pub const MAX_ADDRESS_HISTORY_TXS: u16 = 1_000;
 
 /// A struct that contains databases to store history trees (which are Merkle Mountain Ranges
 /// constructed from the list of extended transactions in an epoch) and extended transactions (which
@@ -45,6 +56,8 @@ pub struct HistoryStore {
     // A database of all transaction (and reward inherent) hashes indexed by their sender and
     // recipient addresses.
     address_db: Database,
+    // A database of metadata about the history store itself, e.g. the last pruned epoch.
+    meta_db: Database,
 }
 
 impl HistoryStore {
@@ -53,6 +66,9 @@ impl HistoryStore {
     const TX_HASH_DB_NAME: &'static str = "LeafHashesByTxHash";
     const LAST_LEAF_DB_NAME: &'static str = "LastLeafIndexesByBlock";
     const ADDRESS_DB_NAME: &'static str = "TxHashesByAddress";
+    const META_DB_NAME: &'static str = "HistoryMetadata";
+
+    const LAST_PRUNED_EPOCH_KEY: &'static str = "last_pruned_epoch";
 
     /// Creates a new HistoryStore.
     pub fn new(env: Environment) -> Self {
@@ -67,6 +83,7 @@ impl HistoryStore {
             Self::ADDRESS_DB_NAME.to_string(),
             DatabaseFlags::DUPLICATE_KEYS | DatabaseFlags::DUP_FIXED_SIZE_VALUES,
         );
+        let meta_db = env.open_database(Self::META_DB_NAME.to_string());
 
         HistoryStore {
             env,
@@ -75,15 +92,29 @@ impl HistoryStore {
             tx_hash_db,
             last_leaf_db,
             address_db,
+            meta_db,
         }
     }
 
+    /// Returns the databases of the history store together with their names.
+    pub fn databases(&self) -> [(&'static str, &Database); 6] {
+        [
+            (Self::HIST_TREE_DB_NAME, &self.hist_tree_db),
+            (Self::EXT_TX_DB_NAME, &self.ext_tx_db),
+            (Self::TX_HASH_DB_NAME, &self.tx_hash_db),
+            (Self::LAST_LEAF_DB_NAME, &self.last_leaf_db),
+            (Self::ADDRESS_DB_NAME, &self.address_db),
+            (Self::META_DB_NAME, &self.meta_db),
+        ]
+    }
+
     pub fn clear(&self, txn: &mut WriteTransaction) {
         txn.clear_database(&self.hist_tree_db);
         txn.clear_database(&self.ext_tx_db);
         txn.clear_database(&self.tx_hash_db);
         txn.clear_database(&self.last_leaf_db);
         txn.clear_database(&self.address_db);
+        txn.clear_database(&self.meta_db);
     }
 
     /// Returns the length (i.e. the number of leaves) of the History Tree at a given block height.
@@ -339,6 +370,126 @@ impl HistoryStore {
         Some(())
     }
 
+    /// Prunes the extended transactions of an epoch, except for those involving one of the
+    /// `retained_addresses`. In contrast to `remove_history`, the history tree is kept, such that
+    /// the history root of the epoch stays available and the retained transactions can still be
+    /// proven. Returns the number of pruned extended transactions.
+    pub fn prune_history(
+        &self,
+        txn: &mut WriteTransaction,
+        epoch_number: u32,
+        retained_addresses: &HashSet<Address>,
+    ) -> usize {
+        // Get the leaf hashes of the epoch.
+        let leaves: Vec<Blake2bHash> = {
+            let tree = MerkleMountainRange::new(MMRStore::with_read_transaction(
+                &self.hist_tree_db,
+                txn,
+                epoch_number,
+            ));
+            (0..tree.num_leaves())
+                .map(|i| tree.get_leaf(i).unwrap())
+                .collect()
+        };
+
+        // Remove the extended transactions and remember the affected addresses. A transaction
+        // can be listed twice for the same address if it is both the sender and the recipient.
+        let mut removed_txs: HashMap<Address, HashMap<Blake2bHash, usize>> = HashMap::new();
+        let mut num_pruned = 0;
+
+        for (leaf_index, leaf_hash) in leaves.into_iter().enumerate() {
+            let ext_tx = match self.get_extended_tx(&leaf_hash, Some(txn)) {
+                Some(ext_tx) => ext_tx,
+                None => continue,
+            };
+
+            let addresses = Self::addresses_of(&ext_tx);
+            if addresses
+                .iter()
+                .any(|address| retained_addresses.contains(address))
+            {
+                continue;
+            }
+
+            txn.remove(&self.ext_tx_db, &leaf_hash);
+
+            let tx_hash = ext_tx.tx_hash();
+            txn.remove_item(
+                &self.tx_hash_db,
+                &tx_hash,
+                &OrderedHash {
+                    index: leaf_index as u32,
+                    hash: leaf_hash,
+                },
+            );
+
+            for address in addresses {
+                *removed_txs
+                    .entry(address)
+                    .or_default()
+                    .entry(tx_hash.clone())
+                    .or_default() += 1;
+            }
+            num_pruned += 1;
+        }
+
+        // Prune the address database. The pruned transactions are among the oldest ones of each
+        // address, so we stop as soon as all of them were found.
+        let mut cursor = txn.write_cursor(&self.address_db);
+
+        for (address, mut removed) in removed_txs {
+            if cursor.seek_key::<Address, OrderedHash>(&address).is_none() {
+                continue;
+            }
+            let mut duplicate = cursor.first_duplicate::<OrderedHash>();
+
+            while let Some(v) = duplicate {
+                if let Some(count) = removed.get_mut(&v.hash) {
+                    cursor.remove();
+                    *count -= 1;
+                    if *count == 0 {
+                        removed.remove(&v.hash);
+                    }
+                }
+                if removed.is_empty() {
+                    break;
+                }
+
+                duplicate = cursor
+                    .next_duplicate::<Address, OrderedHash>()
+                    .map(|(_, v)| v);
+            }
+        }
+        drop(cursor);
+
+        if epoch_number > self.get_last_pruned_epoch(Some(txn)) {
+            txn.put(&self.meta_db, Self::LAST_PRUNED_EPOCH_KEY, &epoch_number);
+        }
+
+        num_pruned
+    }
+
+    /// Returns the last epoch whose history was pruned, or zero if no epoch was pruned yet.
+    pub fn get_last_pruned_epoch(&self, txn_option: Option<&Transaction>) -> u32 {
+        let last_pruned_epoch = match txn_option {
+            Some(txn) => txn.get(&self.meta_db, Self::LAST_PRUNED_EPOCH_KEY),
+            None => ReadTransaction::new(&self.env).get(&self.meta_db, Self::LAST_PRUNED_EPOCH_KEY),
+        };
+        last_pruned_epoch.unwrap_or(0)
+    }
+
+    /// Returns the addresses an extended transaction is indexed by in the address database.
+    fn addresses_of(ext_tx: &ExtendedTransaction) -> Vec<Address> {
+        match &ext_tx.data {
+            ExtTxData::Basic(tx) => {
+                let tx = tx.get_raw_transaction();
+                vec![tx.sender.clone(), tx.recipient.clone()]
+            }
+            ExtTxData::Inherent(Inherent::Reward { target, .. }) => vec![target.clone()],
+            ExtTxData::Inherent(_) => vec![],
+        }
+    }
+
     /// Gets the history tree root for a given epoch.
     pub fn get_history_tree_root(
         &self,
@@ -439,8 +590,10 @@ impl HistoryStore {
 
         for i in start..end {
             let leaf_hash = tree.get_leaf(i as usize).unwrap();
-            let ext_tx = self.get_extended_tx(&leaf_hash, Some(txn)).unwrap();
-            ext_txs.push(ext_tx);
+            // Skip transactions that were pruned from the history.
+            if let Some(ext_tx) = self.get_extended_tx(&leaf_hash, Some(txn)) {
+                ext_txs.push(ext_tx);
+            }
         }
 
         ext_txs
@@ -468,12 +621,14 @@ impl HistoryStore {
             epoch_number,
         ));
 
-        // Get each extended transaction from the tree.
+        // Get each extended transaction from the tree, skipping the ones that were pruned.
         let mut ext_txs = vec![];
 
         for i in 0..tree.num_leaves() {
             let leaf_hash = tree.get_leaf(i).unwrap();
-            ext_txs.push(self.get_extended_tx(&leaf_hash, Some(txn)).unwrap());
+            if let Some(ext_tx) = self.get_extended_tx(&leaf_hash, Some(txn)) {
+                ext_txs.push(ext_tx);
+            }
         }
 
         ext_txs
@@ -696,6 +851,170 @@ impl HistoryStore {
         tx_hashes
     }
 
+    /// Returns the extended transactions (and reward inherents) of the given address that pass the
+    /// given filter, up to the maximum number given. The transactions are returned in the order
+    /// requested by the filter, starting after the cursor transaction if one is given. If the
+    /// cursor transaction isn't in the history of the address, no transactions are returned.
+    ///
+    /// At most `MAX_ADDRESS_HISTORY_TXS` transactions are returned, larger maximums are capped.
+    /// The last returned transaction can be used as cursor to continue.
+    ///
+    /// At most `MAX_SCANNED_ADDRESS_ENTRIES` entries of the history of the address are scanned,
+    /// so fewer transactions may be returned if the filter only matches few of them. If the scan
+    /// stops there, the hash of the last scanned transaction is returned as well, which is the
+    /// cursor to continue the scan with. Otherwise, no such cursor is returned.
+    pub fn get_ext_txs_by_address(
+        &self,
+        address: &Address,
+        max: u16,
+        filter: &AddressHistoryFilter,
+        txn_option: Option<&Transaction>,
+    ) -> (Vec<ExtendedTransaction>, Option<Blake2bHash>) {
+        let max = cmp::min(max, MAX_ADDRESS_HISTORY_TXS);
+        if max == 0 {
+            return (vec![], None);
+        }
+
+        let read_txn: ReadTransaction;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = ReadTransaction::new(&self.env);
+                &read_txn
+            }
+        };
+
+        let mut ext_txs = vec![];
+        let mut cursor = txn.cursor(&self.address_db);
+
+        // Seek to the cursor transaction if one is given, otherwise to the first transaction hash
+        // in the requested order. If there's none, stop here.
+        let mut previous_hash: Option<Blake2bHash> = None;
+        let mut entry = match filter.start_after {
+            Some(ref start_after) => {
+                match self.seek_address_entry(&mut cursor, address, start_after, txn) {
+                    Some(_) => {
+                        previous_hash = Some(start_after.clone());
+                        Self::next_address_entry(&mut cursor, filter.order)
+                    }
+                    None => return (ext_txs, None),
+                }
+            }
+            None => {
+                if cursor.seek_key::<Address, OrderedHash>(address).is_none() {
+                    return (ext_txs, None);
+                }
+                match filter.order {
+                    HistoryOrder::NewestFirst => cursor.last_duplicate::<OrderedHash>(),
+                    HistoryOrder::OldestFirst => cursor.first_duplicate::<OrderedHash>(),
+                }
+            }
+        };
+
+        let mut num_scanned = 0;
+        'entries: while let Some(OrderedHash { hash, .. }) = entry {
+            num_scanned += 1;
+            if num_scanned > MAX_SCANNED_ADDRESS_ENTRIES {
+                // All transactions of the last scanned entry were considered, so the scan can be
+                // continued after it.
+                return (ext_txs, previous_hash);
+            }
+
+            if previous_hash.as_ref() != Some(&hash) {
+                // Transactions to self are indexed twice in a row, only return them once. Pruned
+                // transactions are no longer found and skipped.
+                let mut txs_by_hash = self.get_ext_tx_by_hash(&hash, Some(txn));
+                if filter.order == HistoryOrder::NewestFirst {
+                    txs_by_hash.reverse();
+                }
+
+                for ext_tx in txs_by_hash {
+                    if filter.is_past_range(ext_tx.block_number) {
+                        break 'entries;
+                    }
+                    if filter.matches(address, &ext_tx) {
+                        ext_txs.push(ext_tx);
+                        if ext_txs.len() >= max as usize {
+                            break 'entries;
+                        }
+                    }
+                }
+            }
+            previous_hash = Some(hash);
+
+            entry = Self::next_address_entry(&mut cursor, filter.order);
+        }
+
+        (ext_txs, None)
+    }
+
+    /// Moves the cursor to the next transaction hash of the current address in the given order.
+    fn next_address_entry(cursor: &mut Cursor, order: HistoryOrder) -> Option<OrderedHash> {
+        match order {
+            HistoryOrder::NewestFirst => cursor.prev_duplicate::<Address, OrderedHash>(),
+            HistoryOrder::OldestFirst => cursor.next_duplicate::<Address, OrderedHash>(),
+        }
+        .map(|(_, v)| v)
+    }
+
+    /// Moves the cursor to the entry of the given transaction hash in the address database. The
+    /// entries of an address are ordered by the block of their transaction, so the first entry at
+    /// that block is found by a binary search over the indexes of the entries. Returns `None` if
+    /// the transaction isn't in the history of the address.
+    fn seek_address_entry(
+        &self,
+        cursor: &mut Cursor,
+        address: &Address,
+        tx_hash: &Blake2bHash,
+        txn: &Transaction,
+    ) -> Option<OrderedHash> {
+        let block_number_of = |hash: &Blake2bHash| {
+            self.get_ext_tx_by_hash(hash, Some(txn))
+                .first()
+                .map(|ext_tx| ext_tx.block_number)
+        };
+        let entry_at = |cursor: &mut Cursor, index: u32| {
+            cursor.seek_range_value::<Address, OrderedHash>(
+                address,
+                &OrderedHash {
+                    index,
+                    hash: Blake2bHash::default(),
+                },
+            )
+        };
+
+        let block_number = block_number_of(tx_hash)?;
+        let first_index = cursor.seek_key::<Address, OrderedHash>(address)?.index;
+        let last_index = cursor.last_duplicate::<OrderedHash>()?.index;
+
+        // Find the index of the first entry at or after the block of the transaction.
+        let (mut low, mut high) = (first_index, last_index.saturating_add(1));
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let entry = entry_at(cursor, mid)?;
+            if block_number_of(&entry.hash)? < block_number {
+                low = entry.index + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        // The address can have several transactions in that block.
+        let mut entry = entry_at(cursor, low);
+        while let Some(ordered_hash) = entry {
+            if &ordered_hash.hash == tx_hash {
+                return Some(ordered_hash);
+            }
+            if block_number_of(&ordered_hash.hash)? > block_number {
+                return None;
+            }
+            entry = cursor
+                .next_duplicate::<Address, OrderedHash>()
+                .map(|(_, v)| v);
+        }
+        None
+    }
+
     /// Returns a proof for transactions with the given hashes. The proof also includes the extended
     /// transactions.
     /// The verifier state is used for those cases where the verifier might have an incomplete MMR,
@@ -756,7 +1075,8 @@ impl HistoryStore {
 
         for i in &positions {
             let leaf_hash = tree.get_leaf(*i).unwrap();
-            ext_txs.push(self.get_extended_tx(&leaf_hash, Some(txn)).unwrap());
+            // Pruned transactions can't be proven.
+            ext_txs.push(self.get_extended_tx(&leaf_hash, Some(txn))?);
         }
 
         Some(HistoryTreeProof {
@@ -812,7 +1132,8 @@ impl HistoryStore {
 
         for i in start..end {
             let leaf_hash = tree.get_leaf(i).unwrap();
-            ext_txs.push(self.get_extended_tx(&leaf_hash, Some(txn)).unwrap());
+            // The chunks of pruned epochs can't be provided.
+            ext_txs.push(self.get_extended_tx(&leaf_hash, Some(txn))?);
         }
 
         Some(HistoryTreeChunk {
@@ -1105,11 +1426,12 @@ mod tests {
     };
 
     use super::*;
+    use crate::history::{TransactionKind, TransferDirection};
 
     #[test]
     fn length_at_works() {
         // Initialize History Store.
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let history_store = HistoryStore::new(env.clone());
 
         // Create extended transactions.
@@ -1137,7 +1459,7 @@ mod tests {
     #[test]
     fn get_root_from_ext_txs_works() {
         // Initialize History Store.
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let history_store = HistoryStore::new(env.clone());
 
         // Create extended transactions.
@@ -1163,7 +1485,7 @@ mod tests {
     #[test]
     fn get_ext_tx_by_hash_works() {
         // Initialize History Store.
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let history_store = HistoryStore::new(env.clone());
 
         // Create extended transactions.
@@ -1203,7 +1525,7 @@ mod tests {
     #[test]
     fn get_block_transactions_works() {
         // Initialize History Store.
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let history_store = HistoryStore::new(env.clone());
 
         // Create extended transactions.
@@ -1317,7 +1639,7 @@ mod tests {
     #[test]
     fn get_epoch_transactions_works() {
         // Initialize History Store.
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let history_store = HistoryStore::new(env.clone());
 
         // Create extended transactions.
@@ -1436,7 +1758,7 @@ mod tests {
     #[test]
     fn get_num_extended_transactions_works() {
         // Initialize History Store.
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let history_store = HistoryStore::new(env.clone());
 
         // Create extended transactions.
@@ -1464,7 +1786,7 @@ mod tests {
     #[test]
     fn get_tx_hashes_by_address_works() {
         // Initialize History Store.
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let history_store = HistoryStore::new(env.clone());
 
         // Create extended transactions.
@@ -1519,10 +1841,127 @@ mod tests {
         assert_eq!(query_4.len(), 0);
     }
 
+    #[test]
+    fn get_ext_txs_by_address_works() {
+        // Initialize History Store.
+        let env = VolatileEnvironment::new(11).unwrap();
+        let history_store = HistoryStore::new(env.clone());
+
+        // Create extended transactions.
+        let ext_txs = gen_ext_txs();
+
+        // Add extended transactions to History Store.
+        let mut txn = WriteTransaction::new(&env);
+        history_store.add_to_history(&mut txn, 0, &ext_txs[..3]);
+        history_store.add_to_history(&mut txn, 1, &ext_txs[3..]);
+
+        let sender =
+            Address::from_user_friendly_address("NQ09 VF5Y 1PKV MRM4 5LE1 55KV P6R2 GXYJ XYQF")
+                .unwrap();
+        let reward_target =
+            Address::from_user_friendly_address("NQ04 B79B R4FF 4NGU A9H0 2PT9 9ART 5A88 J73T")
+                .unwrap();
+
+        // Page through the history from oldest to newest.
+        let mut filter = AddressHistoryFilter {
+            order: HistoryOrder::OldestFirst,
+            ..Default::default()
+        };
+        let (page_1, scan_cursor) =
+            history_store.get_ext_txs_by_address(&sender, 2, &filter, Some(&txn));
+        assert_eq!(page_1, vec![ext_txs[0].clone(), ext_txs[1].clone()]);
+        // The scan wasn't cut short, so there's no cursor to continue it with.
+        assert_eq!(scan_cursor, None);
+
+        filter.start_after = Some(page_1[1].tx_hash());
+        let (page_2, _) = history_store.get_ext_txs_by_address(&sender, 2, &filter, Some(&txn));
+        assert_eq!(page_2, vec![ext_txs[3].clone(), ext_txs[5].clone()]);
+
+        filter.start_after = Some(page_2[1].tx_hash());
+        let (page_3, _) = history_store.get_ext_txs_by_address(&sender, 2, &filter, Some(&txn));
+        assert_eq!(page_3, vec![ext_txs[6].clone()]);
+
+        // Page from newest to oldest.
+        let filter = AddressHistoryFilter {
+            start_after: Some(ext_txs[5].tx_hash()),
+            ..Default::default()
+        };
+        let (query, _) = history_store.get_ext_txs_by_address(&sender, 99, &filter, Some(&txn));
+        assert_eq!(
+            query,
+            vec![ext_txs[3].clone(), ext_txs[1].clone(), ext_txs[0].clone()]
+        );
+
+        // An unknown cursor returns nothing.
+        let filter = AddressHistoryFilter {
+            start_after: Some(ext_txs[2].tx_hash()),
+            ..Default::default()
+        };
+        assert!(history_store
+            .get_ext_txs_by_address(&sender, 99, &filter, Some(&txn))
+            .0
+            .is_empty());
+
+        // Filter by block range.
+        let filter = AddressHistoryFilter {
+            min_block: Some(1),
+            max_block: Some(1),
+            ..Default::default()
+        };
+        let (query, _) = history_store.get_ext_txs_by_address(&sender, 99, &filter, Some(&txn));
+        assert_eq!(query, vec![ext_txs[3].clone()]);
+
+        // Filter by direction.
+        let filter = AddressHistoryFilter {
+            direction: TransferDirection::Outgoing,
+            ..Default::default()
+        };
+        assert!(history_store
+            .get_ext_txs_by_address(&Address::burn_address(), 99, &filter, Some(&txn))
+            .0
+            .is_empty());
+        assert_eq!(
+            history_store
+                .get_ext_txs_by_address(&sender, 99, &filter, Some(&txn))
+                .0
+                .len(),
+            5
+        );
+
+        let filter = AddressHistoryFilter {
+            direction: TransferDirection::Incoming,
+            ..Default::default()
+        };
+        assert_eq!(
+            history_store
+                .get_ext_txs_by_address(&reward_target, 99, &filter, Some(&txn))
+                .0
+                .len(),
+            3
+        );
+
+        // Filter by kind.
+        let filter = AddressHistoryFilter {
+            kinds: vec![TransactionKind::Basic, TransactionKind::Staking],
+            ..Default::default()
+        };
+        assert!(history_store
+            .get_ext_txs_by_address(&reward_target, 99, &filter, Some(&txn))
+            .0
+            .is_empty());
+        assert_eq!(
+            history_store
+                .get_ext_txs_by_address(&sender, 99, &filter, Some(&txn))
+                .0
+                .len(),
+            5
+        );
+    }
+
     #[test]
     fn prove_works() {
         // Initialize History Store.
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let history_store = HistoryStore::new(env.clone());
 
         // Create extended transactions.
@@ -1586,7 +2025,7 @@ mod tests {
     #[test]
     fn prove_empty_tree_works() {
         // Initialize History Store.
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let history_store = HistoryStore::new(env.clone());
 
         let txn = WriteTransaction::new(&env);
@@ -1605,7 +2044,7 @@ mod tests {
     #[test]
     fn get_indexes_for_block_works() {
         // Initialize History Store.
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let history_store = HistoryStore::new(env.clone());
         let mut txn = WriteTransaction::new(&env);
 
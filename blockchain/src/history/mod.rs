@@ -1,6 +1,8 @@
+pub use address_filter::{AddressHistoryFilter, HistoryOrder, TransactionKind, TransferDirection};
 pub use history_store::HistoryStore;
 pub use history_tree_chunk::{HistoryTreeChunk, CHUNK_SIZE};
 
+mod address_filter;
 mod history_store;
 mod history_tree_chunk;
 mod mmr_store;
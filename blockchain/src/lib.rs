@@ -1,13 +1,22 @@
 #[macro_use]
 extern crate log;
 
+pub use balance_history_store::{BalanceEntry, BalanceHistoryStore};
 pub use blockchain::blockchain::{Blockchain, BlockchainConfig, TransactionVerificationCache};
+pub use blockchain::integrity::{IntegrityError, IntegrityIssue, IntegrityReport};
+pub use blockchain::replay::{ReplayDivergence, ReplayError, ReplayReport};
+pub use blockchain::snapshot::{SnapshotError, SnapshotManifest};
 pub use history::*;
+pub use rebranch_log::{RebranchReason, RebranchRecord};
+pub use validator_history_store::{ValidatorEvent, ValidatorHistoryEntry, ValidatorHistoryStore};
 
+pub(crate) mod balance_history_store;
 pub(crate) mod blockchain;
 pub(crate) mod blockchain_state;
 #[cfg(feature = "metrics")]
 pub mod chain_metrics;
 pub(crate) mod chain_store;
 pub(crate) mod history;
+pub(crate) mod rebranch_log;
 pub mod reward;
+pub(crate) mod validator_history_store;
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+use std::io;
+
+use beserial::{Deserialize, Serialize};
+use nimiq_block::Block;
+use nimiq_blockchain_interface::ChainInfo;
+use nimiq_database_value::{FromDatabaseValue, IntoDatabaseValue};
+use nimiq_hash::{Blake2bHash, Hash};
+
+/// The reason why the blockchain rebranched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum RebranchReason {
+    /// The adopted chain contains a skip block that replaced a block of our main chain.
+    SkipBlock = 0,
+    /// The adopted chain is a fork of our main chain without skip blocks.
+    Fork = 1,
+}
+
+/// A rebranch of the main chain, as recorded in the rebranch log of the chain store.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RebranchRecord {
+    /// The local time (in milliseconds) when the rebranch happened.
+    pub timestamp: u64,
+    /// The reason of the rebranch.
+    pub reason: RebranchReason,
+    /// The block number of the common ancestor of both chains.
+    pub common_ancestor_number: u32,
+    /// The hash of the common ancestor of both chains.
+    pub common_ancestor_hash: Blake2bHash,
+    /// The hashes of the blocks that were reverted, in ascending block order.
+    #[beserial(len_type(u16))]
+    pub reverted_blocks: Vec<Blake2bHash>,
+    /// The hashes of the blocks that were adopted, in ascending block order.
+    #[beserial(len_type(u16))]
+    pub adopted_blocks: Vec<Blake2bHash>,
+    /// The hashes of the transactions of the reverted blocks that aren't part of the adopted
+    /// blocks.
+    #[beserial(len_type(u32))]
+    pub dropped_transactions: Vec<Blake2bHash>,
+}
+
+impl RebranchRecord {
+    /// Creates a record for a rebranch from the given common ancestor. The reverted and adopted
+    /// chains are given in descending block order, as collected while rebranching, and must
+    /// include the block bodies.
+    pub fn new(
+        timestamp: u64,
+        common_ancestor: &(Blake2bHash, ChainInfo),
+        reverted_chain: &[(Blake2bHash, ChainInfo)],
+        adopted_chain: &[(Blake2bHash, ChainInfo)],
+    ) -> Self {
+        let reverted_blocks: Vec<(&Blake2bHash, &Block)> = reverted_chain
+            .iter()
+            .rev()
+            .map(|(hash, chain_info)| (hash, &chain_info.head))
+            .collect();
+        let adopted_blocks: Vec<(&Blake2bHash, &Block)> = adopted_chain
+            .iter()
+            .rev()
+            .map(|(hash, chain_info)| (hash, &chain_info.head))
+            .collect();
+
+        let reason = if adopted_blocks.iter().any(|(_, block)| block.is_skip()) {
+            RebranchReason::SkipBlock
+        } else {
+            RebranchReason::Fork
+        };
+
+        let adopted_transactions: HashSet<Blake2bHash> = adopted_blocks
+            .iter()
+            .flat_map(|(_, block)| block.transactions().into_iter().flatten())
+            .map(|tx| tx.get_raw_transaction().hash())
+            .collect();
+
+        let dropped_transactions = reverted_blocks
+            .iter()
+            .flat_map(|(_, block)| block.transactions().into_iter().flatten())
+            .map(|tx| tx.get_raw_transaction().hash())
+            .filter(|hash| !adopted_transactions.contains(hash))
+            .collect();
+
+        RebranchRecord {
+            timestamp,
+            reason,
+            common_ancestor_number: common_ancestor.1.head.block_number(),
+            common_ancestor_hash: common_ancestor.0.clone(),
+            reverted_blocks: reverted_blocks
+                .iter()
+                .map(|(hash, _)| (*hash).clone())
+                .collect(),
+            adopted_blocks: adopted_blocks
+                .iter()
+                .map(|(hash, _)| (*hash).clone())
+                .collect(),
+            dropped_transactions,
+        }
+    }
+}
+
+/// The most recent rebranches, oldest first. The log is stored as a single value in the chain
+/// store and bounded to `RebranchLog::MAX_RECORDS` records.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct RebranchLog {
+    #[beserial(len_type(u16))]
+    pub records: Vec<RebranchRecord>,
+}
+
+impl RebranchLog {
+    /// The maximum number of rebranches kept in the log.
+    pub const MAX_RECORDS: usize = 64;
+
+    /// Appends the given record, dropping the oldest ones if the log is full.
+    pub fn push(&mut self, record: RebranchRecord) {
+        self.records.push(record);
+        if self.records.len() > Self::MAX_RECORDS {
+            let excess = self.records.len() - Self::MAX_RECORDS;
+            self.records.drain(..excess);
+        }
+    }
+}
+
+impl IntoDatabaseValue for RebranchLog {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for RebranchLog {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = io::Cursor::new(bytes);
+        Ok(Deserialize::deserialize(&mut cursor)?)
+    }
+}
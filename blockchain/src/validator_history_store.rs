@@ -0,0 +1,270 @@
+use std::borrow::Cow;
+use std::convert::TryInto;
+use std::io;
+
+use nimiq_database::cursor::ReadCursor;
+use nimiq_database::{
+    Database, DatabaseFlags, Environment, ReadTransaction, Transaction, WriteTransaction,
+};
+use nimiq_database_value::{AsDatabaseBytes, FromDatabaseValue};
+use nimiq_keys::Address;
+
+/// An event of a validator recorded in the validator history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidatorEvent {
+    /// The validator produced the block using the given slot.
+    Produced { slot: u16 },
+    /// The validator was supposed to produce the block using the given slot, but it was replaced
+    /// by a skip block.
+    Skipped { slot: u16 },
+    /// A slot of the validator was slashed for the misbehavior at the event block, as in
+    /// `Log::Slash`.
+    Slashed {
+        event_block: u32,
+        slot: u16,
+        newly_disabled: bool,
+    },
+    /// The validator was parked for the misbehavior at the event block, as in `Log::Park`.
+    Parked { event_block: u32 },
+}
+
+impl ValidatorEvent {
+    /// Returns true if the event is a punishment of the validator, i.e. anything but a produced
+    /// block.
+    pub fn is_punishment(&self) -> bool {
+        !matches!(self, ValidatorEvent::Produced { .. })
+    }
+}
+
+/// An event of a validator at the block with the given number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorHistoryEntry {
+    pub block_number: u32,
+    pub event: ValidatorEvent,
+}
+
+impl AsDatabaseBytes for ValidatorHistoryEntry {
+    fn as_database_bytes(&self) -> Cow<[u8]> {
+        let (kind, event_block, slot, flag) = match self.event {
+            ValidatorEvent::Produced { slot } => (0u8, self.block_number, slot, false),
+            ValidatorEvent::Skipped { slot } => (1u8, self.block_number, slot, false),
+            ValidatorEvent::Slashed {
+                event_block,
+                slot,
+                newly_disabled,
+            } => (2u8, event_block, slot, newly_disabled),
+            ValidatorEvent::Parked { event_block } => (3u8, event_block, 0, false),
+        };
+
+        // The block number is stored in big-endian, such that the entries of a validator are
+        // ordered by block number.
+        let bytes = [
+            &self.block_number.to_be_bytes()[..],
+            &[kind],
+            &event_block.to_be_bytes()[..],
+            &slot.to_be_bytes()[..],
+            &[flag as u8],
+        ]
+        .concat();
+        Cow::Owned(bytes)
+    }
+}
+
+impl FromDatabaseValue for ValidatorHistoryEntry {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        if bytes.len() != 12 {
+            return Err(io::Error::from(io::ErrorKind::InvalidData));
+        }
+
+        let block_number = u32::from_be_bytes(bytes[..4].try_into().unwrap());
+        let event_block = u32::from_be_bytes(bytes[5..9].try_into().unwrap());
+        let slot = u16::from_be_bytes(bytes[9..11].try_into().unwrap());
+        let event = match bytes[4] {
+            0 => ValidatorEvent::Produced { slot },
+            1 => ValidatorEvent::Skipped { slot },
+            2 => ValidatorEvent::Slashed {
+                event_block,
+                slot,
+                newly_disabled: bytes[11] != 0,
+            },
+            3 => ValidatorEvent::Parked { event_block },
+            _ => return Err(io::Error::from(io::ErrorKind::InvalidData)),
+        };
+
+        Ok(ValidatorHistoryEntry {
+            block_number,
+            event,
+        })
+    }
+}
+
+/// An optional index of the events of validators: the blocks they produced, the skip blocks that
+/// replaced them and the slashes and parks that hit them. Slashes and parks are only recorded
+/// while the accounts tree is complete, since they are taken from the logs of the staking
+/// contract.
+#[derive(Debug)]
+pub struct ValidatorHistoryStore {
+    env: Environment,
+    // A database of the blocks produced by validators indexed by validator address, ordered by
+    // block number.
+    validator_db: Database,
+    // A database of the punishments of validators indexed by validator address, ordered by block
+    // number. They are kept apart from the produced blocks, which vastly outnumber them.
+    punishment_db: Database,
+}
+
+impl ValidatorHistoryStore {
+    const VALIDATOR_DB_NAME: &'static str = "ValidatorHistory";
+    const PUNISHMENT_DB_NAME: &'static str = "ValidatorPunishments";
+
+    pub fn new(env: Environment) -> Self {
+        let validator_db = env.open_database_with_flags(
+            Self::VALIDATOR_DB_NAME.to_string(),
+            DatabaseFlags::DUPLICATE_KEYS | DatabaseFlags::DUP_FIXED_SIZE_VALUES,
+        );
+        let punishment_db = env.open_database_with_flags(
+            Self::PUNISHMENT_DB_NAME.to_string(),
+            DatabaseFlags::DUPLICATE_KEYS | DatabaseFlags::DUP_FIXED_SIZE_VALUES,
+        );
+        ValidatorHistoryStore {
+            env,
+            validator_db,
+            punishment_db,
+        }
+    }
+
+    /// Records an event of the given validator.
+    pub fn put_event(
+        &self,
+        txn: &mut WriteTransaction,
+        validator_address: &Address,
+        entry: &ValidatorHistoryEntry,
+    ) {
+        let db = if entry.event.is_punishment() {
+            &self.punishment_db
+        } else {
+            &self.validator_db
+        };
+        txn.put(db, validator_address, entry);
+    }
+
+    /// Removes all events of the given validator recorded for the given block. Since blocks are
+    /// reverted from the head, these are always the last entries of the validator.
+    pub fn remove_events(
+        &self,
+        txn: &mut WriteTransaction,
+        validator_address: &Address,
+        block_number: u32,
+    ) {
+        Self::remove_entries(&self.validator_db, txn, validator_address, block_number);
+        Self::remove_entries(&self.punishment_db, txn, validator_address, block_number);
+    }
+
+    /// Returns the blocks in the given range that were produced by the given validator, ordered
+    /// by block number.
+    pub fn get_produced_blocks(
+        &self,
+        validator_address: &Address,
+        from_block: u32,
+        to_block: u32,
+        txn_option: Option<&Transaction>,
+    ) -> Vec<ValidatorHistoryEntry> {
+        self.get_entries(
+            &self.validator_db,
+            validator_address,
+            from_block,
+            to_block,
+            txn_option,
+        )
+    }
+
+    /// Returns the punishments of the given validator at the blocks in the given range, ordered
+    /// by block number.
+    pub fn get_punishments(
+        &self,
+        validator_address: &Address,
+        from_block: u32,
+        to_block: u32,
+        txn_option: Option<&Transaction>,
+    ) -> Vec<ValidatorHistoryEntry> {
+        self.get_entries(
+            &self.punishment_db,
+            validator_address,
+            from_block,
+            to_block,
+            txn_option,
+        )
+    }
+
+    fn remove_entries(
+        db: &Database,
+        txn: &mut WriteTransaction,
+        validator_address: &Address,
+        block_number: u32,
+    ) {
+        loop {
+            let last = {
+                let mut cursor = txn.cursor(db);
+                if cursor
+                    .seek_key::<Address, ValidatorHistoryEntry>(validator_address)
+                    .is_none()
+                {
+                    return;
+                }
+                cursor.last_duplicate::<ValidatorHistoryEntry>()
+            };
+
+            match last {
+                Some(entry) if entry.block_number == block_number => {
+                    txn.remove_item(db, validator_address, &entry);
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn get_entries(
+        &self,
+        db: &Database,
+        validator_address: &Address,
+        from_block: u32,
+        to_block: u32,
+        txn_option: Option<&Transaction>,
+    ) -> Vec<ValidatorHistoryEntry> {
+        let read_txn: ReadTransaction;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = ReadTransaction::new(&self.env);
+                &read_txn
+            }
+        };
+
+        let mut entries = vec![];
+
+        // Entries are ordered by block number first, and this is the smallest possible entry at
+        // the first block of the range.
+        let first_entry = ValidatorHistoryEntry {
+            block_number: from_block,
+            event: ValidatorEvent::Produced { slot: 0 },
+        };
+
+        let mut cursor = txn.cursor(db);
+        let mut entry = cursor.seek_range_value(validator_address, &first_entry);
+
+        while let Some(history_entry) = entry {
+            if history_entry.block_number > to_block {
+                break;
+            }
+            entries.push(history_entry);
+            entry = cursor
+                .next_duplicate::<Address, ValidatorHistoryEntry>()
+                .map(|(_, v)| v);
+        }
+
+        entries
+    }
+}
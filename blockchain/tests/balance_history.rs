@@ -0,0 +1,119 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use nimiq_block::Block;
+use nimiq_block_production::test_custom_block::{next_skip_block, BlockConfig};
+use nimiq_block_production::BlockProducer;
+use nimiq_blockchain::{BalanceEntry, Blockchain, BlockchainConfig};
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, PushResult};
+use nimiq_database::volatile::VolatileEnvironment;
+use nimiq_genesis::NetworkId;
+use nimiq_keys::{Address, KeyPair, PrivateKey};
+use nimiq_primitives::{coin::Coin, policy::Policy};
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{generate_transactions, signing_key, voting_key, UNIT_KEY};
+use nimiq_utils::time::OffsetTime;
+
+fn setup_blockchain(index_balance_history: bool) -> Arc<RwLock<Blockchain>> {
+    let config = BlockchainConfig {
+        index_balance_history,
+        ..Default::default()
+    };
+
+    // The balance history requires an additional database.
+    let env = VolatileEnvironment::new(12).unwrap();
+    Arc::new(RwLock::new(
+        Blockchain::new(
+            env,
+            config,
+            NetworkId::UnitAlbatross,
+            Arc::new(OffsetTime::new()),
+        )
+        .unwrap(),
+    ))
+}
+
+#[test]
+fn it_indexes_balances_and_reverts_them_on_rebranch() {
+    let blockchain = setup_blockchain(true);
+    let producer = BlockProducer::new(signing_key(), voting_key());
+
+    let key_pair = KeyPair::from(PrivateKey::from_str(UNIT_KEY).unwrap());
+    let sender = Address::from(&key_pair.public);
+    let txns = generate_transactions(&key_pair, 1, NetworkId::UnitAlbatross, 1, 1);
+    let recipient = txns[0].recipient.clone();
+    let initial_balance = blockchain
+        .read()
+        .get_account_if_complete(&sender)
+        .unwrap()
+        .balance();
+
+    // Produce a skip block at the same height first, to rebranch to it later.
+    let skip_block = next_skip_block(&voting_key(), &blockchain.read(), &BlockConfig::default());
+
+    let micro_block = {
+        let blockchain = blockchain.read();
+        producer.next_micro_block(
+            &blockchain,
+            blockchain.head().timestamp() + Policy::BLOCK_SEPARATION_TIME,
+            vec![],
+            txns,
+            vec![0x42],
+            None,
+        )
+    };
+    assert_eq!(
+        Blockchain::push(blockchain.upgradable_read(), Block::Micro(micro_block)),
+        Ok(PushResult::Extended)
+    );
+
+    // Both the sender and the recipient have a new balance after block 1.
+    {
+        let blockchain = blockchain.read();
+        assert_eq!(
+            blockchain.get_balance_history(&recipient, 0, 1),
+            Ok(vec![BalanceEntry {
+                block_number: 1,
+                balance: Coin::from_u64_unchecked(1),
+            }])
+        );
+        assert_eq!(
+            blockchain.get_balance_history(&sender, 0, 1),
+            Ok(vec![BalanceEntry {
+                block_number: 1,
+                balance: initial_balance - Coin::from_u64_unchecked(3),
+            }])
+        );
+
+        // Blocks outside of the range aren't returned, except for the balance at its start.
+        assert_eq!(blockchain.get_balance_history(&recipient, 0, 0), Ok(vec![]));
+        assert_eq!(
+            blockchain.get_balance_history(&recipient, 5, 10),
+            blockchain.get_balance_history(&recipient, 0, 1)
+        );
+    }
+
+    // Rebranching to the skip block reverts the balances of block 1.
+    assert_eq!(
+        Blockchain::push(blockchain.upgradable_read(), Block::Micro(skip_block)),
+        Ok(PushResult::Rebranched)
+    );
+
+    let blockchain = blockchain.read();
+    assert_eq!(blockchain.get_balance_history(&recipient, 0, 1), Ok(vec![]));
+    assert_eq!(blockchain.get_balance_history(&sender, 0, 1), Ok(vec![]));
+}
+
+#[test]
+fn it_fails_if_balance_history_is_disabled() {
+    let blockchain = setup_blockchain(false);
+
+    assert_eq!(
+        blockchain
+            .read()
+            .get_balance_history(&Address::burn_address(), 0, 1),
+        Err(BlockchainError::BalanceHistoryDisabled)
+    );
+}
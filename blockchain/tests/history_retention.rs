@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use nimiq_block_production::BlockProducer;
+use nimiq_blockchain::{Blockchain, BlockchainConfig};
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_database::volatile::VolatileEnvironment;
+use nimiq_genesis::NetworkId;
+use nimiq_primitives::policy::Policy;
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{produce_macro_blocks_with_txns, signing_key, voting_key};
+use nimiq_transaction::extended_transaction::{ExtTxData, ExtendedTransaction};
+use nimiq_utils::time::OffsetTime;
+
+#[test]
+fn it_prunes_history_outside_of_retention() {
+    let config = BlockchainConfig {
+        history_max_epochs: Some(1),
+        ..Default::default()
+    };
+
+    let env = VolatileEnvironment::new(11).unwrap();
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::new(
+            env,
+            config,
+            NetworkId::UnitAlbatross,
+            Arc::new(OffsetTime::new()),
+        )
+        .unwrap(),
+    ));
+    let producer = BlockProducer::new(signing_key(), voting_key());
+
+    // The history of the only finished epoch is kept completely.
+    produce_macro_blocks_with_txns(
+        &producer,
+        &blockchain,
+        Policy::batches_per_epoch() as usize,
+        5,
+        1,
+    );
+    let history = blockchain
+        .read()
+        .history_store
+        .get_epoch_transactions(1, None);
+    assert_eq!(
+        blockchain.read().history_store.get_last_pruned_epoch(None),
+        0
+    );
+    let election_block_1 = blockchain.read().head();
+
+    // Keep the transactions to the recipient of the first transaction.
+    let recipient_of = |ext_tx: &ExtendedTransaction| match &ext_tx.data {
+        ExtTxData::Basic(tx) => Some(tx.get_raw_transaction().recipient.clone()),
+        ExtTxData::Inherent(_) => None,
+    };
+    let retained_address = history.iter().find_map(recipient_of).unwrap();
+    blockchain
+        .write()
+        .config
+        .history_retained_addresses
+        .insert(retained_address.clone());
+
+    // Once the next epoch is finished, only the retained transactions of the first one are kept.
+    produce_macro_blocks_with_txns(
+        &producer,
+        &blockchain,
+        Policy::batches_per_epoch() as usize,
+        5,
+        2,
+    );
+    let blockchain = blockchain.read();
+    assert_eq!(blockchain.history_store.get_last_pruned_epoch(None), 1);
+
+    let (retained, pruned): (Vec<_>, Vec<_>) = history
+        .into_iter()
+        .partition(|ext_tx| recipient_of(ext_tx) == Some(retained_address.clone()));
+    assert!(!pruned.is_empty());
+    assert_eq!(
+        blockchain.history_store.get_epoch_transactions(1, None),
+        retained
+    );
+
+    // The history root of the pruned epoch stays available.
+    assert_eq!(
+        blockchain.history_store.get_history_tree_root(1, None),
+        Some(election_block_1.history_root().clone())
+    );
+
+    // Retained transactions can still be proven, pruned ones can't.
+    let retained_hash = retained[0].tx_hash();
+    assert!(blockchain
+        .history_store
+        .prove(1, vec![&retained_hash], None, None)
+        .is_some());
+    let pruned_hash = pruned[0].tx_hash();
+    assert!(blockchain
+        .history_store
+        .get_ext_tx_by_hash(&pruned_hash, None)
+        .is_empty());
+
+    // The address index only lists the transactions that weren't pruned.
+    let sender = match &retained[0].data {
+        ExtTxData::Basic(tx) => tx.get_raw_transaction().sender.clone(),
+        ExtTxData::Inherent(_) => unreachable!(),
+    };
+    let sender_hashes = blockchain
+        .history_store
+        .get_tx_hashes_by_address(&sender, u16::MAX, None);
+    assert!(retained
+        .iter()
+        .all(|ext_tx| sender_hashes.contains(&ext_tx.tx_hash())));
+    assert!(pruned
+        .iter()
+        .all(|ext_tx| !sender_hashes.contains(&ext_tx.tx_hash())));
+
+    // The history of the last finished epoch is complete.
+    assert!(blockchain
+        .history_store
+        .get_epoch_transactions(2, None)
+        .iter()
+        .any(|ext_tx| matches!(ext_tx.data, ExtTxData::Inherent(_))));
+}
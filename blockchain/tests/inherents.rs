@@ -17,7 +17,7 @@ use std::sync::Arc;
 #[test]
 fn it_can_create_batch_finalization_inherents() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(
         Blockchain::new(
             env,
@@ -157,7 +157,7 @@ fn it_can_create_batch_finalization_inherents() {
 #[test]
 fn it_can_penalize_delayed_batch() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(
         Blockchain::new(
             env,
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use nimiq_block_production::BlockProducer;
+use nimiq_blockchain::{
+    Blockchain, BlockchainConfig, IntegrityError, IntegrityIssue, IntegrityReport,
+};
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_database::{volatile::VolatileEnvironment, Environment};
+use nimiq_genesis::NetworkId;
+use nimiq_primitives::policy::Policy;
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{
+    produce_macro_blocks, push_micro_block, signing_key, voting_key,
+};
+use nimiq_utils::time::OffsetTime;
+
+fn setup_blockchain(num_micro_blocks: usize) -> (Environment, Arc<RwLock<Blockchain>>) {
+    let env = VolatileEnvironment::new(11).unwrap();
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::new(
+            env.clone(),
+            BlockchainConfig::default(),
+            NetworkId::UnitAlbatross,
+            Arc::new(OffsetTime::new()),
+        )
+        .unwrap(),
+    ));
+
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    produce_macro_blocks(&producer, &blockchain, 1);
+    for _ in 0..num_micro_blocks {
+        push_micro_block(&producer, &blockchain);
+    }
+
+    (env, blockchain)
+}
+
+fn rollback(env: Environment) -> Result<u32, IntegrityError> {
+    Blockchain::rollback_to_consistent_state(
+        env,
+        BlockchainConfig::default(),
+        NetworkId::UnitAlbatross,
+        Arc::new(OffsetTime::new()),
+    )
+}
+
+#[test]
+fn it_accepts_a_consistent_database() {
+    let (env, blockchain) = setup_blockchain(3);
+
+    let report: IntegrityReport = Blockchain::check_integrity(&env, true);
+    assert!(report.is_consistent(), "{:?}", report.issues);
+    assert!(report.accounts_complete);
+    assert_eq!(
+        report.head,
+        Some((
+            blockchain.read().head_hash(),
+            Policy::blocks_per_batch() + 3
+        ))
+    );
+}
+
+#[test]
+fn it_rolls_back_inconsistent_history() {
+    let (env, blockchain) = setup_blockchain(3);
+
+    // Append a leaf to the history tree that doesn't belong to any block.
+    {
+        let blockchain = blockchain.read();
+        let epoch_number = blockchain.epoch_number();
+        let ext_tx = blockchain
+            .history_store
+            .get_epoch_transactions(epoch_number, None)
+            .pop()
+            .unwrap();
+        let mut txn = blockchain.write_transaction();
+        blockchain
+            .history_store
+            .add_to_history(&mut txn, epoch_number, &[ext_tx]);
+        txn.commit();
+    }
+
+    let report = Blockchain::check_integrity(&env, true);
+    assert!(matches!(
+        report.issues[..],
+        [IntegrityIssue::HistoryRootMismatch { block_number, .. }]
+            if block_number == Policy::blocks_per_batch() + 3
+    ));
+
+    // The history isn't checked if requested.
+    assert!(Blockchain::check_integrity(&env, false).is_consistent());
+
+    drop(blockchain);
+    assert_eq!(rollback(env.clone()).unwrap(), Policy::blocks_per_batch());
+
+    let report = Blockchain::check_integrity(&env, true);
+    assert!(report.is_consistent(), "{:?}", report.issues);
+    assert_eq!(report.head.unwrap().1, Policy::blocks_per_batch());
+
+    // The rolled back blockchain can be loaded again.
+    let blockchain = Blockchain::new(
+        env,
+        BlockchainConfig::default(),
+        NetworkId::UnitAlbatross,
+        Arc::new(OffsetTime::new()),
+    )
+    .unwrap();
+    assert_eq!(blockchain.block_number(), Policy::blocks_per_batch());
+    assert!(blockchain.head().is_macro());
+}
+
+#[test]
+fn it_detects_a_head_behind_the_accounts() {
+    let (env, blockchain) = setup_blockchain(2);
+
+    // Move the head back by one block without reverting the accounts.
+    {
+        let blockchain = blockchain.read();
+        let head = blockchain.head();
+        let mut head_info = blockchain
+            .chain_store
+            .get_chain_info(&head.hash(), false, None)
+            .unwrap();
+        let mut parent_info = blockchain
+            .chain_store
+            .get_chain_info(head.parent_hash(), false, None)
+            .unwrap();
+
+        let mut txn = blockchain.write_transaction();
+        head_info.on_main_chain = false;
+        parent_info.main_chain_successor = None;
+        blockchain
+            .chain_store
+            .put_chain_info(&mut txn, &head.hash(), &head_info, false);
+        blockchain
+            .chain_store
+            .put_chain_info(&mut txn, head.parent_hash(), &parent_info, false);
+        blockchain
+            .chain_store
+            .set_head(&mut txn, head.parent_hash());
+        txn.commit();
+    }
+
+    let report = Blockchain::check_integrity(&env, false);
+    assert!(matches!(
+        report.issues[..],
+        [IntegrityIssue::AccountsRootMismatch { block_number, .. }]
+            if block_number == Policy::blocks_per_batch() + 1
+    ));
+
+    // The accounts don't match any block the chain can be rolled back to.
+    drop(blockchain);
+    assert!(matches!(
+        rollback(env),
+        Err(IntegrityError::AccountsUnrecoverable(block_number))
+            if block_number == Policy::blocks_per_batch()
+    ));
+}
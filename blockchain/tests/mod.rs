@@ -1,15 +1,21 @@
+use parking_lot::RwLock;
 use std::sync::Arc;
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt;
 
 use nimiq_block::Block;
-use nimiq_blockchain::Blockchain;
-use nimiq_blockchain_interface::{AbstractBlockchain, PushResult};
+use nimiq_block_production::BlockProducer;
+use nimiq_blockchain::{Blockchain, BlockchainConfig};
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, PushResult};
+use nimiq_database::memory::MemoryEnvironment;
+use nimiq_genesis::NetworkId;
 use nimiq_primitives::policy::Policy;
 use nimiq_test_log::test;
 use nimiq_test_utils::{
-    block_production::TemporaryBlockProducer, blockchain::produce_macro_blocks,
+    block_production::TemporaryBlockProducer,
+    blockchain::{produce_macro_blocks, signing_key, voting_key},
 };
+use nimiq_utils::time::OffsetTime;
 
 #[test]
 fn it_can_rebranch_skip_block() {
@@ -444,3 +450,96 @@ async fn can_enforce_validity_window() {
         "Blockchain with blocks > validity window should be able to enforce it"
     );
 }
+
+#[test]
+fn it_can_rebuild_historic_account_state() {
+    let temp_producer = TemporaryBlockProducer::new();
+    let blockchain = Arc::clone(&temp_producer.blockchain);
+
+    // Rewards are paid out to the reward address of the validator.
+    let reward_address = {
+        let blockchain = blockchain.read();
+        let staking_contract = blockchain.get_staking_contract();
+        let data_store = blockchain.get_staking_contract_store();
+        let txn = blockchain.read_transaction();
+        let validator_address = staking_contract
+            .active_validators
+            .keys()
+            .next()
+            .unwrap()
+            .clone();
+        staking_contract
+            .get_validator(&data_store.read(&txn), &validator_address)
+            .unwrap()
+            .reward_address
+    };
+
+    // Produce three batches, including some skip blocks, and remember the state after each block.
+    let mut balances = vec![];
+    for i in 0..3 * Policy::blocks_per_batch() {
+        temp_producer.next_block(vec![], i % 7 == 3);
+        let blockchain = blockchain.read();
+        balances.push((
+            blockchain.block_number(),
+            blockchain.get_account_if_complete(&reward_address).unwrap(),
+        ));
+    }
+
+    let blockchain = blockchain.read();
+    let head_number = blockchain.block_number();
+    let oldest_block_number = Policy::last_macro_block(head_number);
+
+    for (block_number, account) in balances {
+        let historic_account = blockchain.get_account_at(&reward_address, block_number);
+        if block_number < oldest_block_number {
+            assert_eq!(
+                historic_account,
+                Err(BlockchainError::StateNotAvailable(block_number))
+            );
+        } else {
+            assert_eq!(historic_account, Ok(account));
+        }
+    }
+
+    assert_eq!(
+        blockchain.get_account_at(&reward_address, head_number + 1),
+        Err(BlockchainError::StateNotAvailable(head_number + 1))
+    );
+
+    // The actual state is not affected.
+    assert_eq!(
+        blockchain.state().accounts.get_root_hash_assert(None),
+        blockchain.head().state_root().clone()
+    );
+}
+
+#[test]
+fn it_works_with_an_in_memory_database() {
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::new(
+            MemoryEnvironment::new(),
+            BlockchainConfig::default(),
+            NetworkId::UnitAlbatross,
+            Arc::new(OffsetTime::new()),
+        )
+        .unwrap(),
+    ));
+
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    produce_macro_blocks(
+        &producer,
+        &blockchain,
+        2 * Policy::batches_per_epoch() as usize,
+    );
+
+    let blockchain = blockchain.read();
+    assert_eq!(blockchain.block_number(), 2 * Policy::blocks_per_epoch());
+    assert_eq!(
+        blockchain.state().accounts.get_root_hash_assert(None),
+        blockchain.head().state_root().clone()
+    );
+    assert_eq!(
+        blockchain.history_store.get_history_tree_root(2, None),
+        Some(blockchain.head().history_root().clone())
+    );
+}
@@ -0,0 +1,84 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use nimiq_block::Block;
+use nimiq_block_production::test_custom_block::{next_skip_block, BlockConfig};
+use nimiq_block_production::BlockProducer;
+use nimiq_blockchain::{Blockchain, BlockchainConfig, RebranchReason};
+use nimiq_blockchain_interface::{AbstractBlockchain, PushResult};
+use nimiq_database::volatile::VolatileEnvironment;
+use nimiq_genesis::NetworkId;
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::{KeyPair, PrivateKey};
+use nimiq_primitives::policy::Policy;
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{generate_transactions, signing_key, voting_key, UNIT_KEY};
+use nimiq_utils::time::OffsetTime;
+
+#[test]
+fn it_records_rebranches_to_skip_blocks() {
+    let env = VolatileEnvironment::new(11).unwrap();
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::new(
+            env,
+            BlockchainConfig::default(),
+            NetworkId::UnitAlbatross,
+            Arc::new(OffsetTime::new()),
+        )
+        .unwrap(),
+    ));
+    let producer = BlockProducer::new(signing_key(), voting_key());
+
+    let key_pair = KeyPair::from(PrivateKey::from_str(UNIT_KEY).unwrap());
+    let txns = generate_transactions(&key_pair, 1, NetworkId::UnitAlbatross, 2, 1);
+    let tx_hashes: Vec<Blake2bHash> = txns.iter().map(|tx| tx.hash()).collect();
+
+    let genesis_hash = blockchain.read().head_hash();
+    assert!(blockchain.read().get_recent_rebranches(None).is_empty());
+
+    // Produce a skip block at the same height first, to rebranch to it later.
+    let skip_block = next_skip_block(&voting_key(), &blockchain.read(), &BlockConfig::default());
+    let skip_block_hash = skip_block.hash();
+
+    let micro_block = {
+        let blockchain = blockchain.read();
+        producer.next_micro_block(
+            &blockchain,
+            blockchain.head().timestamp() + Policy::BLOCK_SEPARATION_TIME,
+            vec![],
+            txns,
+            vec![0x42],
+            None,
+        )
+    };
+    let micro_block_hash = micro_block.hash();
+    assert_eq!(
+        Blockchain::push(blockchain.upgradable_read(), Block::Micro(micro_block)),
+        Ok(PushResult::Extended)
+    );
+    assert!(blockchain.read().get_recent_rebranches(None).is_empty());
+
+    assert_eq!(
+        Blockchain::push(blockchain.upgradable_read(), Block::Micro(skip_block)),
+        Ok(PushResult::Rebranched)
+    );
+
+    let records = blockchain.read().get_recent_rebranches(None);
+    assert_eq!(records.len(), 1);
+
+    let record = &records[0];
+    assert_eq!(record.reason, RebranchReason::SkipBlock);
+    assert_eq!(record.common_ancestor_number, 0);
+    assert_eq!(record.common_ancestor_hash, genesis_hash);
+    assert_eq!(record.reverted_blocks, vec![micro_block_hash]);
+    assert_eq!(record.adopted_blocks, vec![skip_block_hash]);
+
+    // The skip block doesn't contain any transactions, so all of them were dropped.
+    let mut dropped_transactions = record.dropped_transactions.clone();
+    dropped_transactions.sort();
+    let mut expected_transactions = tx_hashes;
+    expected_transactions.sort();
+    assert_eq!(dropped_transactions, expected_transactions);
+}
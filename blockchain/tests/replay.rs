@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use nimiq_block::Block;
+use nimiq_block_production::test_custom_block::{next_skip_block, BlockConfig};
+use nimiq_block_production::BlockProducer;
+use nimiq_blockchain::{Blockchain, BlockchainConfig, ReplayError, ReplayReport};
+use nimiq_blockchain_interface::{AbstractBlockchain, PushResult};
+use nimiq_database::{volatile::VolatileEnvironment, Environment};
+use nimiq_genesis::NetworkId;
+use nimiq_primitives::policy::Policy;
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{
+    produce_macro_blocks_with_txns, push_micro_block, signing_key, voting_key,
+};
+use nimiq_utils::time::OffsetTime;
+
+fn setup_blockchain() -> (Environment, Arc<RwLock<Blockchain>>) {
+    setup_blockchain_with_config(BlockchainConfig::default())
+}
+
+fn setup_blockchain_with_config(
+    config: BlockchainConfig,
+) -> (Environment, Arc<RwLock<Blockchain>>) {
+    let env = VolatileEnvironment::new(11).unwrap();
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::new(
+            env.clone(),
+            config,
+            NetworkId::UnitAlbatross,
+            Arc::new(OffsetTime::new()),
+        )
+        .unwrap(),
+    ));
+    (env, blockchain)
+}
+
+fn replay(
+    source_env: &Environment,
+    target_env: &Environment,
+    until: Option<u32>,
+) -> Result<ReplayReport, ReplayError> {
+    Blockchain::replay(
+        source_env,
+        target_env.clone(),
+        BlockchainConfig::default(),
+        NetworkId::UnitAlbatross,
+        Arc::new(OffsetTime::new()),
+        until,
+    )
+}
+
+#[test]
+fn it_replays_and_resumes_from_a_macro_block() {
+    let (source_env, source) = setup_blockchain();
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    produce_macro_blocks_with_txns(&producer, &source, 2, 1, 1);
+    push_micro_block(&producer, &source);
+    let source_head_number = source.read().block_number();
+
+    let target_env = VolatileEnvironment::new(11).unwrap();
+
+    // Replay the first batch.
+    let report = replay(&source_env, &target_env, Some(Policy::blocks_per_batch())).unwrap();
+    assert!(report.is_consistent());
+    assert_eq!(report.start_block, 0);
+    assert_eq!(report.last_block, Policy::blocks_per_batch());
+
+    // Resume from its macro block up to the head of the source.
+    let report = replay(&source_env, &target_env, None).unwrap();
+    assert!(report.is_consistent(), "{:?}", report.divergence);
+    assert_eq!(report.start_block, Policy::blocks_per_batch());
+    assert_eq!(report.last_block, source_head_number);
+
+    let target = Blockchain::new(
+        target_env,
+        BlockchainConfig::default(),
+        NetworkId::UnitAlbatross,
+        Arc::new(OffsetTime::new()),
+    )
+    .unwrap();
+    assert_eq!(target.head_hash(), source.read().head_hash());
+}
+
+#[test]
+fn it_fails_if_the_target_is_not_on_the_source_chain() {
+    let (source_env, source) = setup_blockchain();
+    let (target_env, target) = setup_blockchain();
+    let producer = BlockProducer::new(signing_key(), voting_key());
+
+    push_micro_block(&producer, &source);
+
+    let skip_block = next_skip_block(&voting_key(), &target.read(), &BlockConfig::default());
+    assert_eq!(
+        Blockchain::push(target.upgradable_read(), Block::Micro(skip_block)),
+        Ok(PushResult::Extended)
+    );
+    drop(target);
+
+    assert!(matches!(
+        replay(&source_env, &target_env, None),
+        Err(ReplayError::UnknownStart(1, _))
+    ));
+}
+
+#[test]
+fn it_replays_pruned_epochs_from_the_history() {
+    let (source_env, source) = setup_blockchain_with_config(BlockchainConfig {
+        max_epochs_stored: 1,
+        ..Default::default()
+    });
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    produce_macro_blocks_with_txns(
+        &producer,
+        &source,
+        (2 * Policy::batches_per_epoch() + 1) as usize,
+        1,
+        1,
+    );
+    push_micro_block(&producer, &source);
+    let source_head_number = source.read().block_number();
+
+    // The micro blocks of the first epoch were pruned.
+    assert!(source.read().get_block_at(1, false, None).is_err());
+    assert!(source
+        .read()
+        .get_block_at(Policy::blocks_per_epoch() + 1, false, None)
+        .is_ok());
+
+    let target_env = VolatileEnvironment::new(11).unwrap();
+    let report = replay(&source_env, &target_env, None).unwrap();
+    assert!(report.is_consistent(), "{:?}", report.divergence);
+    assert_eq!(report.start_block, 0);
+    assert_eq!(report.last_block, source_head_number);
+
+    let target = Blockchain::new(
+        target_env,
+        BlockchainConfig::default(),
+        NetworkId::UnitAlbatross,
+        Arc::new(OffsetTime::new()),
+    )
+    .unwrap();
+    assert_eq!(target.head_hash(), source.read().head_hash());
+    assert_eq!(
+        &target.state().accounts.get_root_hash_assert(None),
+        source.read().head().state_root()
+    );
+}
+
+#[test]
+fn it_fails_if_pruned_epochs_have_no_history() {
+    let (source_env, source) = setup_blockchain_with_config(BlockchainConfig {
+        max_epochs_stored: 1,
+        history_max_epochs: Some(1),
+        ..Default::default()
+    });
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    produce_macro_blocks_with_txns(
+        &producer,
+        &source,
+        (2 * Policy::batches_per_epoch()) as usize,
+        1,
+        1,
+    );
+
+    let target_env = VolatileEnvironment::new(11).unwrap();
+    assert!(matches!(
+        replay(&source_env, &target_env, None),
+        Err(ReplayError::MissingHistory)
+    ));
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use nimiq_block_production::BlockProducer;
+use nimiq_blockchain::{Blockchain, BlockchainConfig, SnapshotError};
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_database::volatile::VolatileEnvironment;
+use nimiq_genesis::NetworkId;
+use nimiq_primitives::policy::Policy;
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{
+    produce_macro_blocks, push_micro_block, signing_key, voting_key,
+};
+use nimiq_utils::time::OffsetTime;
+
+#[test]
+fn it_can_export_and_import_snapshots() {
+    let time = Arc::new(OffsetTime::new());
+    let env = VolatileEnvironment::new(11).unwrap();
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::new(
+            env,
+            BlockchainConfig::default(),
+            NetworkId::UnitAlbatross,
+            Arc::clone(&time),
+        )
+        .unwrap(),
+    ));
+
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    produce_macro_blocks(
+        &producer,
+        &blockchain,
+        (Policy::batches_per_epoch() + 1) as usize,
+    );
+    let macro_head = blockchain.read().head();
+
+    // Snapshots are taken at the last macro block, even if micro blocks follow it.
+    push_micro_block(&producer, &blockchain);
+    push_micro_block(&producer, &blockchain);
+
+    let mut snapshot = vec![];
+    let manifest = blockchain.read().export_snapshot(&mut snapshot).unwrap();
+    assert_eq!(manifest.block_hash, macro_head.hash());
+    assert_eq!(manifest.block_number, macro_head.block_number());
+    assert_eq!(&manifest.state_root, macro_head.state_root());
+    assert!(manifest.full_history);
+
+    // Snapshots are rejected for other networks.
+    let other_env = VolatileEnvironment::new(11).unwrap();
+    assert!(matches!(
+        Blockchain::import_snapshot(
+            &other_env,
+            NetworkId::DevAlbatross,
+            true,
+            &mut &snapshot[..]
+        ),
+        Err(SnapshotError::WrongNetwork(NetworkId::UnitAlbatross))
+    ));
+
+    // Snapshots are rejected if they don't contain the history the node needs.
+    assert!(matches!(
+        Blockchain::import_snapshot(
+            &other_env,
+            NetworkId::UnitAlbatross,
+            false,
+            &mut &snapshot[..]
+        ),
+        Err(SnapshotError::HistoryMismatch {
+            snapshot: true,
+            node: false
+        })
+    ));
+
+    let imported = Blockchain::import_snapshot(
+        &other_env,
+        NetworkId::UnitAlbatross,
+        true,
+        &mut &snapshot[..],
+    )
+    .unwrap();
+    assert_eq!(imported, manifest);
+
+    // A blockchain on the imported environment continues at the snapshot's macro block.
+    let imported_blockchain = Blockchain::new(
+        other_env.clone(),
+        BlockchainConfig::default(),
+        NetworkId::UnitAlbatross,
+        time,
+    )
+    .unwrap();
+    assert_eq!(imported_blockchain.head_hash(), manifest.block_hash);
+    assert_eq!(imported_blockchain.block_number(), manifest.block_number);
+    assert_eq!(
+        imported_blockchain.election_head_hash(),
+        blockchain.read().election_head_hash()
+    );
+
+    // Snapshots can only be imported into an empty database.
+    assert!(matches!(
+        Blockchain::import_snapshot(
+            &other_env,
+            NetworkId::UnitAlbatross,
+            true,
+            &mut &snapshot[..]
+        ),
+        Err(SnapshotError::DatabaseNotEmpty)
+    ));
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use nimiq_block::Block;
+use nimiq_block_production::test_custom_block::{next_skip_block, BlockConfig};
+use nimiq_block_production::BlockProducer;
+use nimiq_blockchain::{Blockchain, BlockchainConfig, ValidatorEvent, ValidatorHistoryEntry};
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, PushResult};
+use nimiq_database::volatile::VolatileEnvironment;
+use nimiq_genesis::NetworkId;
+use nimiq_keys::Address;
+use nimiq_primitives::policy::Policy;
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{signing_key, voting_key};
+use nimiq_utils::time::OffsetTime;
+
+fn setup_blockchain(index_validator_history: bool) -> Arc<RwLock<Blockchain>> {
+    let config = BlockchainConfig {
+        index_validator_history,
+        ..Default::default()
+    };
+
+    // The validator history requires an additional database.
+    let env = VolatileEnvironment::new(13).unwrap();
+    Arc::new(RwLock::new(
+        Blockchain::new(
+            env,
+            config,
+            NetworkId::UnitAlbatross,
+            Arc::new(OffsetTime::new()),
+        )
+        .unwrap(),
+    ))
+}
+
+#[test]
+fn it_indexes_producers_and_punishments() {
+    let blockchain = setup_blockchain(true);
+    let producer = BlockProducer::new(signing_key(), voting_key());
+
+    let (validator, slot) = blockchain.read().get_slot_owner_at(1, 1, None).unwrap();
+    let validator_address = validator.address;
+
+    // Produce a skip block at the same height first, to rebranch to it later.
+    let skip_block = next_skip_block(&voting_key(), &blockchain.read(), &BlockConfig::default());
+
+    let micro_block = {
+        let blockchain = blockchain.read();
+        producer.next_micro_block(
+            &blockchain,
+            blockchain.head().timestamp() + Policy::BLOCK_SEPARATION_TIME,
+            vec![],
+            vec![],
+            vec![0x42],
+            None,
+        )
+    };
+    assert_eq!(
+        Blockchain::push(blockchain.upgradable_read(), Block::Micro(micro_block)),
+        Ok(PushResult::Extended)
+    );
+
+    {
+        let blockchain = blockchain.read();
+        assert_eq!(
+            blockchain.get_blocks_by_producer(&validator_address, 0, 1),
+            Ok(vec![ValidatorHistoryEntry {
+                block_number: 1,
+                event: ValidatorEvent::Produced { slot },
+            }])
+        );
+        assert_eq!(
+            blockchain.get_blocks_by_producer(&validator_address, 2, 10),
+            Ok(vec![])
+        );
+        assert_eq!(
+            blockchain.get_validator_punishments(&validator_address),
+            Ok(vec![])
+        );
+    }
+
+    // Rebranching to the skip block removes the produced block and records the punishments.
+    assert_eq!(
+        Blockchain::push(blockchain.upgradable_read(), Block::Micro(skip_block)),
+        Ok(PushResult::Rebranched)
+    );
+
+    let blockchain = blockchain.read();
+    assert_eq!(
+        blockchain.get_blocks_by_producer(&validator_address, 0, 1),
+        Ok(vec![])
+    );
+
+    let punishments = blockchain
+        .get_validator_punishments(&validator_address)
+        .unwrap();
+    assert_eq!(
+        punishments[0],
+        ValidatorHistoryEntry {
+            block_number: 1,
+            event: ValidatorEvent::Skipped { slot },
+        }
+    );
+    assert!(punishments.iter().all(|entry| entry.block_number == 1));
+    assert!(punishments
+        .iter()
+        .any(|entry| matches!(entry.event, ValidatorEvent::Slashed { event_block: 1, .. })));
+}
+
+#[test]
+fn it_fails_if_validator_history_is_disabled() {
+    let blockchain = setup_blockchain(false);
+
+    assert_eq!(
+        blockchain
+            .read()
+            .get_blocks_by_producer(&Address::burn_address(), 0, 1),
+        Err(BlockchainError::ValidatorHistoryDisabled)
+    );
+    assert_eq!(
+        blockchain
+            .read()
+            .get_validator_punishments(&Address::burn_address()),
+        Err(BlockchainError::ValidatorHistoryDisabled)
+    );
+}
@@ -15,7 +15,7 @@ pub use nimiq::{
         logging::{initialize_logging, log_error_cause_chain},
         metrics_server::NimiqTaskMonitor,
         panic::initialize_panic_reporting,
-        signal_handling::initialize_signal_handler,
+        signal_handling::{initialize_signal_handler, register_shutdown_hook},
     },
 };
 
@@ -109,6 +109,15 @@ async fn main_inner() -> Result<(), Error> {
     let consensus = client.consensus_proxy();
     let mempool = client.mempool();
 
+    // Persist the pending transactions when the client is closed
+    if let Some(mempool) = mempool.clone() {
+        register_shutdown_hook(move || {
+            if let Err(e) = mempool.save_snapshot() {
+                log::error!("Failed to persist mempool: {}", e);
+            }
+        });
+    }
+
     let zkp_component = client.take_zkp_component().unwrap();
     tokio::spawn(zkp_component); //ITODO get metrics on this? ask JD
 
@@ -13,7 +13,7 @@ use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 use nimiq_network_interface::{
-    network::{CloseReason, Network},
+    network::{CloseReason, Network, PeerReputationEvent},
     peer_info::Services,
     request::{OutboundRequestError, RequestError},
 };
@@ -399,6 +399,8 @@ impl<N: Network> ConsensusProxy<N> {
                                 } else {
                                     // The proof didn't verify so we continue with another peer
                                     log::warn!(peer=%peer_id, "The transaction proof from this peer did not verify");
+                                    self.network
+                                        .report_peer(peer_id, PeerReputationEvent::BadResponse);
                                 }
                             } else {
                                 // If we receive a proof but we do not receive a block, we disconnect from the peer
@@ -651,7 +651,7 @@ mod tests {
     #[test(tokio::test)]
     async fn it_can_cluster_epoch_ids() {
         let time = Arc::new(OffsetTime::new());
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let blockchain = Arc::new(RwLock::new(
             Blockchain::new(
                 env,
@@ -811,7 +811,7 @@ mod tests {
     #[test(tokio::test)]
     async fn it_can_cluster_checkpoint_ids() {
         let time = Arc::new(OffsetTime::new());
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let blockchain = Arc::new(RwLock::new(
             Blockchain::new(
                 env,
@@ -1099,7 +1099,7 @@ mod tests {
     #[test(tokio::test)]
     async fn it_splits_clusters_correctly() {
         let time = Arc::new(OffsetTime::new());
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let blockchain = Arc::new(RwLock::new(
             Blockchain::new(
                 env,
@@ -264,7 +264,7 @@ mod tests {
 
     fn blockchain() -> Arc<RwLock<Blockchain>> {
         let time = Arc::new(OffsetTime::new());
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         Arc::new(RwLock::new(
             Blockchain::new(
                 env,
@@ -445,7 +445,7 @@ mod tests {
 
     fn blockchain() -> BlockchainProxy {
         let time = Arc::new(OffsetTime::new());
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         BlockchainProxy::Full(Arc::new(RwLock::new(
             Blockchain::new(
                 env,
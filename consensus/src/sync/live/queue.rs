@@ -19,7 +19,7 @@ use nimiq_blockchain_interface::{
 use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_bls::cache::PublicKeyCache;
 use nimiq_hash::Blake2bHash;
-use nimiq_network_interface::network::{MsgAcceptance, Network};
+use nimiq_network_interface::network::{MsgAcceptance, Network, PeerReputationEvent, PubsubId};
 use nimiq_primitives::{
     key_nibbles::KeyNibbles,
     trie::trie_chunk::{TrieChunk, TrieChunkWithStart},
@@ -421,6 +421,20 @@ fn blockchain_push<N: Network>(
     blockchain_push_result
 }
 
+/// Returns whether the push error proves that the block itself is invalid, as opposed to errors
+/// that may as well be caused by our own state, e.g. a missing predecessor or a failing database.
+fn is_invalid_block(error: &PushError) -> bool {
+    matches!(
+        error,
+        PushError::InvalidBlock(_)
+            | PushError::InvalidZKP
+            | PushError::InvalidSuccessor
+            | PushError::InvalidPredecessor
+            | PushError::InvalidFork
+            | PushError::DuplicateTransaction
+    )
+}
+
 fn validate_message<N: Network>(
     network: Arc<N>,
     pubsub_id: Option<N::PubsubId>,
@@ -437,8 +451,13 @@ fn validate_message<N: Network>(
 
                     PushResult::Forked | PushResult::Ignored => MsgAcceptance::Ignore,
                 },
-                Err(_) => {
-                    // TODO Ban peer
+                Err(error) => {
+                    if is_invalid_block(error) {
+                        network.report_peer(
+                            id.propagation_source(),
+                            PeerReputationEvent::InvalidBlock,
+                        );
+                    }
                     MsgAcceptance::Reject
                 }
             };
@@ -30,7 +30,7 @@ async fn test_request_transactions_by_address() {
 
     let blockchain1 = Arc::new(RwLock::new(
         Blockchain::new(
-            VolatileEnvironment::new(11).unwrap(),
+            VolatileEnvironment::new(12).unwrap(),
             BlockchainConfig::default(),
             NetworkId::UnitAlbatross,
             Arc::new(OffsetTime::new()),
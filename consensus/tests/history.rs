@@ -64,7 +64,7 @@ impl MacroSync<MockPeerId> for MockHistorySyncStream {
 
 fn blockchain() -> Arc<RwLock<Blockchain>> {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     Arc::new(RwLock::new(
         Blockchain::new(
             env,
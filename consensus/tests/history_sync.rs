@@ -170,7 +170,7 @@ async fn sync_ingredients() {
 
     // Setup first peer.
     let time = Arc::new(OffsetTime::new());
-    let env1 = VolatileEnvironment::new(11).unwrap();
+    let env1 = VolatileEnvironment::new(12).unwrap();
     let blockchain1 = Arc::new(RwLock::new(
         Blockchain::new(
             env1.clone(),
@@ -221,7 +221,7 @@ async fn sync_ingredients() {
     );
 
     // Setup second peer (not synced yet).
-    let env2 = VolatileEnvironment::new(11).unwrap();
+    let env2 = VolatileEnvironment::new(12).unwrap();
     let time = Arc::new(OffsetTime::new());
     let blockchain2 = Arc::new(RwLock::new(
         Blockchain::new(
@@ -16,7 +16,7 @@ use nimiq_test_utils::validator::seeded_rng;
 #[test(tokio::test(flavor = "multi_thread", worker_threads = 4))]
 async fn test_request_component() {
     let mut hub = Some(MockHub::default());
-    let env = VolatileEnvironment::new(10).expect("Could not open a volatile database");
+    let env = VolatileEnvironment::new(11).expect("Could not open a volatile database");
 
     // Generate genesis block.
     let key = KeyPair::generate(&mut seeded_rng(0));
@@ -45,7 +45,7 @@ use nimiq_utils::{math::CeilingDiv, time::OffsetTime};
 
 fn blockchain(complete: bool) -> Blockchain {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Blockchain::new(
         env.clone(),
         BlockchainConfig::default(),
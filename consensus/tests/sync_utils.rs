@@ -93,7 +93,7 @@ pub async fn sync_two_peers(
     let mut networks = vec![];
 
     // Setup first peer.
-    let env1 = VolatileEnvironment::new(11).unwrap();
+    let env1 = VolatileEnvironment::new(12).unwrap();
     let time = Arc::new(OffsetTime::new());
     let blockchain1 = Arc::new(RwLock::new(
         Blockchain::new(
@@ -144,7 +144,7 @@ pub async fn sync_two_peers(
 
     // Setup second peer (not synced yet).
     let time = Arc::new(OffsetTime::new());
-    let env2 = VolatileEnvironment::new(11).unwrap();
+    let env2 = VolatileEnvironment::new(12).unwrap();
 
     let blockchain2_proxy = match sync_mode {
         SyncMode::History | SyncMode::Full => {
@@ -69,6 +69,13 @@ pub(crate) trait RawReadCursor {
         K: AsDatabaseBytes + FromDatabaseValue,
         V: FromDatabaseValue;
 
+    /// Moves the cursor to the first duplicate of `key` that is greater than or equal to `value`
+    /// and returns it. Only applicable to databases with duplicate keys.
+    fn seek_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue;
+
     fn count_duplicates(&mut self) -> usize;
 }
 
@@ -141,6 +148,13 @@ pub trait ReadCursor {
         K: AsDatabaseBytes + FromDatabaseValue,
         V: FromDatabaseValue;
 
+    /// Moves the cursor to the first duplicate of `key` that is greater than or equal to `value`
+    /// and returns it. Only applicable to databases with duplicate keys.
+    fn seek_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue;
+
     fn count_duplicates(&mut self) -> usize;
 }
 
@@ -256,6 +270,15 @@ macro_rules! impl_read_cursor_from_raw {
             {
                 self.$raw.seek_range_key(key)
             }
+
+            fn seek_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+            where
+                K: AsDatabaseBytes + ?Sized,
+                V: AsDatabaseBytes + FromDatabaseValue,
+            {
+                self.$raw.seek_range_value(key, value)
+            }
+
             fn count_duplicates(&mut self) -> usize {
                 self.$raw.count_duplicates()
             }
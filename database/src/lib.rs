@@ -14,6 +14,8 @@ use crate::cursor::{ReadCursor, WriteCursor as WriteCursorTrait};
 #[macro_use]
 pub mod cursor;
 pub mod mdbx;
+pub mod memory;
+pub mod snapshot;
 pub mod volatile;
 
 use nimiq_database_value::{AsDatabaseBytes, FromDatabaseValue, IntoDatabaseValue};
@@ -68,6 +70,7 @@ bitflags! {
 pub enum Environment {
     Volatile(volatile::VolatileEnvironment),
     Persistent(mdbx::MdbxEnvironment),
+    InMemory(memory::MemoryEnvironment),
 }
 
 impl Environment {
@@ -79,6 +82,9 @@ impl Environment {
             Environment::Persistent(ref env) => {
                 Database::Persistent(env.open_database(name, Default::default()))
             }
+            Environment::InMemory(ref env) => {
+                Database::InMemory(env.open_database(name, Default::default()))
+            }
         }
     }
 
@@ -88,6 +94,7 @@ impl Environment {
             Environment::Persistent(ref env) => {
                 Database::Persistent(env.open_database(name, flags))
             }
+            Environment::InMemory(ref env) => Database::InMemory(env.open_database(name, flags)),
         }
     }
 
@@ -98,6 +105,7 @@ impl Environment {
 pub enum Database {
     Volatile(volatile::VolatileDatabase),
     Persistent(mdbx::MdbxDatabase),
+    InMemory(memory::MemoryDatabase),
 }
 
 impl Database {
@@ -112,7 +120,15 @@ impl Database {
         match self {
             Database::Persistent(ref db) => Some(db),
             Database::Volatile(ref db) => Some(db.as_mdbx()),
+            Database::InMemory(_) => None,
+        }
+    }
+
+    fn in_memory(&self) -> Option<&memory::MemoryDatabase> {
+        if let Database::InMemory(ref db) = self {
+            return Some(db);
         }
+        None
     }
 }
 
@@ -122,6 +138,8 @@ pub enum Transaction<'env> {
     VolatileWrite(volatile::VolatileWriteTransaction<'env>),
     PersistentRead(mdbx::MdbxReadTransaction<'env>),
     PersistentWrite(mdbx::MdbxWriteTransaction<'env>),
+    InMemoryRead(memory::MemoryReadTransaction<'env>),
+    InMemoryWrite(memory::MemoryWriteTransaction<'env>),
 }
 
 impl<'env> Transaction<'env> {
@@ -135,6 +153,8 @@ impl<'env> Transaction<'env> {
             Transaction::VolatileWrite(ref txn) => txn.get(db.volatile().unwrap(), key),
             Transaction::PersistentRead(ref txn) => txn.get(db.persistent().unwrap(), key),
             Transaction::PersistentWrite(ref txn) => txn.get(db.persistent().unwrap(), key),
+            Transaction::InMemoryRead(ref txn) => txn.get(db.in_memory().unwrap(), key),
+            Transaction::InMemoryWrite(ref txn) => txn.get(db.in_memory().unwrap(), key),
         }
     }
 
@@ -144,6 +164,12 @@ impl<'env> Transaction<'env> {
             Transaction::VolatileWrite(ref txn) => Cursor::VolatileCursor(txn.cursor(db)),
             Transaction::PersistentRead(ref txn) => Cursor::PersistentCursor(txn.cursor(db)),
             Transaction::PersistentWrite(ref txn) => Cursor::PersistentCursor(txn.cursor(db)),
+            Transaction::InMemoryRead(ref txn) => {
+                Cursor::InMemoryCursor(txn.cursor(db.in_memory().unwrap()))
+            }
+            Transaction::InMemoryWrite(ref txn) => {
+                Cursor::InMemoryCursor(txn.cursor(db.in_memory().unwrap()))
+            }
         }
     }
 }
@@ -160,6 +186,9 @@ impl<'env> ReadTransaction<'env> {
             Environment::Persistent(ref env) => ReadTransaction(Transaction::PersistentRead(
                 mdbx::MdbxReadTransaction::new(env),
             )),
+            Environment::InMemory(ref env) => ReadTransaction(Transaction::InMemoryRead(
+                memory::MemoryReadTransaction::new(env),
+            )),
         }
     }
 
@@ -198,6 +227,9 @@ impl<'env> WriteTransaction<'env> {
             Environment::Persistent(ref env) => WriteTransaction(Transaction::PersistentWrite(
                 mdbx::MdbxWriteTransaction::new(env),
             )),
+            Environment::InMemory(ref env) => WriteTransaction(Transaction::InMemoryWrite(
+                memory::MemoryWriteTransaction::new(env),
+            )),
         }
     }
 
@@ -224,6 +256,9 @@ impl<'env> WriteTransaction<'env> {
             Transaction::PersistentWrite(ref mut txn) => {
                 txn.put_reserve(db.persistent().unwrap(), key, value)
             }
+            Transaction::InMemoryWrite(ref mut txn) => {
+                txn.put_reserve(db.in_memory().unwrap(), key, value)
+            }
             _ => {
                 unreachable!();
             }
@@ -244,6 +279,7 @@ impl<'env> WriteTransaction<'env> {
             Transaction::PersistentWrite(ref mut txn) => {
                 txn.put(db.persistent().unwrap(), key, value)
             }
+            Transaction::InMemoryWrite(ref mut txn) => txn.put(db.in_memory().unwrap(), key, value),
             _ => {
                 unreachable!();
             }
@@ -257,6 +293,7 @@ impl<'env> WriteTransaction<'env> {
         match self.0 {
             Transaction::VolatileWrite(ref mut txn) => txn.remove(db.volatile().unwrap(), key),
             Transaction::PersistentWrite(ref mut txn) => txn.remove(db.persistent().unwrap(), key),
+            Transaction::InMemoryWrite(ref mut txn) => txn.remove(db.in_memory().unwrap(), key),
             _ => {
                 unreachable!();
             }
@@ -275,6 +312,9 @@ impl<'env> WriteTransaction<'env> {
             Transaction::PersistentWrite(ref mut txn) => {
                 txn.remove_item(db.persistent().unwrap(), key, value)
             }
+            Transaction::InMemoryWrite(ref mut txn) => {
+                txn.remove_item(db.in_memory().unwrap(), key, value)
+            }
             _ => {
                 unreachable!();
             }
@@ -285,6 +325,7 @@ impl<'env> WriteTransaction<'env> {
         match self.0 {
             Transaction::VolatileWrite(txn) => txn.commit(),
             Transaction::PersistentWrite(txn) => txn.commit(),
+            Transaction::InMemoryWrite(txn) => txn.commit(),
             _ => {
                 unreachable!();
             }
@@ -305,6 +346,9 @@ impl<'env> WriteTransaction<'env> {
             Transaction::PersistentWrite(ref txn) => {
                 WriteCursor::PersistentCursor(txn.write_cursor(db))
             }
+            Transaction::InMemoryWrite(ref txn) => {
+                WriteCursor::InMemoryCursor(txn.write_cursor(db.in_memory().unwrap()))
+            }
             _ => unreachable!(),
         }
     }
@@ -315,6 +359,7 @@ impl<'env> WriteTransaction<'env> {
             Transaction::PersistentWrite(ref mut txn) => {
                 txn.clear_database(db.persistent().unwrap())
             }
+            Transaction::InMemoryWrite(ref mut txn) => txn.clear_database(db.in_memory().unwrap()),
             _ => {
                 unreachable!();
             }
@@ -333,11 +378,13 @@ impl<'env> Deref for WriteTransaction<'env> {
 pub enum Cursor<'txn> {
     VolatileCursor(volatile::VolatileCursor<'txn>),
     PersistentCursor(mdbx::MdbxCursor<'txn>),
+    InMemoryCursor(memory::MemoryCursor<'txn>),
 }
 
 pub enum WriteCursor<'txn> {
     VolatileCursor(volatile::VolatileWriteCursor<'txn>),
     PersistentCursor(mdbx::MdbxWriteCursor<'txn>),
+    InMemoryCursor(memory::MemoryWriteCursor<'txn>),
 }
 
 macro_rules! gen_cursor_match {
@@ -345,18 +392,21 @@ macro_rules! gen_cursor_match {
         match $self {
             $t::PersistentCursor(ref mut cursor) => cursor.$f(),
             $t::VolatileCursor(ref mut cursor) => cursor.$f(),
+            $t::InMemoryCursor(ref mut cursor) => cursor.$f(),
         }
     };
     ($self: ident, $f: ident, $k: expr, $t: ident) => {
         match $self {
             $t::PersistentCursor(ref mut cursor) => cursor.$f($k),
             $t::VolatileCursor(ref mut cursor) => cursor.$f($k),
+            $t::InMemoryCursor(ref mut cursor) => cursor.$f($k),
         }
     };
     ($self: ident, $f: ident, $k: expr, $v: expr, $t: ident) => {
         match $self {
             $t::PersistentCursor(ref mut cursor) => cursor.$f($k, $v),
             $t::VolatileCursor(ref mut cursor) => cursor.$f($k, $v),
+            $t::InMemoryCursor(ref mut cursor) => cursor.$f($k, $v),
         }
     };
 }
@@ -472,6 +522,14 @@ impl<'txn> ReadCursor for Cursor<'txn> {
         gen_cursor_match!(self, seek_range_key, key, Cursor)
     }
 
+    fn seek_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue,
+    {
+        gen_cursor_match!(self, seek_range_value, key, value, Cursor)
+    }
+
     fn count_duplicates(&mut self) -> usize {
         gen_cursor_match!(self, count_duplicates, Cursor)
     }
@@ -588,6 +646,14 @@ impl<'txn> ReadCursor for WriteCursor<'txn> {
         gen_cursor_match!(self, seek_range_key, key, WriteCursor)
     }
 
+    fn seek_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue,
+    {
+        gen_cursor_match!(self, seek_range_value, key, value, WriteCursor)
+    }
+
     fn count_duplicates(&mut self) -> usize {
         gen_cursor_match!(self, count_duplicates, WriteCursor)
     }
@@ -11,6 +11,11 @@ use super::*;
 
 type DbKvPair<'a> = (Cow<'a, [u8]>, Cow<'a, [u8]>);
 
+/// Default maximum size of a persistent database: 1 TB.
+pub const DEFAULT_SIZE: usize = 1024 * 1024 * 1024 * 1024;
+/// Default maximum number of databases in a persistent environment.
+pub const DEFAULT_MAX_DBS: u32 = 20;
+
 #[derive(Clone, Debug)]
 pub struct MdbxEnvironment {
     env: Arc<libmdbx::Environment<NoWriteMap>>,
@@ -667,6 +672,29 @@ impl<'txn> RawReadCursor for RawMDBXCursor<'txn> {
         }
     }
 
+    fn seek_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue,
+    {
+        let key = AsDatabaseBytes::as_database_bytes(key);
+        let value = AsDatabaseBytes::as_database_bytes(value);
+        match self {
+            Self::RoCursor(rocursor) => {
+                let result: Option<Cow<[u8]>> = rocursor
+                    .get_both_range(key.as_ref(), value.as_ref())
+                    .unwrap();
+                Some(FromDatabaseValue::copy_from_database(&result?).unwrap())
+            }
+            Self::RwCursor(rwcursor) => {
+                let result: Option<Cow<[u8]>> = rwcursor
+                    .get_both_range(key.as_ref(), value.as_ref())
+                    .unwrap();
+                Some(FromDatabaseValue::copy_from_database(&result?).unwrap())
+            }
+        }
+    }
+
     fn count_duplicates(&mut self) -> usize {
         match self {
             Self::RoCursor(rocursor) => {
@@ -863,6 +891,20 @@ impl<'txn> RawReadCursor for RawWriteLmdbCursor<'txn> {
         ))
     }
 
+    fn seek_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue,
+    {
+        let key = AsDatabaseBytes::as_database_bytes(key);
+        let value = AsDatabaseBytes::as_database_bytes(value);
+        let result: Option<Cow<[u8]>> = self
+            .cursor
+            .get_both_range(key.as_ref(), value.as_ref())
+            .unwrap();
+        Some(FromDatabaseValue::copy_from_database(&result?).unwrap())
+    }
+
     fn count_duplicates(&mut self) -> usize {
         0
     }
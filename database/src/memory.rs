@@ -0,0 +1,677 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::Bound::{Excluded, Included, Unbounded};
+use std::sync::Arc;
+
+use im::OrdSet;
+use parking_lot::{Mutex, MutexGuard, RwLock};
+
+use super::*;
+use crate::cursor::{RawReadCursor, ReadCursor, WriteCursor as WriteCursorTrait};
+
+/// A key/value pair. Databases with duplicate keys store one entry per value.
+type Entry = (Vec<u8>, Vec<u8>);
+
+/// The entries of a database, ordered by key and then by value like in MDBX.
+type Entries = OrdSet<Entry>;
+
+type Tables = HashMap<String, Entries>;
+
+/// A database environment that is kept entirely in memory.
+///
+/// Databases are stored as persistent ordered sets, so read transactions can cheaply hold on to
+/// the state that was committed when they were opened while a write transaction modifies its own
+/// copy. Like in MDBX, there can only be one write transaction at a time.
+#[derive(Clone, Debug)]
+pub struct MemoryEnvironment {
+    inner: Arc<MemoryEnvironmentInner>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryEnvironmentInner {
+    // The last committed state of all databases.
+    tables: RwLock<Arc<Tables>>,
+    // Held by the currently active write transaction.
+    write_lock: Mutex<()>,
+}
+
+impl MemoryEnvironment {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Environment {
+        Environment::InMemory(MemoryEnvironment {
+            inner: Arc::new(MemoryEnvironmentInner::default()),
+        })
+    }
+
+    pub(super) fn open_database(&self, name: String, flags: DatabaseFlags) -> MemoryDatabase {
+        // Databases are created on their first write, databases that don't exist are empty.
+        MemoryDatabase { name, flags }
+    }
+}
+
+#[derive(Debug)]
+pub struct MemoryDatabase {
+    name: String,
+    flags: DatabaseFlags,
+}
+
+impl MemoryDatabase {
+    fn is_dup_sort(&self) -> bool {
+        self.flags.contains(DatabaseFlags::DUPLICATE_KEYS)
+    }
+
+    /// Integer keys are stored in big endian byte order, such that they are sorted by their
+    /// numerical value like in MDBX.
+    fn encode_key(&self, key: &[u8]) -> Vec<u8> {
+        convert_integer_key(self.flags, key)
+    }
+}
+
+fn convert_integer_key(flags: DatabaseFlags, key: &[u8]) -> Vec<u8> {
+    let mut key = key.to_vec();
+    if cfg!(target_endian = "little")
+        && flags.contains(DatabaseFlags::UINT_KEYS)
+        && (key.len() == 4 || key.len() == 8)
+    {
+        key.reverse();
+    }
+    key
+}
+
+fn first_of_key<'a>(entries: &'a Entries, key: &[u8]) -> Option<&'a Entry> {
+    entries
+        .range((Included((key.to_vec(), vec![])), Unbounded))
+        .next()
+        .filter(|(entry_key, _)| entry_key == key)
+}
+
+fn last_of_key<'a>(entries: &'a Entries, key: &[u8]) -> Option<&'a Entry> {
+    entries
+        .range((Included((key.to_vec(), vec![])), Unbounded))
+        .take_while(|(entry_key, _)| entry_key == key)
+        .last()
+}
+
+fn remove_key(entries: &mut Entries, key: &[u8]) {
+    let removed: Vec<Entry> = entries
+        .range((Included((key.to_vec(), vec![])), Unbounded))
+        .take_while(|(entry_key, _)| entry_key == key)
+        .cloned()
+        .collect();
+    for entry in removed {
+        entries.remove(&entry);
+    }
+}
+
+fn get_value<K, V>(tables: &Tables, db: &MemoryDatabase, key: &K) -> Option<V>
+where
+    K: AsDatabaseBytes + ?Sized,
+    V: FromDatabaseValue,
+{
+    let entries = tables.get(&db.name)?;
+    let key = db.encode_key(&AsDatabaseBytes::as_database_bytes(key));
+    let (_, value) = first_of_key(entries, &key)?;
+    Some(FromDatabaseValue::copy_from_database(value).unwrap())
+}
+
+#[derive(Debug)]
+pub struct MemoryReadTransaction<'env> {
+    tables: Arc<Tables>,
+    _env: PhantomData<&'env MemoryEnvironment>,
+}
+
+impl<'env> MemoryReadTransaction<'env> {
+    pub(super) fn new(env: &'env MemoryEnvironment) -> Self {
+        MemoryReadTransaction {
+            tables: Arc::clone(&env.inner.tables.read()),
+            _env: PhantomData,
+        }
+    }
+
+    pub(super) fn get<K, V>(&self, db: &MemoryDatabase, key: &K) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: FromDatabaseValue,
+    {
+        get_value(&self.tables, db, key)
+    }
+
+    pub(super) fn cursor<'txn>(&'txn self, db: &MemoryDatabase) -> MemoryCursor<'txn> {
+        MemoryCursor {
+            raw: RawMemoryCursor::new(&self.tables, db),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MemoryWriteTransaction<'env> {
+    env: &'env MemoryEnvironment,
+    tables: Mutex<Tables>,
+    _write_lock: MutexGuard<'env, ()>,
+}
+
+impl<'env> MemoryWriteTransaction<'env> {
+    pub(super) fn new(env: &'env MemoryEnvironment) -> Self {
+        let write_lock = env.inner.write_lock.lock();
+        let tables = Tables::clone(&env.inner.tables.read());
+        MemoryWriteTransaction {
+            env,
+            tables: Mutex::new(tables),
+            _write_lock: write_lock,
+        }
+    }
+
+    fn entries_mut(&mut self, db: &MemoryDatabase) -> &mut Entries {
+        self.tables.get_mut().entry(db.name.clone()).or_default()
+    }
+
+    pub(super) fn get<K, V>(&self, db: &MemoryDatabase, key: &K) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: FromDatabaseValue,
+    {
+        get_value(&self.tables.lock(), db, key)
+    }
+
+    pub(super) fn put_reserve<K, V>(&mut self, db: &MemoryDatabase, key: &K, value: &V)
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: IntoDatabaseValue + ?Sized,
+    {
+        assert!(
+            !db.is_dup_sort(),
+            "put_reserve is not supported for databases with duplicate keys"
+        );
+
+        let mut bytes = vec![0u8; IntoDatabaseValue::database_byte_size(value)];
+        IntoDatabaseValue::copy_into_database(value, &mut bytes);
+        self.put(db, key, &bytes);
+    }
+
+    pub(super) fn put<K, V>(&mut self, db: &MemoryDatabase, key: &K, value: &V)
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + ?Sized,
+    {
+        let key = db.encode_key(&AsDatabaseBytes::as_database_bytes(key));
+        let value = AsDatabaseBytes::as_database_bytes(value).into_owned();
+
+        let entries = self.entries_mut(db);
+        if !db.is_dup_sort() {
+            remove_key(entries, &key);
+        }
+        entries.insert((key, value));
+    }
+
+    pub(super) fn remove<K>(&mut self, db: &MemoryDatabase, key: &K)
+    where
+        K: AsDatabaseBytes + ?Sized,
+    {
+        let key = db.encode_key(&AsDatabaseBytes::as_database_bytes(key));
+        remove_key(self.entries_mut(db), &key);
+    }
+
+    pub(super) fn remove_item<K, V>(&mut self, db: &MemoryDatabase, key: &K, value: &V)
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + ?Sized,
+    {
+        let key = db.encode_key(&AsDatabaseBytes::as_database_bytes(key));
+        let value = AsDatabaseBytes::as_database_bytes(value).into_owned();
+        self.entries_mut(db).remove(&(key, value));
+    }
+
+    pub(super) fn commit(self) {
+        *self.env.inner.tables.write() = Arc::new(self.tables.into_inner());
+    }
+
+    pub(super) fn cursor<'txn>(&'txn self, db: &MemoryDatabase) -> MemoryCursor<'txn> {
+        MemoryCursor {
+            raw: RawMemoryCursor::new(&self.tables.lock(), db),
+        }
+    }
+
+    pub(super) fn write_cursor<'txn>(&'txn self, db: &MemoryDatabase) -> MemoryWriteCursor<'txn> {
+        MemoryWriteCursor {
+            raw: RawMemoryCursor::new(&self.tables.lock(), db),
+            tables: &self.tables,
+            name: db.name.clone(),
+        }
+    }
+
+    pub(super) fn clear_database(&mut self, db: &MemoryDatabase) {
+        self.entries_mut(db).clear();
+    }
+}
+
+/// A cursor over the state of a database at the time the cursor was created. Entries are
+/// addressed by their key and value, so the cursor stays valid if its current entry is removed.
+pub struct RawMemoryCursor<'txn> {
+    entries: Entries,
+    flags: DatabaseFlags,
+    position: Option<Entry>,
+    _txn: PhantomData<&'txn ()>,
+}
+
+impl<'txn> RawMemoryCursor<'txn> {
+    fn new(tables: &Tables, db: &MemoryDatabase) -> Self {
+        RawMemoryCursor {
+            entries: tables.get(&db.name).cloned().unwrap_or_default(),
+            flags: db.flags,
+            position: None,
+            _txn: PhantomData,
+        }
+    }
+
+    /// Moves the cursor to the given entry, if there is one, and returns it.
+    fn move_to<K, V>(&mut self, entry: Option<Entry>) -> Option<(K, V)>
+    where
+        K: FromDatabaseValue,
+        V: FromDatabaseValue,
+    {
+        let (key, value) = self.position.insert(entry?);
+        Some((
+            FromDatabaseValue::copy_from_database(&convert_integer_key(self.flags, key)).unwrap(),
+            FromDatabaseValue::copy_from_database(value).unwrap(),
+        ))
+    }
+
+    /// Moves the cursor to the given entry, if there is one, and returns its value.
+    fn move_to_value<V>(&mut self, entry: Option<Entry>) -> Option<V>
+    where
+        V: FromDatabaseValue,
+    {
+        let (_, value): (Vec<u8>, V) = self.move_to(entry)?;
+        Some(value)
+    }
+
+    fn current_key(&self) -> Option<&[u8]> {
+        self.position.as_ref().map(|(key, _)| &key[..])
+    }
+
+    fn entry_after(&self, position: &Entry) -> Option<Entry> {
+        self.entries
+            .range((Excluded(position), Unbounded))
+            .next()
+            .cloned()
+    }
+
+    fn entry_before(&self, position: &Entry) -> Option<Entry> {
+        self.entries
+            .range((Unbounded, Excluded(position)))
+            .next_back()
+            .cloned()
+    }
+}
+
+impl<'txn> RawReadCursor for RawMemoryCursor<'txn> {
+    fn first<K, V>(&mut self) -> Option<(K, V)>
+    where
+        K: FromDatabaseValue,
+        V: FromDatabaseValue,
+    {
+        let entry = self.entries.get_min().cloned();
+        self.move_to(entry)
+    }
+
+    fn first_duplicate<V>(&mut self) -> Option<V>
+    where
+        V: FromDatabaseValue,
+    {
+        let entry = first_of_key(&self.entries, self.current_key()?).cloned();
+        self.move_to_value(entry)
+    }
+
+    fn last<K, V>(&mut self) -> Option<(K, V)>
+    where
+        K: FromDatabaseValue,
+        V: FromDatabaseValue,
+    {
+        let entry = self.entries.get_max().cloned();
+        self.move_to(entry)
+    }
+
+    fn last_duplicate<V>(&mut self) -> Option<V>
+    where
+        V: FromDatabaseValue,
+    {
+        let entry = last_of_key(&self.entries, self.current_key()?).cloned();
+        self.move_to_value(entry)
+    }
+
+    fn get_current<K, V>(&mut self) -> Option<(K, V)>
+    where
+        K: FromDatabaseValue,
+        V: FromDatabaseValue,
+    {
+        let entry = self.position.clone().filter(|e| self.entries.contains(e));
+        self.move_to(entry)
+    }
+
+    fn next<K, V>(&mut self) -> Option<(K, V)>
+    where
+        K: FromDatabaseValue,
+        V: FromDatabaseValue,
+    {
+        let entry = match self.position {
+            Some(ref position) => self.entry_after(position),
+            None => self.entries.get_min().cloned(),
+        };
+        self.move_to(entry)
+    }
+
+    fn next_duplicate<K, V>(&mut self) -> Option<(K, V)>
+    where
+        K: FromDatabaseValue,
+        V: FromDatabaseValue,
+    {
+        let position = self.position.as_ref()?;
+        let entry = self
+            .entry_after(position)
+            .filter(|(key, _)| *key == position.0);
+        self.move_to(entry)
+    }
+
+    fn next_no_duplicate<K, V>(&mut self) -> Option<(K, V)>
+    where
+        K: FromDatabaseValue,
+        V: FromDatabaseValue,
+    {
+        let entry = match self.position {
+            Some((ref key, _)) => self
+                .entries
+                .range((Included((key.clone(), vec![])), Unbounded))
+                .find(|(entry_key, _)| entry_key != key)
+                .cloned(),
+            None => self.entries.get_min().cloned(),
+        };
+        self.move_to(entry)
+    }
+
+    fn prev<K, V>(&mut self) -> Option<(K, V)>
+    where
+        K: FromDatabaseValue,
+        V: FromDatabaseValue,
+    {
+        let entry = match self.position {
+            Some(ref position) => self.entry_before(position),
+            None => self.entries.get_max().cloned(),
+        };
+        self.move_to(entry)
+    }
+
+    fn prev_duplicate<K, V>(&mut self) -> Option<(K, V)>
+    where
+        K: FromDatabaseValue,
+        V: FromDatabaseValue,
+    {
+        let position = self.position.as_ref()?;
+        let entry = self
+            .entry_before(position)
+            .filter(|(key, _)| *key == position.0);
+        self.move_to(entry)
+    }
+
+    fn prev_no_duplicate<K, V>(&mut self) -> Option<(K, V)>
+    where
+        K: FromDatabaseValue,
+        V: FromDatabaseValue,
+    {
+        // Like MDBX, this moves to the last duplicate of the previous key.
+        let entry = match self.position {
+            Some((ref key, _)) => self
+                .entries
+                .range((Unbounded, Excluded((key.clone(), vec![]))))
+                .next_back()
+                .cloned(),
+            None => self.entries.get_max().cloned(),
+        };
+        self.move_to(entry)
+    }
+
+    fn seek_key<K, V>(&mut self, key: &K) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: FromDatabaseValue,
+    {
+        let key = convert_integer_key(self.flags, &AsDatabaseBytes::as_database_bytes(key));
+        let entry = first_of_key(&self.entries, &key).cloned();
+        if entry.is_none() {
+            self.position = None;
+        }
+        self.move_to_value(entry)
+    }
+
+    fn seek_key_both<K, V>(&mut self, key: &K) -> Option<(K, V)>
+    where
+        K: AsDatabaseBytes + FromDatabaseValue,
+        V: FromDatabaseValue,
+    {
+        let key = convert_integer_key(self.flags, &AsDatabaseBytes::as_database_bytes(key));
+        let entry = first_of_key(&self.entries, &key).cloned();
+        self.move_to(entry)
+    }
+
+    fn seek_range_key<K, V>(&mut self, key: &K) -> Option<(K, V)>
+    where
+        K: AsDatabaseBytes + FromDatabaseValue,
+        V: FromDatabaseValue,
+    {
+        let key = convert_integer_key(self.flags, &AsDatabaseBytes::as_database_bytes(key));
+        let entry = self
+            .entries
+            .range((Included((key, vec![])), Unbounded))
+            .next()
+            .cloned();
+        self.move_to(entry)
+    }
+
+    fn seek_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue,
+    {
+        let key = convert_integer_key(self.flags, &AsDatabaseBytes::as_database_bytes(key));
+        let value = AsDatabaseBytes::as_database_bytes(value).into_owned();
+        let entry = self
+            .entries
+            .range((Included((key.clone(), value)), Unbounded))
+            .next()
+            .filter(|(entry_key, _)| *entry_key == key)
+            .cloned();
+        if entry.is_none() {
+            self.position = None;
+        }
+        self.move_to_value(entry)
+    }
+
+    fn count_duplicates(&mut self) -> usize {
+        let key = match self.current_key() {
+            Some(key) => key.to_vec(),
+            None => return 0,
+        };
+        self.entries
+            .range((Included((key.clone(), vec![])), Unbounded))
+            .take_while(|(entry_key, _)| *entry_key == key)
+            .count()
+    }
+}
+
+pub struct MemoryCursor<'txn> {
+    raw: RawMemoryCursor<'txn>,
+}
+
+impl_read_cursor_from_raw!(MemoryCursor<'txn>, raw);
+
+pub struct MemoryWriteCursor<'txn> {
+    raw: RawMemoryCursor<'txn>,
+    tables: &'txn Mutex<Tables>,
+    name: String,
+}
+
+impl_read_cursor_from_raw!(MemoryWriteCursor<'txn>, raw);
+
+impl<'txn> WriteCursorTrait for MemoryWriteCursor<'txn> {
+    fn remove(&mut self) {
+        if let Some(ref entry) = self.raw.position {
+            self.raw.entries.remove(entry);
+            if let Some(entries) = self.tables.lock().get_mut(&self.name) {
+                entries.remove(entry);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nimiq_test_log::test;
+
+    #[test]
+    fn it_can_save_basic_objects() {
+        let env = MemoryEnvironment::new();
+        let db = env.open_database("test".to_string());
+
+        // Read non-existent value.
+        let mut tx = WriteTransaction::new(&env);
+        assert!(tx.get::<str, String>(&db, "test").is_none());
+
+        // Write and read value.
+        tx.put_reserve(&db, "test", "one");
+        assert_eq!(tx.get::<str, String>(&db, "test"), Some("one".to_string()));
+        // Overwrite and read value.
+        tx.put_reserve(&db, "test", "two");
+        assert_eq!(tx.get::<str, String>(&db, "test"), Some("two".to_string()));
+        tx.commit();
+
+        // Read value.
+        let tx = ReadTransaction::new(&env);
+        assert_eq!(tx.get::<str, String>(&db, "test"), Some("two".to_string()));
+        tx.close();
+
+        // Remove value.
+        let mut tx = WriteTransaction::new(&env);
+        tx.remove(&db, "test");
+        assert!(tx.get::<str, String>(&db, "test").is_none());
+        tx.commit();
+
+        // Write and abort.
+        let mut tx = WriteTransaction::new(&env);
+        tx.put_reserve(&db, "test", "one");
+        tx.abort();
+
+        // Check aborted transaction.
+        let tx = ReadTransaction::new(&env);
+        assert!(tx.get::<str, String>(&db, "test").is_none());
+    }
+
+    #[test]
+    fn isolation_test() {
+        let env = MemoryEnvironment::new();
+        let db = env.open_database("test".to_string());
+
+        // Read transactions keep seeing the state from when they were opened.
+        let tx = ReadTransaction::new(&env);
+        let mut txw = WriteTransaction::new(&env);
+        txw.put(&db, "test", "one");
+        assert!(tx.get::<str, String>(&db, "test").is_none());
+        txw.commit();
+        assert!(tx.get::<str, String>(&db, "test").is_none());
+
+        let tx2 = ReadTransaction::new(&env);
+        assert_eq!(tx2.get::<str, String>(&db, "test"), Some("one".to_string()));
+    }
+
+    #[test]
+    fn cursor_test() {
+        let env = MemoryEnvironment::new();
+        let db = env.open_database_with_flags("test".to_string(), DatabaseFlags::DUPLICATE_KEYS);
+        let int_db = env.open_database_with_flags("int".to_string(), DatabaseFlags::UINT_KEYS);
+
+        let mut txw = WriteTransaction::new(&env);
+        txw.put::<str, u32>(&db, "test1", &125);
+        txw.put::<str, u32>(&db, "test1", &12);
+        txw.put::<str, u32>(&db, "test1", &5783);
+        txw.put::<str, u32>(&db, "test2", &5783);
+        for number in [256u32, 1, 65536, 2] {
+            txw.put::<u32, u32>(&int_db, &number, &number);
+        }
+        txw.commit();
+
+        let tx = ReadTransaction::new(&env);
+        let mut cursor = tx.cursor(&db);
+        assert_eq!(
+            cursor.first::<String, u32>(),
+            Some(("test1".to_string(), 12))
+        );
+        assert_eq!(cursor.count_duplicates(), 3);
+        assert_eq!(
+            cursor.next_duplicate::<String, u32>(),
+            Some(("test1".to_string(), 125))
+        );
+        assert_eq!(cursor.last_duplicate::<u32>(), Some(5783));
+        assert!(cursor.next_duplicate::<String, u32>().is_none());
+        assert_eq!(
+            cursor.next::<String, u32>(),
+            Some(("test2".to_string(), 5783))
+        );
+        assert_eq!(
+            cursor.prev_no_duplicate::<String, u32>(),
+            Some(("test1".to_string(), 5783))
+        );
+        assert_eq!(cursor.first_duplicate::<u32>(), Some(12));
+        assert!(cursor.prev::<String, u32>().is_none());
+        assert_eq!(cursor.seek_key::<str, u32>("test2"), Some(5783));
+        assert!(cursor.seek_key::<str, u32>("test3").is_none());
+        assert_eq!(cursor.seek_range_value::<str, u32>("test1", &13), Some(125));
+        assert_eq!(
+            cursor.next_duplicate::<String, u32>(),
+            Some(("test1".to_string(), 5783))
+        );
+        assert!(cursor.seek_range_value::<str, u32>("test3", &0).is_none());
+
+        // Integer keys are sorted by their numerical value.
+        let mut cursor = tx.cursor(&int_db);
+        let mut numbers = vec![];
+        let mut entry: Option<(u32, u32)> = cursor.first();
+        while let Some((number, _)) = entry {
+            numbers.push(number);
+            entry = cursor.next();
+        }
+        assert_eq!(numbers, vec![1, 2, 256, 65536]);
+    }
+
+    #[test]
+    fn write_cursor_test() {
+        let env = MemoryEnvironment::new();
+        let db = env.open_database_with_flags("test".to_string(), DatabaseFlags::DUPLICATE_KEYS);
+
+        let mut txw = WriteTransaction::new(&env);
+        txw.put::<str, u32>(&db, "test1", &125);
+        txw.put::<str, u32>(&db, "test1", &12);
+        txw.put::<str, u32>(&db, "test2", &5783);
+        txw.commit();
+
+        // Remove all entries but one while iterating.
+        let txw = WriteTransaction::new(&env);
+        {
+            let mut cursor = txw.write_cursor(&db);
+            let mut entry: Option<(String, u32)> = cursor.first();
+            while let Some((_, value)) = entry {
+                if value != 125 {
+                    cursor.remove();
+                }
+                entry = cursor.next();
+            }
+        }
+        txw.commit();
+
+        let tx = ReadTransaction::new(&env);
+        let mut cursor = tx.cursor(&db);
+        assert_eq!(
+            cursor.first::<String, u32>(),
+            Some(("test1".to_string(), 125))
+        );
+        assert!(cursor.next::<String, u32>().is_none());
+    }
+}
@@ -0,0 +1,247 @@
+use std::io::{self, Read, Write};
+
+use crate::cursor::ReadCursor;
+use crate::{Database, Environment, ReadTransaction, Transaction, WriteTransaction};
+
+/// Magic bytes at the beginning of every database snapshot.
+const SNAPSHOT_MAGIC: &[u8; 4] = b"NDBS";
+
+/// Marks the end of the entries of a database in a snapshot.
+const END_OF_DATABASE: u32 = u32::MAX;
+
+impl Environment {
+    /// Writes a copy of the given databases to `writer`. All databases are read within the same
+    /// read transaction, so the copy is consistent even if the databases are modified meanwhile.
+    ///
+    /// The databases are identified by the given names, which need to be passed in the same
+    /// order to `import_snapshot`. Entries with duplicate keys are copied as well.
+    pub fn export_snapshot<W: Write>(
+        &self,
+        databases: &[(&str, &Database)],
+        writer: &mut W,
+    ) -> io::Result<()> {
+        let txn = ReadTransaction::new(self);
+        let databases: Vec<_> = databases
+            .iter()
+            .map(|(name, db)| (*name, &*txn, *db))
+            .collect();
+        write_snapshot(&databases, writer)
+    }
+
+    /// Reads a snapshot written by `export_snapshot` into the given databases, replacing their
+    /// current contents.
+    ///
+    /// The returned transaction isn't committed yet, such that the caller can check the
+    /// imported data before committing it.
+    pub fn import_snapshot<R: Read>(
+        &self,
+        databases: &[(&str, &Database)],
+        reader: &mut R,
+    ) -> io::Result<WriteTransaction> {
+        let mut txn = WriteTransaction::new(self);
+
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != SNAPSHOT_MAGIC {
+            return Err(invalid_data("not a database snapshot"));
+        }
+
+        for (name, db) in databases {
+            let stored_name = read_bytes(reader)?
+                .ok_or_else(|| invalid_data("unexpected end of database list"))?;
+            if stored_name != name.as_bytes() {
+                return Err(invalid_data(format!(
+                    "expected database {name}, found {}",
+                    String::from_utf8_lossy(&stored_name)
+                )));
+            }
+
+            txn.clear_database(db);
+
+            let mut num_entries = 0u64;
+            while let Some(key) = read_bytes(reader)? {
+                let value =
+                    read_bytes(reader)?.ok_or_else(|| invalid_data("entry without value"))?;
+                txn.put(db, &key, &value);
+                num_entries += 1;
+            }
+
+            debug!(database = name, num_entries, "Imported database");
+        }
+
+        Ok(txn)
+    }
+}
+
+impl<'env> WriteTransaction<'env> {
+    /// Replaces the contents of `db` with the entries of `source_db` as seen by `source_txn`.
+    /// The source database may belong to a different environment.
+    pub fn copy_database(&mut self, db: &Database, source_txn: &Transaction, source_db: &Database) {
+        self.clear_database(db);
+
+        let mut cursor = source_txn.cursor(source_db);
+        let mut entry: Option<(Vec<u8>, Vec<u8>)> = cursor.first();
+        while let Some((key, value)) = entry {
+            self.put(db, &key, &value);
+            entry = cursor.next();
+        }
+    }
+}
+
+/// Writes a copy of the given databases to `writer`, reading each database within the given
+/// transaction. This allows combining databases of different environments into one snapshot,
+/// which can be imported by `Environment::import_snapshot` like any other snapshot.
+pub fn write_snapshot<W: Write>(
+    databases: &[(&str, &Transaction, &Database)],
+    writer: &mut W,
+) -> io::Result<()> {
+    writer.write_all(SNAPSHOT_MAGIC)?;
+    for (name, txn, db) in databases {
+        write_bytes(writer, name.as_bytes())?;
+
+        let mut num_entries = 0u64;
+        let mut cursor = txn.cursor(db);
+        let mut entry: Option<(Vec<u8>, Vec<u8>)> = cursor.first();
+        while let Some((key, value)) = entry {
+            write_bytes(writer, &key)?;
+            write_bytes(writer, &value)?;
+            num_entries += 1;
+            entry = cursor.next();
+        }
+        writer.write_all(&END_OF_DATABASE.to_be_bytes())?;
+
+        debug!(database = name, num_entries, "Exported database");
+    }
+    writer.flush()
+}
+
+/// Writes a length-prefixed byte string.
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(bytes.len())
+        .ok()
+        .filter(|len| *len != END_OF_DATABASE)
+        .ok_or_else(|| invalid_data("value too large"))?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(bytes)
+}
+
+/// Reads a length-prefixed byte string. Returns `None` at the end of a database.
+fn read_bytes<R: Read>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len);
+    if len == END_OF_DATABASE {
+        return Ok(None);
+    }
+
+    let mut bytes = vec![0u8; len as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(Some(bytes))
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{volatile::VolatileEnvironment, DatabaseFlags};
+    use nimiq_test_log::test;
+
+    #[test]
+    fn it_can_export_and_import_snapshots() {
+        let env = VolatileEnvironment::new(2).unwrap();
+        let db = env.open_database("test".to_string());
+        let dup_db = env.open_database_with_flags("dup".to_string(), DatabaseFlags::DUPLICATE_KEYS);
+
+        let mut txw = WriteTransaction::new(&env);
+        txw.put::<str, str>(&db, "test1", "one");
+        txw.put::<str, str>(&db, "test2", "two");
+        txw.put::<str, u32>(&dup_db, "test", &125);
+        txw.put::<str, u32>(&dup_db, "test", &12);
+        txw.commit();
+
+        let mut snapshot = vec![];
+        env.export_snapshot(&[("test", &db), ("dup", &dup_db)], &mut snapshot)
+            .unwrap();
+
+        let other_env = VolatileEnvironment::new(2).unwrap();
+        let other_db = other_env.open_database("test".to_string());
+        let other_dup_db =
+            other_env.open_database_with_flags("dup".to_string(), DatabaseFlags::DUPLICATE_KEYS);
+
+        // Existing contents are replaced.
+        let mut txw = WriteTransaction::new(&other_env);
+        txw.put::<str, str>(&other_db, "test3", "three");
+        txw.commit();
+
+        // Databases need to be listed in the same order.
+        assert!(other_env
+            .import_snapshot(
+                &[("dup", &other_dup_db), ("test", &other_db)],
+                &mut &snapshot[..]
+            )
+            .is_err());
+
+        other_env
+            .import_snapshot(
+                &[("test", &other_db), ("dup", &other_dup_db)],
+                &mut &snapshot[..],
+            )
+            .unwrap()
+            .commit();
+
+        let tx = ReadTransaction::new(&other_env);
+        assert_eq!(
+            tx.get::<str, String>(&other_db, "test1"),
+            Some("one".to_string())
+        );
+        assert_eq!(
+            tx.get::<str, String>(&other_db, "test2"),
+            Some("two".to_string())
+        );
+        assert!(tx.get::<str, String>(&other_db, "test3").is_none());
+
+        let mut cursor = tx.cursor(&other_dup_db);
+        assert_eq!(
+            cursor.first::<String, u32>(),
+            Some(("test".to_string(), 12))
+        );
+        assert_eq!(
+            cursor.next::<String, u32>(),
+            Some(("test".to_string(), 125))
+        );
+        assert!(cursor.next::<String, u32>().is_none());
+    }
+
+    #[test]
+    fn it_can_copy_databases_between_environments() {
+        let env = VolatileEnvironment::new(1).unwrap();
+        let db = env.open_database("test".to_string());
+
+        let mut txw = WriteTransaction::new(&env);
+        txw.put::<str, str>(&db, "test1", "one");
+        txw.put::<str, str>(&db, "test2", "two");
+        txw.commit();
+
+        let other_env = VolatileEnvironment::new(1).unwrap();
+        let other_db = other_env.open_database("test".to_string());
+
+        let mut txw = WriteTransaction::new(&other_env);
+        txw.put::<str, str>(&other_db, "test3", "three");
+        txw.copy_database(&other_db, &ReadTransaction::new(&env), &db);
+        txw.commit();
+
+        let tx = ReadTransaction::new(&other_env);
+        assert_eq!(
+            tx.get::<str, String>(&other_db, "test1"),
+            Some("one".to_string())
+        );
+        assert_eq!(
+            tx.get::<str, String>(&other_db, "test2"),
+            Some("two".to_string())
+        );
+        assert!(tx.get::<str, String>(&other_db, "test3").is_none());
+    }
+}
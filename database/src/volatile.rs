@@ -264,6 +264,14 @@ impl<'txn> ReadCursor for VolatileCursor<'txn> {
         self.0.seek_range_key(key)
     }
 
+    fn seek_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue,
+    {
+        self.0.seek_range_value(key, value)
+    }
+
     fn count_duplicates(&mut self) -> usize {
         self.0.count_duplicates()
     }
@@ -382,6 +390,14 @@ impl<'txn> ReadCursor for VolatileWriteCursor<'txn> {
         self.0.seek_range_key(key)
     }
 
+    fn seek_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue,
+    {
+        self.0.seek_range_value(key, value)
+    }
+
     fn count_duplicates(&mut self) -> usize {
         self.0.count_duplicates()
     }
@@ -591,6 +607,8 @@ mod tests {
             assert_eq!(cursor.get_current::<String, u32>(), Some((test1, 5783)));
             assert!(cursor.prev_no_duplicate::<String, u32>().is_none());
             assert_eq!(cursor.next::<String, u32>(), Some((test2, 5783)));
+            assert_eq!(cursor.seek_range_value::<str, u32>("test1", &13), Some(125));
+            assert!(cursor.seek_range_value::<str, u32>("test", &0).is_none());
         }
     }
 }
@@ -17,7 +17,7 @@ fn main() {
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
-    let env = VolatileEnvironment::new(10).expect("Could not open a volatile database");
+    let env = VolatileEnvironment::new(11).expect("Could not open a volatile database");
     let args = env::args().collect::<Vec<String>>();
 
     if let Some(file) = args.get(1) {
@@ -27,7 +27,7 @@ fn generate_albatross(name: &str, out_dir: &Path, src_dir: &Path) {
     let genesis_config = src_dir.join(format!("{name}.toml"));
     log::info!("genesis source file: {}", genesis_config.display());
 
-    let env = VolatileEnvironment::new(10).expect("Could not open a volatile database");
+    let env = VolatileEnvironment::new(11).expect("Could not open a volatile database");
     let builder = GenesisBuilder::from_config_file(genesis_config).unwrap();
     let genesis_hash = builder.write_to_files(env, &directory).unwrap();
     write_genesis_rs(&directory, name, &genesis_hash);
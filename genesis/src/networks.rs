@@ -70,7 +70,7 @@ impl NetworkInfo {
 
 #[cfg(feature = "genesis-override")]
 fn read_genesis_config(config: &Path) -> Result<GenesisData, GenesisBuilderError> {
-    let env = VolatileEnvironment::new(10).expect("Could not open a volatile database");
+    let env = VolatileEnvironment::new(11).expect("Could not open a volatile database");
 
     let GenesisInfo {
         block,
@@ -9,7 +9,7 @@ use rustls_pemfile::Item;
 
 use nimiq_block::Block;
 #[cfg(feature = "full-consensus")]
-use nimiq_blockchain::{Blockchain, BlockchainConfig};
+use nimiq_blockchain::{Blockchain, BlockchainConfig, SnapshotError};
 use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_bls::cache::PublicKeyCache;
@@ -28,6 +28,8 @@ use nimiq_network_interface::{
     peer_info::{NodeType, Services},
     Multiaddr, Protocol,
 };
+#[cfg(feature = "database-storage")]
+use nimiq_network_libp2p::discovery::peer_contact_store::DBPeerContactStore;
 use nimiq_network_libp2p::{
     discovery::peer_contacts::PeerContact, Config as NetworkConfig, Network,
     TlsConfig as NetworkTls,
@@ -198,6 +200,15 @@ impl ClientInner {
         let (mut provided_services, required_services) =
             generate_service_flags(config.consensus.sync_mode);
 
+        // History nodes that prune their history can neither serve the full history nor look up
+        // the pruned transactions
+        if matches!(config.consensus.sync_mode, SyncMode::History)
+            && config.consensus.history_max_epochs.is_some()
+        {
+            log::info!("History is pruned, not providing the history service");
+            provided_services.remove(Services::HISTORY | Services::TRANSACTION_INDEX);
+        }
+
         // We update the services flags depending on our validator configuration
         #[cfg(feature = "validator")]
         if config.validator.is_some() {
@@ -276,16 +287,48 @@ impl ClientInner {
             None
         };
 
+        // Only history and full nodes store the blockchain, so light nodes can't be initialized
+        // from a snapshot.
+        if config.database.import_snapshot.is_some()
+            && config.consensus.sync_mode == SyncMode::Light
+        {
+            return Err(Error::config_error(
+                "Snapshots can only be imported by history and full nodes",
+            ));
+        }
+
+        // Open database. This happens before the network is set up, since the peer contact book is
+        // persisted in it.
+        #[cfg(feature = "full-consensus")]
+        let import_snapshot = config.database.import_snapshot.clone();
+        #[cfg(feature = "database-storage")]
+        let environment = config.storage.database(
+            config.network_id,
+            config.consensus.sync_mode,
+            config.database,
+        )?;
+
         // Setup libp2p network
-        let network_config = NetworkConfig::new(
-            identity_keypair,
-            peer_contact,
-            seeds,
-            network_info.genesis_hash().clone(),
-            false,
-            required_services,
-            tls_config,
-        );
+        let network_config = NetworkConfig {
+            reputation: config.network.reputation,
+            connection_pool: config.network.connection_limits,
+            ..NetworkConfig::new(
+                identity_keypair,
+                peer_contact,
+                seeds,
+                network_info.genesis_hash().clone(),
+                false,
+                required_services,
+                tls_config,
+                config.network.gossipsub,
+                config.network.dht,
+            )
+        };
+        #[cfg(feature = "database-storage")]
+        let network_config = NetworkConfig {
+            peer_contact_store: Some(Box::new(DBPeerContactStore::new(environment.clone()))),
+            ..network_config
+        };
 
         log::debug!(
             addresses = ?config.network.listen_addresses,
@@ -301,13 +344,31 @@ impl ClientInner {
         // Start buffering network events as early as possible
         let network_events = network.subscribe_events();
 
-        // Open database
-        #[cfg(feature = "database-storage")]
-        let environment = config.storage.database(
-            config.network_id,
-            config.consensus.sync_mode,
-            config.database,
-        )?;
+        // Initialize the database from a snapshot if requested
+        #[cfg(feature = "full-consensus")]
+        if let Some(path) = import_snapshot {
+            // Only history nodes that don't prune their history need the full history.
+            let full_history = config.consensus.sync_mode == SyncMode::History
+                && config.consensus.history_max_epochs.is_none();
+            let mut reader = io::BufReader::new(fs::File::open(&path)?);
+            match Blockchain::import_snapshot(
+                &environment,
+                config.network_id,
+                full_history,
+                &mut reader,
+            ) {
+                Ok(manifest) => log::info!(
+                    block_number = manifest.block_number,
+                    block_hash = %manifest.block_hash,
+                    "Initialized database from snapshot {}",
+                    path.display()
+                ),
+                Err(SnapshotError::DatabaseNotEmpty) => {
+                    log::info!("Database is already initialized, not importing snapshot")
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
 
         let bls_cache = Arc::new(Mutex::new(PublicKeyCache::new(
             Policy::BLS_CACHE_MAX_CAPACITY,
@@ -316,6 +377,8 @@ impl ClientInner {
         #[cfg(feature = "full-consensus")]
         let mut blockchain_config = BlockchainConfig {
             max_epochs_stored: config.consensus.max_epochs_stored,
+            index_balance_history: config.consensus.index_balance_history,
+            index_validator_history: config.consensus.index_validator_history,
             ..Default::default()
         };
 
@@ -337,6 +400,13 @@ impl ClientInner {
             #[cfg(feature = "full-consensus")]
             SyncMode::History => {
                 blockchain_config.keep_history = true;
+                blockchain_config.history_max_epochs = config.consensus.history_max_epochs;
+                blockchain_config.history_retained_addresses = config
+                    .consensus
+                    .history_retained_addresses
+                    .iter()
+                    .cloned()
+                    .collect();
                 let blockchain = Arc::new(RwLock::new(
                     Blockchain::new(
                         environment.clone(),
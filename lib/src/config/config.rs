@@ -1,3 +1,5 @@
+#[cfg(feature = "rpc-server")]
+use std::collections::HashSet;
 #[cfg(any(feature = "rpc-server", feature = "metrics-server"))]
 use std::net::IpAddr;
 #[cfg(feature = "metrics-server")]
@@ -5,23 +7,37 @@ use std::net::SocketAddr;
 use std::{
     path::{Path, PathBuf},
     string::ToString,
+    time::Duration,
 };
 
 use derive_builder::Builder;
+#[cfg(feature = "rpc-server")]
+use ipnet::IpNet;
 use strum_macros::Display;
+#[cfg(feature = "rpc-server")]
+use url::Url;
 
 use beserial::Deserialize;
 #[cfg(feature = "validator")]
 use nimiq_bls::{KeyPair as BlsKeyPair, SecretKey as BlsSecretKey};
 #[cfg(feature = "database-storage")]
-use nimiq_database::{mdbx::MdbxEnvironment, volatile::VolatileEnvironment, Environment};
+use nimiq_database::{
+    mdbx::{self, MdbxEnvironment},
+    volatile::VolatileEnvironment,
+    Environment,
+};
+use nimiq_keys::Address;
 #[cfg(feature = "validator")]
-use nimiq_keys::{Address, KeyPair, PrivateKey};
+use nimiq_keys::{KeyPair, PrivateKey};
 #[cfg(feature = "nimiq-mempool")]
 use nimiq_mempool::{config::MempoolConfig, filter::MempoolRules};
 use nimiq_network_interface::Multiaddr;
-use nimiq_network_libp2p::Keypair as IdentityKeypair;
+use nimiq_network_libp2p::{
+    ConnectionPoolConfig, DhtParams, GossipsubParams, Keypair as IdentityKeypair, ReputationConfig,
+};
 use nimiq_primitives::{networks::NetworkId, policy::Policy};
+#[cfg(feature = "rpc-server")]
+use nimiq_rpc_server::rate_limit::RateLimit;
 use nimiq_utils::file_store::FileStore;
 #[cfg(feature = "validator")]
 use nimiq_utils::key_rng::SecureGenerate;
@@ -36,7 +52,10 @@ use crate::config::consts::default_bind;
 use crate::{
     config::{
         command_line::CommandLine,
-        config_file::{ConfigFile, Seed, TlsSettings},
+        config_file::{
+            ConfigFile, ConnectionLimitsSettings, DhtSettings, GossipsubSettings,
+            ReputationSettings, Seed, TlsSettings,
+        },
         paths,
         user_agent::UserAgent,
     },
@@ -82,6 +101,20 @@ pub struct ConsensusConfig {
     #[builder(default = "1")]
     /// Maximum number of epochs that are stored in the client
     pub max_epochs_stored: u32,
+    #[builder(default)]
+    /// Number of finished epochs whose full history is kept by history nodes. Older epochs are
+    /// pruned, except for the transactions of `history_retained_addresses`. Must be at least 1.
+    /// `None` keeps the full history.
+    pub history_max_epochs: Option<u32>,
+    #[builder(default)]
+    /// Addresses whose transactions are never pruned from the history
+    pub history_retained_addresses: Vec<Address>,
+    #[builder(default)]
+    /// Whether to index the balances of all addresses by block number
+    pub index_balance_history: bool,
+    #[builder(default)]
+    /// Whether to index the produced blocks and punishments of all validators
+    pub index_validator_history: bool,
 }
 
 impl Default for ConsensusConfig {
@@ -90,6 +123,10 @@ impl Default for ConsensusConfig {
             sync_mode: SyncMode::default(),
             min_peers: 3,
             max_epochs_stored: Policy::MIN_EPOCHS_STORED,
+            history_max_epochs: None,
+            history_retained_addresses: vec![],
+            index_balance_history: false,
+            index_validator_history: false,
         }
     }
 }
@@ -122,6 +159,22 @@ pub struct NetworkConfig {
     /// Optional TLS configuration for secure WebSocket
     #[builder(default)]
     pub tls: Option<TlsConfig>,
+
+    /// Weights, decay and ban durations of the peer reputation
+    #[builder(default)]
+    pub reputation: ReputationConfig,
+
+    /// Mesh sizes, message size limit and heartbeat of gossipsub
+    #[builder(default)]
+    pub gossipsub: GossipsubParams,
+
+    /// Record TTL, publication and replication intervals of the DHT
+    #[builder(default)]
+    pub dht: DhtParams,
+
+    /// Maximum numbers of peers in total, per direction, per IP and per subnet
+    #[builder(default)]
+    pub connection_limits: ConnectionPoolConfig,
 }
 
 /// Configuration for setting TLS for secure WebSocket
@@ -143,6 +196,170 @@ impl From<TlsSettings> for TlsConfig {
     }
 }
 
+impl TryFrom<ReputationSettings> for ReputationConfig {
+    type Error = Error;
+
+    fn try_from(value: ReputationSettings) -> Result<Self, Self::Error> {
+        let default = ReputationConfig::default();
+        let config = ReputationConfig {
+            ban_threshold: value.ban_threshold.unwrap_or(default.ban_threshold),
+            ban_duration: value
+                .ban_duration
+                .map_or(default.ban_duration, Duration::from_secs),
+            max_ban_duration: value
+                .max_ban_duration
+                .map_or(default.max_ban_duration, Duration::from_secs),
+            decay_half_life: value
+                .decay_half_life
+                .map_or(default.decay_half_life, Duration::from_secs),
+            ..default
+        };
+
+        if config.ban_threshold >= 0.0 {
+            return Err(Error::config_error(
+                "The reputation ban threshold must be negative",
+            ));
+        }
+        if config.ban_duration.is_zero() || config.decay_half_life.is_zero() {
+            return Err(Error::config_error(
+                "The reputation ban duration and decay half-life must be positive",
+            ));
+        }
+        if config.max_ban_duration < config.ban_duration {
+            return Err(Error::config_error(
+                "The maximum reputation ban duration must not be shorter than the ban duration",
+            ));
+        }
+
+        Ok(config)
+    }
+}
+
+impl TryFrom<GossipsubSettings> for GossipsubParams {
+    type Error = Error;
+
+    fn try_from(value: GossipsubSettings) -> Result<Self, Self::Error> {
+        let default = GossipsubParams::default();
+        let params = GossipsubParams {
+            mesh_n: value.mesh_n.unwrap_or(default.mesh_n),
+            mesh_n_low: value.mesh_n_low.unwrap_or(default.mesh_n_low),
+            mesh_n_high: value.mesh_n_high.unwrap_or(default.mesh_n_high),
+            max_transmit_size: value.max_transmit_size.unwrap_or(default.max_transmit_size),
+            heartbeat_interval: value
+                .heartbeat_interval
+                .map_or(default.heartbeat_interval, Duration::from_millis),
+        };
+
+        if params.mesh_n_low > params.mesh_n || params.mesh_n > params.mesh_n_high {
+            return Err(Error::config_error(
+                "The gossipsub mesh sizes must satisfy mesh_n_low <= mesh_n <= mesh_n_high",
+            ));
+        }
+        // Gossipsub keeps at least two outbound peers in each mesh, which must fit into the lower
+        // bound and into half of the target mesh size.
+        if params.mesh_n_low < 2 || params.mesh_n < 4 {
+            return Err(Error::config_error(
+                "The gossipsub mesh_n_low must be at least 2 and mesh_n at least 4",
+            ));
+        }
+        // Blocks are gossiped with their body, so they must fit into a message.
+        if params.max_transmit_size < Policy::MAX_SIZE_MICRO_BODY {
+            return Err(Error::config_error(format!(
+                "The gossipsub max_transmit_size must be at least the maximum micro block body size of {} bytes",
+                Policy::MAX_SIZE_MICRO_BODY
+            )));
+        }
+        if params.heartbeat_interval.is_zero() {
+            return Err(Error::config_error(
+                "The gossipsub heartbeat_interval must be positive",
+            ));
+        }
+
+        Ok(params)
+    }
+}
+
+impl TryFrom<DhtSettings> for DhtParams {
+    type Error = Error;
+
+    fn try_from(value: DhtSettings) -> Result<Self, Self::Error> {
+        let default = DhtParams::default();
+        let params = DhtParams {
+            record_ttl: value
+                .record_ttl
+                .map_or(default.record_ttl, Duration::from_secs),
+            publication_interval: value
+                .publication_interval
+                .map_or(default.publication_interval, Duration::from_secs),
+            replication_interval: value
+                .replication_interval
+                .map(Duration::from_secs)
+                .or(default.replication_interval),
+        };
+
+        if params.record_ttl.is_zero()
+            || params.publication_interval.is_zero()
+            || params.replication_interval == Some(Duration::ZERO)
+        {
+            return Err(Error::config_error(
+                "The DHT record TTL and intervals must be positive",
+            ));
+        }
+        // Records must be re-published before they expire.
+        if params.publication_interval > params.record_ttl {
+            return Err(Error::config_error(
+                "The DHT publication_interval must not be longer than the record_ttl",
+            ));
+        }
+
+        Ok(params)
+    }
+}
+
+impl TryFrom<ConnectionLimitsSettings> for ConnectionPoolConfig {
+    type Error = Error;
+
+    fn try_from(value: ConnectionLimitsSettings) -> Result<Self, Self::Error> {
+        let default = ConnectionPoolConfig::default();
+        let config = ConnectionPoolConfig {
+            peer_count_max: value.peer_count_max.unwrap_or(default.peer_count_max),
+            inbound_peer_count_max: value
+                .inbound_peer_count_max
+                .unwrap_or(default.inbound_peer_count_max),
+            outbound_peer_count_max: value
+                .outbound_peer_count_max
+                .unwrap_or(default.outbound_peer_count_max),
+            peer_count_per_ip_max: value
+                .peer_count_per_ip_max
+                .unwrap_or(default.peer_count_per_ip_max),
+            peer_count_per_subnet_max: value
+                .peer_count_per_subnet_max
+                .unwrap_or(default.peer_count_per_subnet_max),
+            ipv4_subnet_mask: value.ipv4_subnet_mask.unwrap_or(default.ipv4_subnet_mask),
+            ipv6_subnet_mask: value.ipv6_subnet_mask.unwrap_or(default.ipv6_subnet_mask),
+            ..default
+        };
+
+        if config.peer_count_max == 0
+            || config.inbound_peer_count_max == 0
+            || config.outbound_peer_count_max == 0
+            || config.peer_count_per_ip_max == 0
+            || config.peer_count_per_subnet_max == 0
+        {
+            return Err(Error::config_error(
+                "The connection limits must be positive",
+            ));
+        }
+        if config.ipv4_subnet_mask > 32 || config.ipv6_subnet_mask > 128 {
+            return Err(Error::config_error(
+                "The subnet masks must not exceed 32 bits for IPv4 and 128 bits for IPv6",
+            ));
+        }
+
+        Ok(config)
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct FileStorageConfig {
     /// The parent directory where the database will be stored. The database directory name
@@ -232,8 +449,8 @@ pub struct DatabaseConfig {
     #[builder(default = "1024 * 1024 * 1024 * 1024")]
     size: usize,
 
-    /// Max number of DBs. Recommended: 13
-    #[builder(default = "13")]
+    /// Max number of DBs. Default: 20
+    #[builder(default = "mdbx::DEFAULT_MAX_DBS")]
     max_dbs: u32,
 
     /// Max number of threads that can open read transactions.
@@ -242,15 +459,20 @@ pub struct DatabaseConfig {
     /// Recommended: 600
     #[builder(default = "600")]
     max_readers: u32,
+
+    /// Snapshot file to initialize an empty database from. Nodes started from a snapshot
+    /// continue syncing from the macro block the snapshot was taken at.
+    #[builder(default)]
+    pub import_snapshot: Option<PathBuf>,
 }
 #[cfg(feature = "database-storage")]
 impl Default for DatabaseConfig {
     fn default() -> Self {
         Self {
-            // 1 TB
-            size: 1024 * 1024 * 1024 * 1024,
-            max_dbs: 13,
+            size: mdbx::DEFAULT_SIZE,
+            max_dbs: mdbx::DEFAULT_MAX_DBS,
             max_readers: 600,
+            import_snapshot: None,
         }
     }
 }
@@ -264,6 +486,7 @@ impl From<Option<DatabaseSettings>> for DatabaseConfig {
                 size: db_settings.size.unwrap_or(default.size),
                 max_dbs: db_settings.max_dbs.unwrap_or(default.max_dbs),
                 max_readers: db_settings.max_readers.unwrap_or(default.max_readers),
+                import_snapshot: db_settings.import_snapshot.map(PathBuf::from),
             }
         } else {
             default
@@ -520,23 +743,78 @@ pub struct RpcServerConfig {
     #[builder(default = "consts::RPC_DEFAULT_PORT")]
     pub port: u16,
 
-    /// TODO
+    /// If specified, allow cross-origin requests from these origins (e.g. `https://example.com`).
+    /// A single `*` allows requests from any origin.
+    ///
     #[builder(setter(strip_option))]
     pub corsdomain: Option<Vec<String>>,
 
-    /// If specified, only allow connections from these IP addresses
+    /// If specified, only allow connections from these IP addresses or subnets (in CIDR notation)
     ///
     #[builder(setter(strip_option))]
-    pub allow_ips: Option<Vec<IpAddr>>,
+    pub allow_ips: Option<Vec<IpNet>>,
 
     /// If specified, only allow these RPC methods
     ///
     #[builder(setter(strip_option))]
     pub allowed_methods: Option<Vec<String>>,
 
-    /// If specified, require HTTP basic auth with these credentials
+    /// If specified, require HTTP basic auth with these credentials. This user can call all
+    /// allowed methods.
     #[builder(setter(strip_option))]
     pub credentials: Option<Credentials>,
+
+    /// Additional users, each restricted to the methods of its role. If any users are
+    /// specified, HTTP basic auth is required.
+    ///
+    /// Default: no additional users
+    ///
+    #[builder(default)]
+    pub users: Vec<RpcUserConfig>,
+
+    /// Enables the websocket endpoint, which is required for subscriptions.
+    ///
+    /// Default: `false`
+    ///
+    #[builder(default)]
+    pub enable_websocket: bool,
+
+    /// Maximum number of subscriptions a single websocket connection can hold.
+    ///
+    /// Default: `16`
+    ///
+    #[builder(default = "consts::RPC_DEFAULT_MAX_SUBSCRIPTIONS")]
+    pub max_subscriptions: usize,
+
+    /// Number of notifications buffered per subscription. Subscriptions of clients that fall
+    /// further behind are closed.
+    ///
+    /// Default: `64`
+    ///
+    #[builder(default = "consts::RPC_DEFAULT_SUBSCRIPTION_BUFFER_SIZE")]
+    pub subscription_buffer_size: usize,
+
+    /// Rate limits per client and class of methods. Clients are identified by their username if
+    /// credentials are required, or by their IP address otherwise.
+    ///
+    /// Default: no rate limits
+    ///
+    #[builder(default)]
+    pub rate_limits: Vec<RateLimit>,
+}
+
+/// A user of the RPC server that is restricted to a set of methods
+#[cfg(feature = "rpc-server")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcUserConfig {
+    /// The credentials of the user
+    pub credentials: Credentials,
+
+    /// The name of the role the user was assigned
+    pub role: String,
+
+    /// The methods the user is allowed to call
+    pub allowed_methods: HashSet<String>,
 }
 
 #[cfg(feature = "metrics-server")]
@@ -684,6 +962,7 @@ impl ClientConfigBuilder {
             control_size_limit,
             filter_rules,
             filter_limit,
+            ..Default::default()
         });
         self
     }
@@ -722,11 +1001,59 @@ impl ClientConfigBuilder {
             seeds: config_file.network.seed_nodes.clone(),
 
             tls: config_file.network.tls.as_ref().map(|s| s.clone().into()),
+
+            reputation: config_file
+                .network
+                .reputation
+                .clone()
+                .map(ReputationConfig::try_from)
+                .transpose()?
+                .unwrap_or_default(),
+
+            gossipsub: config_file
+                .network
+                .gossipsub
+                .clone()
+                .map(GossipsubParams::try_from)
+                .transpose()?
+                .unwrap_or_default(),
+
+            dht: config_file
+                .network
+                .dht
+                .clone()
+                .map(DhtParams::try_from)
+                .transpose()?
+                .unwrap_or_default(),
+
+            connection_limits: config_file
+                .network
+                .connection_limits
+                .clone()
+                .map(ConnectionPoolConfig::try_from)
+                .transpose()?
+                .unwrap_or_default(),
         });
 
         // Configure consensus
+        if config_file.consensus.history_max_epochs == Some(0) {
+            return Err(Error::config_error(
+                "history_max_epochs must be at least 1, omit it to keep the full history",
+            ));
+        }
         let mut consensus = ConsensusConfigBuilder::default()
             .sync_mode(config_file.consensus.sync_mode)
+            .history_max_epochs(config_file.consensus.history_max_epochs)
+            .history_retained_addresses(
+                config_file
+                    .consensus
+                    .history_retained_addresses
+                    .iter()
+                    .map(|address| Address::from_any_str(address))
+                    .collect::<Result<Vec<Address>, _>>()?,
+            )
+            .index_balance_history(config_file.consensus.index_balance_history)
+            .index_validator_history(config_file.consensus.index_validator_history)
             .build()
             .unwrap();
         if let Some(min_peers) = config_file.consensus.min_peers {
@@ -818,13 +1145,36 @@ impl ClientConfigBuilder {
                         .allowip
                         .iter()
                         .map(|s| {
-                            s.parse::<IpAddr>()
-                                .map_err(|e| Error::config_error(format!("Invalid IP: {e}")))
+                            // Accept both single IP addresses and subnets in CIDR notation.
+                            s.parse::<IpNet>()
+                                .or_else(|_| s.parse::<IpAddr>().map(IpNet::from))
+                                .map_err(|e| {
+                                    Error::config_error(format!("Invalid IP or subnet: {e}"))
+                                })
                         })
-                        .collect::<Result<Vec<IpAddr>, Error>>();
+                        .collect::<Result<Vec<IpNet>, Error>>();
                     Some(result?)
                 };
 
+                // Normalize the CORS domains to their origin, i.e. `scheme://host[:port]`.
+                let corsdomain = rpc_config
+                    .corsdomain
+                    .iter()
+                    .map(|domain| {
+                        if domain == "*" {
+                            return Ok(domain.clone());
+                        }
+                        match Url::parse(domain) {
+                            Ok(url) if matches!(url.scheme(), "http" | "https") => {
+                                Ok(url.origin().ascii_serialization())
+                            }
+                            _ => Err(Error::config_error(format!(
+                                "Invalid CORS domain: {domain}"
+                            ))),
+                        }
+                    })
+                    .collect::<Result<Vec<String>, Error>>()?;
+
                 let credentials = match (&rpc_config.username, &rpc_config.password) {
                     (Some(u), Some(p)) => Some(Credentials::new(u.clone(), p.clone())),
                     (None, None) => None,
@@ -835,13 +1185,70 @@ impl ClientConfigBuilder {
                     }
                 };
 
+                let mut users: Vec<RpcUserConfig> = Vec::with_capacity(rpc_config.users.len());
+                for user in &rpc_config.users {
+                    let allowed_methods = rpc_config.roles.get(&user.role).ok_or_else(|| {
+                        Error::config_error(format!(
+                            "RPC user {} has unknown role {}",
+                            user.username, user.role
+                        ))
+                    })?;
+                    let is_duplicate = users
+                        .iter()
+                        .map(|u| &u.credentials.username)
+                        .chain(credentials.as_ref().map(|c| &c.username))
+                        .any(|username| *username == user.username);
+                    if is_duplicate {
+                        return Err(Error::config_error(format!(
+                            "Duplicate RPC user {}",
+                            user.username
+                        )));
+                    }
+                    users.push(RpcUserConfig {
+                        credentials: Credentials::new(&user.username, &user.password),
+                        role: user.role.clone(),
+                        allowed_methods: allowed_methods.iter().cloned().collect(),
+                    });
+                }
+
+                let mut rate_limits = Vec::with_capacity(rpc_config.rate_limits.len());
+                for limit in &rpc_config.rate_limits {
+                    if limit.burst == 0 || !limit.per_second.is_finite() || limit.per_second <= 0.0
+                    {
+                        return Err(Error::config_error(
+                            "RPC rate limits need a positive burst and rate per second",
+                        ));
+                    }
+                    if limit.methods.is_empty()
+                        && rate_limits.iter().any(|l: &RateLimit| l.methods.is_empty())
+                    {
+                        return Err(Error::config_error(
+                            "Only one RPC rate limit can apply to all other methods",
+                        ));
+                    }
+                    rate_limits.push(RateLimit {
+                        methods: limit.methods.clone(),
+                        burst: limit.burst,
+                        per_second: limit.per_second,
+                    });
+                }
+
                 self.rpc_server = Some(Some(RpcServerConfig {
                     bind_to,
                     port: rpc_config.port.unwrap_or(consts::RPC_DEFAULT_PORT),
-                    corsdomain: Some(rpc_config.corsdomain.clone()),
+                    corsdomain: Some(corsdomain),
                     allow_ips,
                     allowed_methods: Some(rpc_config.methods.clone()),
                     credentials,
+                    users,
+                    enable_websocket: rpc_config.websocket,
+                    max_subscriptions: rpc_config
+                        .max_subscriptions
+                        .unwrap_or(consts::RPC_DEFAULT_MAX_SUBSCRIPTIONS),
+                    subscription_buffer_size: rpc_config
+                        .subscription_buffer_size
+                        .unwrap_or(consts::RPC_DEFAULT_SUBSCRIPTION_BUFFER_SIZE),
+                    rate_limits,
                 }));
             }
         }
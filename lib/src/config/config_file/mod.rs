@@ -141,6 +141,13 @@ pub struct NetworkSettings {
 
     pub tls: Option<TlsSettings>,
     pub instant_inbound: Option<bool>,
+
+    pub reputation: Option<ReputationSettings>,
+
+    pub gossipsub: Option<GossipsubSettings>,
+    pub dht: Option<DhtSettings>,
+
+    pub connection_limits: Option<ConnectionLimitsSettings>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -159,6 +166,68 @@ pub struct TlsSettings {
     pub certificates: String,
 }
 
+/// Settings for the reputation of misbehaving peers. Unset values fall back to their defaults.
+#[derive(Clone, Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ReputationSettings {
+    /// Score at or below which a peer gets banned. Must be negative.
+    pub ban_threshold: Option<f64>,
+    /// Duration of the first ban of a peer in seconds. Every further ban doubles it.
+    pub ban_duration: Option<u64>,
+    /// Maximum duration of a ban in seconds.
+    pub max_ban_duration: Option<u64>,
+    /// Time in seconds after which half of the penalties of a peer are forgiven.
+    pub decay_half_life: Option<u64>,
+}
+
+/// Settings for the gossipsub mesh. Unset values fall back to their defaults.
+#[derive(Clone, Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct GossipsubSettings {
+    /// Target number of peers in the mesh of a topic.
+    pub mesh_n: Option<usize>,
+    /// Minimum number of peers in the mesh of a topic before adding more.
+    pub mesh_n_low: Option<usize>,
+    /// Maximum number of peers in the mesh of a topic before removing some.
+    pub mesh_n_high: Option<usize>,
+    /// Maximum size of a gossipsub message in bytes.
+    pub max_transmit_size: Option<usize>,
+    /// Interval of the gossipsub heartbeat in milliseconds.
+    pub heartbeat_interval: Option<u64>,
+}
+
+/// Settings for the Kademlia DHT. Unset values fall back to their defaults.
+#[derive(Clone, Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DhtSettings {
+    /// Time in seconds after which records expire.
+    pub record_ttl: Option<u64>,
+    /// Interval in seconds in which our own records are re-published.
+    pub publication_interval: Option<u64>,
+    /// Interval in seconds in which stored records are replicated. Replication is disabled if unset.
+    pub replication_interval: Option<u64>,
+}
+
+/// Settings for the number of peers we accept and dial. Unset values fall back to their defaults.
+#[derive(Clone, Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectionLimitsSettings {
+    /// Maximum number of connected peers.
+    pub peer_count_max: Option<usize>,
+    /// Maximum number of connected peers that dialed us.
+    pub inbound_peer_count_max: Option<usize>,
+    /// Maximum number of connected peers that we dialed.
+    pub outbound_peer_count_max: Option<usize>,
+    /// Maximum number of connected peers per IP address.
+    pub peer_count_per_ip_max: Option<usize>,
+    /// Maximum number of connected peers per subnet.
+    pub peer_count_per_subnet_max: Option<usize>,
+    /// Prefix length of the IPv4 subnets.
+    pub ipv4_subnet_mask: Option<u8>,
+    /// Prefix length of the IPv6 subnets.
+    pub ipv6_subnet_mask: Option<u8>,
+}
+
 #[derive(Clone, Debug, Deserialize, Default)]
 #[serde(deny_unknown_fields)]
 /// Different knobs used to tweak the consensus mechanism and settings
@@ -174,6 +243,18 @@ pub struct ConsensusSettings {
     pub network: Network,
     /// Minimum number of peers necessary to reach consensus
     pub min_peers: Option<usize>,
+    /// Number of finished epochs whose full history is kept by history nodes. Older epochs are
+    /// pruned, except for the transactions of `history_retained_addresses`.
+    pub history_max_epochs: Option<u32>,
+    #[serde(default)]
+    /// Addresses whose transactions are never pruned from the history
+    pub history_retained_addresses: Vec<String>,
+    #[serde(default)]
+    /// Whether to index the balances of all addresses by block number
+    pub index_balance_history: bool,
+    #[serde(default)]
+    /// Whether to index the produced blocks and punishments of all validators
+    pub index_validator_history: bool,
 }
 
 #[derive(Clone, Copy, Deserialize, Debug, Default, Eq, PartialEq)]
@@ -273,6 +354,39 @@ pub struct RpcServerSettings {
     pub methods: Vec<String>,
     pub username: Option<String>,
     pub password: Option<String>,
+    #[serde(default)]
+    pub websocket: bool,
+    pub max_subscriptions: Option<usize>,
+    pub subscription_buffer_size: Option<usize>,
+    #[serde(default)]
+    pub rate_limits: Vec<RpcRateLimitSettings>,
+    #[serde(default)]
+    pub users: Vec<RpcUserSettings>,
+    #[serde(default)]
+    pub roles: HashMap<String, Vec<String>>,
+}
+
+/// An additional user of the RPC server
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcUserSettings {
+    pub username: String,
+    pub password: String,
+    /// The role of the user, which determines the methods it can call
+    pub role: String,
+}
+
+/// A token bucket rate limit for a class of RPC methods
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcRateLimitSettings {
+    /// The methods this limit applies to. If empty, it applies to all other methods.
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// Maximum number of requests a client can make in a burst
+    pub burst: u32,
+    /// Number of requests per second a client can make in the long run
+    pub per_second: f64,
 }
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -348,6 +462,7 @@ pub struct DatabaseSettings {
     pub size: Option<usize>,
     pub max_dbs: Option<u32>,
     pub max_readers: Option<u32>,
+    pub import_snapshot: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -357,6 +472,11 @@ pub struct MempoolSettings {
     pub size_limit: Option<usize>,
     pub control_size_limit: Option<usize>,
     pub blacklist_limit: Option<usize>,
+    pub replacement_fee_increment: Option<f64>,
+    #[serde(default)]
+    pub allow_dependent_transactions: bool,
+    pub persistence_file: Option<String>,
+    pub persistence_interval: Option<u64>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -409,6 +529,15 @@ impl From<MempoolSettings> for MempoolConfig {
                 .blacklist_limit
                 .unwrap_or(MempoolFilter::DEFAULT_BLACKLIST_SIZE),
             filter_rules: mempool.filter.map(MempoolRules::from).unwrap_or_default(),
+            replacement_fee_increment: mempool
+                .replacement_fee_increment
+                .unwrap_or(Mempool::DEFAULT_REPLACEMENT_FEE_INCREMENT),
+            allow_dependent_transactions: mempool.allow_dependent_transactions,
+            persistence_file: mempool.persistence_file.map(std::path::PathBuf::from),
+            persistence_interval: mempool
+                .persistence_interval
+                .map(std::time::Duration::from_secs)
+                .unwrap_or(Mempool::DEFAULT_PERSISTENCE_INTERVAL),
         }
     }
 }
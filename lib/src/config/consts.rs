@@ -9,6 +9,12 @@ pub const REVERSE_PROXY_DEFAULT_PORT: u16 = 8444;
 /// The default port for the RPC server
 pub const RPC_DEFAULT_PORT: u16 = 8648;
 
+/// The default maximum number of subscriptions per RPC websocket connection
+pub const RPC_DEFAULT_MAX_SUBSCRIPTIONS: usize = 16;
+
+/// The default number of notifications buffered per RPC subscription
+pub const RPC_DEFAULT_SUBSCRIPTION_BUFFER_SIZE: usize = 64;
+
 /// The default port for the metrics server
 pub const METRICS_DEFAULT_PORT: u16 = 9100;
 
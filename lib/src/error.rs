@@ -14,6 +14,10 @@ pub enum Error {
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[cfg(feature = "full-consensus")]
+    #[error("Snapshot error: {0}")]
+    Snapshot(#[from] nimiq_blockchain::SnapshotError),
+
     #[error("Network error: {0}")]
     Network(#[from] nimiq_network_libp2p::NetworkError),
 
@@ -1,9 +1,20 @@
 use std::{collections::HashSet, iter::FromIterator, sync::Arc};
 
-use nimiq_rpc_server::dispatchers::*;
+use parking_lot::RwLock;
+
+use nimiq_rpc_server::{
+    dispatchers::*,
+    permissions::User,
+    subscriptions::{
+        is_subscription_method, SubscriptionDispatcher, SubscriptionLimits, SubscriptionRegistry,
+        UNSUBSCRIBE_METHOD,
+    },
+    wallets::UnlockedWallets,
+    Config, Server as _Server,
+};
 
 use nimiq_jsonrpc_core::Credentials;
-use nimiq_jsonrpc_server::{AllowListDispatcher, Config, ModularDispatcher, Server as _Server};
+use nimiq_jsonrpc_server::{AllowListDispatcher, Dispatcher, ModularDispatcher};
 
 use nimiq_wallet::WalletStore;
 
@@ -13,7 +24,8 @@ use crate::config::config::RpcServerConfig;
 use crate::config::consts::default_bind;
 use crate::error::Error;
 
-pub type Server = _Server<AllowListDispatcher<ModularDispatcher>>;
+pub type Server =
+    _Server<BatchDispatcher<SubscriptionDispatcher<AllowListDispatcher<ModularDispatcher>>>>;
 
 #[cfg(feature = "rpc-server")]
 pub fn initialize_rpc_server(
@@ -22,13 +34,37 @@ pub fn initialize_rpc_server(
     wallet_store: Arc<WalletStore>,
 ) -> Result<Server, Error> {
     let ip = config.bind_to.unwrap_or_else(default_bind);
-    log::info!("Initializing RPC server: {}:{}", ip, config.port);
+    log::info!(
+        websocket = config.enable_websocket,
+        "Initializing RPC server: {}:{}",
+        ip,
+        config.port
+    );
 
     // Configure RPC server
-    let basic_auth = config.credentials.map(|credentials| Credentials {
-        username: credentials.username,
-        password: credentials.password,
-    });
+    // The user configured by `username` and `password` can call all methods, while the
+    // additional users are restricted to the methods of their role.
+    let mut users: Vec<User> = config
+        .credentials
+        .map(|credentials| User {
+            credentials: Credentials {
+                username: credentials.username,
+                password: credentials.password,
+            },
+            allowed_methods: None,
+        })
+        .into_iter()
+        .collect();
+    for user in &config.users {
+        log::info!(username = %user.credentials.username, role = %user.role, "Adding RPC user");
+        users.push(User {
+            credentials: Credentials {
+                username: user.credentials.username.clone(),
+                password: user.credentials.password.clone(),
+            },
+            allowed_methods: Some(user.allowed_methods.clone()),
+        });
+    }
 
     let allowed_methods = config.allowed_methods.unwrap_or_default();
     let allowed_methods = if allowed_methods.is_empty() {
@@ -37,39 +73,124 @@ pub fn initialize_rpc_server(
         Some(HashSet::from_iter(allowed_methods))
     };
 
-    // TODO: Pass this to the rpc server config
-    let _corsdomain = config.corsdomain.unwrap_or_default();
+    // Without any configured domain, no CORS headers are sent and browsers will reject
+    // cross-origin requests.
+    let cors_origins = config.corsdomain.unwrap_or_default();
+
+    // Requests from IPs outside of the allowed subnets are rejected before being dispatched.
+    let ip_whitelist = config.allow_ips;
+    if let Some(ip_whitelist) = &ip_whitelist {
+        log::info!(?ip_whitelist, "Restricting RPC server access");
+    }
+
+    let subscription_limits = SubscriptionLimits {
+        max_subscriptions: config.max_subscriptions,
+        buffer_size: config.subscription_buffer_size,
+    };
+
+    // The server dispatches requests concurrently using several dispatchers, which share the
+    // unlocked wallets and the subscriptions.
+    let unlocked_wallets = Arc::new(RwLock::new(UnlockedWallets::default()));
+    let subscriptions = SubscriptionRegistry::default();
+    let blockchain = client.blockchain();
+    let consensus_proxy = client.consensus_proxy();
+    let network = client.network();
+    let mempool = client.mempool();
+    let validator_proxy = client.validator_proxy();
+    let zkp_component = client.zkp_component();
 
-    let mut dispatcher = ModularDispatcher::default();
+    // Batches consisting of methods that only read the blockchain are evaluated against a
+    // single blockchain state.
+    let blockchain_methods: Arc<HashSet<String>> = Arc::new(
+        BlockchainDispatcher::new(blockchain.clone())
+            .method_names()
+            .into_iter()
+            .chain(PolicyDispatcher {}.method_names())
+            .filter(|method| !is_subscription_method(method))
+            .map(str::to_owned)
+            .collect(),
+    );
 
-    let wallet_dispatcher = WalletDispatcher::new(wallet_store);
-    let unlocked_wallets = Arc::clone(&wallet_dispatcher.unlocked_wallets);
+    let batch_blockchain = blockchain.clone();
+    let new_modular_dispatcher = move || {
+        let mut dispatcher = ModularDispatcher::default();
 
-    dispatcher.add(BlockchainDispatcher::new(client.blockchain()));
+        dispatcher.add(BlockchainDispatcher::new(blockchain.clone()));
 
-    dispatcher.add(ConsensusDispatcher::new(
-        client.consensus_proxy(),
-        Some(unlocked_wallets),
-    ));
-    dispatcher.add(NetworkDispatcher::new(client.network()));
-    if let Some(mempool) = client.mempool() {
-        dispatcher.add(MempoolDispatcher::new(mempool));
+        dispatcher.add(ConsensusDispatcher::new(
+            consensus_proxy.clone(),
+            Some(Arc::clone(&unlocked_wallets)),
+        ));
+        dispatcher.add(NetworkDispatcher::new(Arc::clone(&network)));
+        if let Some(mempool) = &mempool {
+            dispatcher.add(MempoolDispatcher::new(Arc::clone(mempool)));
+        }
+        dispatcher.add(PolicyDispatcher {});
+        if let Some(validator_proxy) = &validator_proxy {
+            dispatcher.add(ValidatorDispatcher::new(validator_proxy.clone()));
+        }
+        dispatcher.add(WalletDispatcher::with_unlocked_wallets(
+            Arc::clone(&wallet_store),
+            Arc::clone(&unlocked_wallets),
+        ));
+
+        dispatcher.add(ZKPComponentDispatcher::new(zkp_component.clone()));
+
+        dispatcher
+    };
+
+    // The method names in the configuration are checked against the methods the dispatchers
+    // actually provide, such that a typo fails loudly instead of being ignored.
+    let mut known_methods: HashSet<String> = new_modular_dispatcher()
+        .method_names()
+        .into_iter()
+        .map(str::to_owned)
+        .collect();
+    known_methods.insert(UNSUBSCRIBE_METHOD.to_owned());
+    let check_method = |method: &String, context: String| {
+        if known_methods.contains(method) {
+            Ok(())
+        } else {
+            Err(Error::config_error(format!(
+                "Unknown RPC method {method} in {context}"
+            )))
+        }
+    };
+    for method in allowed_methods.iter().flatten() {
+        check_method(method, "the allowed methods".to_owned())?;
+    }
+    for user in &config.users {
+        for method in &user.allowed_methods {
+            check_method(method, format!("RPC role {}", user.role))?;
+        }
     }
-    dispatcher.add(PolicyDispatcher {});
-    if let Some(validator_proxy) = client.validator_proxy() {
-        dispatcher.add(ValidatorDispatcher::new(validator_proxy));
+    for limit in &config.rate_limits {
+        for method in &limit.methods {
+            check_method(method, "the RPC rate limits".to_owned())?;
+        }
     }
-    dispatcher.add(wallet_dispatcher);
 
-    dispatcher.add(ZKPComponentDispatcher::new(client.zkp_component()));
+    let new_dispatcher = move || {
+        BatchDispatcher::new(
+            SubscriptionDispatcher::new(
+                AllowListDispatcher::new(new_modular_dispatcher(), allowed_methods.clone()),
+                subscription_limits.clone(),
+                subscriptions.clone(),
+            ),
+            batch_blockchain.clone(),
+            Arc::clone(&blockchain_methods),
+        )
+    };
 
     Ok(Server::new(
         Config {
             bind_to: (config.bind_to.unwrap_or_else(default_bind), config.port).into(),
-            enable_websocket: false,
-            ip_whitelist: None,
-            basic_auth,
+            enable_websocket: config.enable_websocket,
+            ip_whitelist,
+            cors_origins,
+            users,
+            rate_limits: config.rate_limits,
         },
-        AllowListDispatcher::new(dispatcher, allowed_methods),
+        new_dispatcher,
     ))
 }
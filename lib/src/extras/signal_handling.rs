@@ -1,19 +1,40 @@
-use signal_hook::{consts::SIGINT, iterator::Signals};
+use parking_lot::{const_mutex, Mutex};
+use signal_hook::{
+    consts::{SIGINT, SIGTERM},
+    iterator::Signals,
+};
 use tokio::time::{sleep, Duration};
 
+type ShutdownHook = Box<dyn FnOnce() + Send>;
+
+/// Hooks that are run before the client exits.
+static SHUTDOWN_HOOKS: Mutex<Vec<ShutdownHook>> = const_mutex(Vec::new());
+
+/// Registers a function that is run when the client is closed with Ctrl+C or terminated.
+pub fn register_shutdown_hook<F: FnOnce() + Send + 'static>(hook: F) {
+    SHUTDOWN_HOOKS.lock().push(Box::new(hook));
+}
+
 pub fn initialize_signal_handler() {
-    let signals = Signals::new([SIGINT]);
+    let signals = Signals::new([SIGINT, SIGTERM]);
 
     if let Ok(mut signals) = signals {
         tokio::spawn(async move {
-            for _ in signals.forever() {
-                log::warn!("Received Ctrl+C. Closing client");
+            for signal in signals.forever() {
+                if signal == SIGTERM {
+                    log::warn!("Received SIGTERM. Closing client");
+                } else {
+                    log::warn!("Received Ctrl+C. Closing client");
+                }
+                for hook in SHUTDOWN_HOOKS.lock().drain(..) {
+                    hook();
+                }
                 // Add some delay for the log message to propagate into loki
                 sleep(Duration::from_millis(200)).await;
                 std::process::exit(0);
             }
         });
     } else {
-        log::error!("Could not obtain SIGINT signal");
+        log::error!("Could not obtain SIGINT and SIGTERM signals");
     }
 }
@@ -113,3 +113,188 @@ fn config_file_partial_db_entry() {
 
     assert_eq!(config.storage, db_config.into());
 }
+
+#[cfg(feature = "rpc-server")]
+#[test]
+fn config_file_rpc_server_allowip_and_corsdomain() {
+    let config_file: ConfigFile = toml::from_str(
+        r#"
+    [rpc-server]
+    allowip = ["127.0.0.1", "10.0.0.0/8", "fd00::/8"]
+    corsdomain = ["https://dashboard.example.com", "*"]
+    "#,
+    )
+    .unwrap();
+
+    let mut config_builder = ClientConfigBuilder::default();
+    config_builder.config_file(&config_file).unwrap();
+    let config = config_builder.build().unwrap();
+
+    let rpc_server = config.rpc_server.unwrap();
+    assert_eq!(
+        rpc_server
+            .allow_ips
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec!["127.0.0.1/32", "10.0.0.0/8", "fd00::/8"]
+    );
+    assert_eq!(
+        rpc_server.corsdomain.unwrap(),
+        vec!["https://dashboard.example.com", "*"]
+    );
+
+    // Invalid subnet
+    let config_file: ConfigFile = toml::from_str(
+        r#"
+    [rpc-server]
+    allowip = ["10.0.0.0/33"]
+    "#,
+    )
+    .unwrap();
+    assert!(ClientConfigBuilder::default()
+        .config_file(&config_file)
+        .is_err());
+
+    // Invalid CORS domain
+    let config_file: ConfigFile = toml::from_str(
+        r#"
+    [rpc-server]
+    corsdomain = ["example.com"]
+    "#,
+    )
+    .unwrap();
+    assert!(ClientConfigBuilder::default()
+        .config_file(&config_file)
+        .is_err());
+}
+
+#[cfg(feature = "rpc-server")]
+#[test]
+fn config_file_rpc_server_rate_limits() {
+    let config_file: ConfigFile = toml::from_str(
+        r#"
+    [rpc-server]
+
+    [[rpc-server.rate_limits]]
+    methods = ["getTransactionsByAddress"]
+    burst = 5
+    per_second = 0.5
+
+    [[rpc-server.rate_limits]]
+    burst = 100
+    per_second = 20
+    "#,
+    )
+    .unwrap();
+
+    let mut config_builder = ClientConfigBuilder::default();
+    config_builder.config_file(&config_file).unwrap();
+    let config = config_builder.build().unwrap();
+
+    let rate_limits = config.rpc_server.unwrap().rate_limits;
+    assert_eq!(rate_limits.len(), 2);
+    assert_eq!(rate_limits[0].methods, vec!["getTransactionsByAddress"]);
+    assert_eq!(rate_limits[0].burst, 5);
+    assert_eq!(rate_limits[1].per_second, 20.0);
+
+    // Zero rates are rejected
+    let config_file: ConfigFile = toml::from_str(
+        r#"
+    [rpc-server]
+
+    [[rpc-server.rate_limits]]
+    burst = 10
+    per_second = 0
+    "#,
+    )
+    .unwrap();
+    assert!(ClientConfigBuilder::default()
+        .config_file(&config_file)
+        .is_err());
+}
+
+#[cfg(feature = "rpc-server")]
+#[test]
+fn config_file_rpc_server_users_and_roles() {
+    let config_file: ConfigFile = toml::from_str(
+        r#"
+    [rpc-server]
+    username = "admin"
+    password = "secret"
+
+    [rpc-server.roles]
+    read-only = ["getBlockNumber", "getAccountByAddress"]
+
+    [[rpc-server.users]]
+    username = "monitoring"
+    password = "another-secret"
+    role = "read-only"
+    "#,
+    )
+    .unwrap();
+
+    let mut config_builder = ClientConfigBuilder::default();
+    config_builder.config_file(&config_file).unwrap();
+    let config = config_builder.build().unwrap();
+
+    let users = config.rpc_server.unwrap().users;
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].credentials.username, "monitoring");
+    assert_eq!(users[0].role, "read-only");
+    assert!(users[0].allowed_methods.contains("getBlockNumber"));
+    assert!(!users[0].allowed_methods.contains("unlockAccount"));
+
+    // Unknown role
+    let config_file: ConfigFile = toml::from_str(
+        r#"
+    [[rpc-server.users]]
+    username = "monitoring"
+    password = "another-secret"
+    role = "read-only"
+    "#,
+    )
+    .unwrap();
+    assert!(ClientConfigBuilder::default()
+        .config_file(&config_file)
+        .is_err());
+
+    // Unknown method in role
+    let config_file: ConfigFile = toml::from_str(
+        r#"
+    [rpc-server.roles]
+    read-only = ["getBlockNumber", "getBlockNumbr"]
+
+    [[rpc-server.users]]
+    username = "monitoring"
+    password = "another-secret"
+    role = "read-only"
+    "#,
+    )
+    .unwrap();
+    assert!(ClientConfigBuilder::default()
+        .config_file(&config_file)
+        .is_err());
+
+    // Duplicate user
+    let config_file: ConfigFile = toml::from_str(
+        r#"
+    [rpc-server]
+    username = "monitoring"
+    password = "secret"
+
+    [rpc-server.roles]
+    read-only = ["getBlockNumber"]
+
+    [[rpc-server.users]]
+    username = "monitoring"
+    password = "another-secret"
+    role = "read-only"
+    "#,
+    )
+    .unwrap();
+    assert!(ClientConfigBuilder::default()
+        .config_file(&config_file)
+        .is_err());
+}
@@ -1,3 +1,5 @@
+use std::{path::PathBuf, time::Duration};
+
 use crate::filter::{MempoolFilter, MempoolRules};
 use crate::mempool::Mempool;
 
@@ -12,6 +14,16 @@ pub struct MempoolConfig {
     pub filter_rules: MempoolRules,
     /// Mempool filter limit or size
     pub filter_limit: usize,
+    /// Minimum increase of the fee per byte for a transaction to replace a pending one
+    pub replacement_fee_increment: f64,
+    /// Whether transactions that spend funds the sender receives from other pending
+    /// transactions are accepted
+    pub allow_dependent_transactions: bool,
+    /// File the pending transactions are persisted to, such that they survive a restart.
+    /// Persistence is disabled if this is `None`.
+    pub persistence_file: Option<PathBuf>,
+    /// Interval in which the pending transactions are persisted
+    pub persistence_interval: Duration,
 }
 
 impl Default for MempoolConfig {
@@ -21,6 +33,10 @@ impl Default for MempoolConfig {
             control_size_limit: Mempool::DEFAULT_CONTROL_SIZE_LIMIT,
             filter_rules: MempoolRules::default(),
             filter_limit: MempoolFilter::DEFAULT_BLACKLIST_SIZE,
+            replacement_fee_increment: Mempool::DEFAULT_REPLACEMENT_FEE_INCREMENT,
+            allow_dependent_transactions: false,
+            persistence_file: None,
+            persistence_interval: Mempool::DEFAULT_PERSISTENCE_INTERVAL,
         }
     }
 }
@@ -0,0 +1,94 @@
+use nimiq_primitives::coin::Coin;
+
+/// Number of recent blocks that are considered to determine how congested the network is.
+pub const FEE_ESTIMATION_BLOCKS: u32 = 10;
+
+/// If the recent blocks were filled above this ratio, the network is considered congested.
+const CONGESTION_FILL_RATIO: f64 = 0.9;
+
+/// A suggested fee for a transaction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeEstimate {
+    /// The suggested fee per byte.
+    pub fee_per_byte: f64,
+    /// The suggested total fee of the transaction.
+    pub fee: Coin,
+}
+
+/// How full the recent blocks were.
+#[derive(Clone, Debug, Default)]
+pub struct RecentBlocks {
+    /// Number of bytes used by transactions in the recent blocks.
+    pub used_bytes: usize,
+    /// Number of bytes available for transactions in the recent blocks.
+    pub available_bytes: usize,
+    /// The lowest fee per byte of the transactions included in the recent blocks.
+    pub min_fee_per_byte: Option<f64>,
+}
+
+impl RecentBlocks {
+    /// Returns the ratio of the space in the recent blocks that was used by transactions.
+    pub fn fill_ratio(&self) -> f64 {
+        if self.available_bytes == 0 {
+            0.0
+        } else {
+            self.used_bytes as f64 / self.available_bytes as f64
+        }
+    }
+}
+
+/// Estimates the fee a transaction of the given size needs to pay to be included within
+/// `target_blocks` blocks.
+///
+/// `pending` are the fee per byte and size of the transactions in the mempool, in the order in
+/// which they are included in blocks. `reserved_bytes` is the space taken up by transactions
+/// that are included before any of them (i.e. control transactions). The transaction needs to
+/// outbid the first pending transaction that would no longer fit into the target blocks
+/// together with it. If the recent blocks were (almost) full, the network is congested and the
+/// mempool might not reflect the competition yet, so the transaction also needs to outbid the
+/// cheapest transaction that was recently included. The fee is capped at the maximum amount of
+/// coins.
+pub fn estimate_fee(
+    pending: impl IntoIterator<Item = (f64, usize)>,
+    reserved_bytes: usize,
+    block_size: usize,
+    recent_blocks: &RecentBlocks,
+    min_fee_per_byte: f64,
+    tx_size: usize,
+    target_blocks: u32,
+) -> FeeEstimate {
+    // We need to account for one extra byte per transaction to encode its execution status.
+    let space = (target_blocks as usize)
+        .saturating_mul(block_size)
+        .saturating_sub(reserved_bytes)
+        .saturating_sub(tx_size.saturating_add(1));
+
+    // The fee needs to be at least the minimum fee.
+    let mut fee = (min_fee_per_byte * tx_size as f64).ceil() as u64;
+
+    let mut used_bytes = 0;
+    for (fee_per_byte, size) in pending {
+        used_bytes += size + 1;
+        if used_bytes > space {
+            fee = fee.max(outbid(fee_per_byte, tx_size));
+            break;
+        }
+    }
+
+    if recent_blocks.fill_ratio() >= CONGESTION_FILL_RATIO {
+        if let Some(fee_per_byte) = recent_blocks.min_fee_per_byte {
+            fee = fee.max(outbid(fee_per_byte, tx_size));
+        }
+    }
+
+    let fee = fee.min(Coin::MAX_SAFE_VALUE);
+    FeeEstimate {
+        fee_per_byte: fee as f64 / tx_size as f64,
+        fee: Coin::from_u64_unchecked(fee),
+    }
+}
+
+/// Returns the lowest fee for a transaction of the given size that pays strictly more per byte.
+fn outbid(fee_per_byte: f64, tx_size: usize) -> u64 {
+    ((fee_per_byte * tx_size as f64).floor() as u64).saturating_add(1)
+}
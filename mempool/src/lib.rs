@@ -14,15 +14,23 @@ mod mempool_state;
 pub mod config;
 /// Mempool executor module
 pub mod executor;
+/// Fee estimation module
+pub mod fee_estimation;
 
 /// Mempool filter module
 pub mod filter;
 /// Main mempool module
 pub mod mempool;
+/// Mempool transaction dependencies module
+mod mempool_dependencies;
+/// Mempool events module
+pub mod mempool_events;
 /// Mempool metrics
 #[cfg(feature = "metrics")]
 mod mempool_metrics;
 /// Mempool transaction module
 pub mod mempool_transactions;
+/// Mempool persistence module
+pub mod persistence;
 /// Verify transaction module
 pub mod verify;
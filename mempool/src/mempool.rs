@@ -3,13 +3,16 @@ use futures::lock::{Mutex, MutexGuard};
 use futures::stream::BoxStream;
 use parking_lot::RwLock;
 use std::collections::HashSet;
-use std::sync::atomic::AtomicU32;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::{channel as broadcast, Receiver as BroadcastReceiver};
 use tokio_metrics::TaskMonitor;
 
 use beserial::Serialize;
 use nimiq_account::ReservedBalance;
-use nimiq_block::Block;
+use nimiq_block::{Block, MicroBlock};
 use nimiq_blockchain::{Blockchain, TransactionVerificationCache};
 use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_hash::{Blake2bHash, Hash};
@@ -19,11 +22,15 @@ use nimiq_transaction::{ControlTransactionTopic, Transaction, TransactionTopic};
 
 use crate::config::MempoolConfig;
 use crate::executor::MempoolExecutor;
+use crate::fee_estimation::{estimate_fee, FeeEstimate, RecentBlocks, FEE_ESTIMATION_BLOCKS};
 use crate::filter::{MempoolFilter, MempoolRules};
+use crate::mempool_dependencies::TransactionDependencies;
+use crate::mempool_events::{MempoolEvent, MEMPOOL_EVENTS_CAPACITY};
 #[cfg(feature = "metrics")]
 use crate::mempool_metrics::MempoolMetrics;
 use crate::mempool_state::{EvictionReason, MempoolState};
 use crate::mempool_transactions::{MempoolTransactions, TxPriority};
+use crate::persistence::{MempoolPersistenceError, MempoolSnapshot};
 use crate::verify::{verify_tx, VerifyErr};
 
 /// Struct defining the Mempool
@@ -45,6 +52,23 @@ pub struct Mempool {
 
     /// Total number of ongoing verification tasks
     verification_tasks: Arc<AtomicU32>,
+
+    /// File the pending transactions are persisted to
+    persistence_file: Option<PathBuf>,
+
+    /// Interval in which the pending transactions are persisted
+    persistence_interval: Duration,
+
+    /// Handle used to stop the task that periodically persists the pending transactions
+    persistence_handle: Mutex<Option<AbortHandle>>,
+
+    /// Whether restoring the persisted transactions has been started already
+    snapshot_restoring: AtomicBool,
+
+    /// Whether restoring the persisted transactions has finished. Until then, the pending
+    /// transactions aren't persisted, such that the snapshot isn't overwritten before it was
+    /// restored.
+    snapshot_restored: Arc<AtomicBool>,
 }
 
 impl Mempool {
@@ -54,11 +78,21 @@ impl Mempool {
     /// Default total size limit of control transactions in the mempool (bytes)
     pub const DEFAULT_CONTROL_SIZE_LIMIT: usize = 6_000_000;
 
+    /// Default minimum increase of the fee per byte for a transaction to replace a pending one
+    pub const DEFAULT_REPLACEMENT_FEE_INCREMENT: f64 = 1.0;
+
+    /// Default interval in which the pending transactions are persisted
+    pub const DEFAULT_PERSISTENCE_INTERVAL: Duration = Duration::from_secs(60);
+
     /// Creates a new mempool
     pub fn new(blockchain: Arc<RwLock<Blockchain>>, config: MempoolConfig) -> Self {
+        let (events, _) = broadcast(MEMPOOL_EVENTS_CAPACITY);
         let state = Arc::new(RwLock::new(MempoolState::new(
             config.size_limit,
             config.control_size_limit,
+            config.replacement_fee_increment,
+            config.allow_dependent_transactions,
+            events,
         )));
 
         Self {
@@ -71,6 +105,11 @@ impl Mempool {
             executor_handle: Mutex::new(None),
             control_executor_handle: Mutex::new(None),
             verification_tasks: Arc::new(AtomicU32::new(0)),
+            persistence_file: config.persistence_file,
+            persistence_interval: config.persistence_interval,
+            persistence_handle: Mutex::new(None),
+            snapshot_restoring: AtomicBool::new(false),
+            snapshot_restored: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -155,6 +194,50 @@ impl Mempool {
             control_executor_handle,
             txn_stream,
         );
+
+        self.start_persistence().await;
+    }
+
+    /// Spawns a task that periodically persists the pending transactions, if persistence is
+    /// enabled.
+    async fn start_persistence(&self) {
+        let path = match &self.persistence_file {
+            Some(path) => path.clone(),
+            None => return,
+        };
+
+        let mut handle = self.persistence_handle.lock().await;
+        if handle.is_some() {
+            return;
+        }
+
+        let state = Arc::clone(&self.state);
+        let snapshot_restored = Arc::clone(&self.snapshot_restored);
+        let mut interval = tokio::time::interval(self.persistence_interval);
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let future = async move {
+            // The first tick completes immediately.
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+
+                // Don't overwrite the snapshot before it was restored.
+                if !snapshot_restored.load(Ordering::Acquire) {
+                    continue;
+                }
+
+                let snapshot = MempoolSnapshot::from_state(&state.read());
+                let path = path.clone();
+                match tokio::task::spawn_blocking(move || snapshot.save(&path)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(error)) => error!(%error, "Failed to persist mempool"),
+                    Err(error) => error!(%error, "Mempool persistence task failed"),
+                }
+            }
+        };
+        tokio::spawn(Abortable::new(future, abort_registration));
+
+        *handle = Some(abort_handle);
     }
 
     /// Starts the mempool executor with a custom transaction stream
@@ -222,6 +305,102 @@ impl Mempool {
             .take()
             .expect("Expected a control executor handle")
             .abort();
+
+        // Stop persisting periodically and persist the transactions one last time
+        if let Some(handle) = self.persistence_handle.lock().await.take() {
+            handle.abort();
+        }
+        if let Err(error) = self.save_snapshot() {
+            error!(%error, "Failed to persist mempool");
+        }
+    }
+
+    /// Persists the pending transactions to the configured persistence file. Does nothing if
+    /// persistence is disabled or if the persisted transactions haven't been restored yet, such
+    /// that a snapshot isn't overwritten before it was restored.
+    ///
+    /// This should be called when the node shuts down.
+    pub fn save_snapshot(&self) -> Result<(), MempoolPersistenceError> {
+        let path = match &self.persistence_file {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        if !self.snapshot_restored.load(Ordering::Acquire) {
+            debug!("Not persisting mempool before the persisted transactions were restored");
+            return Ok(());
+        }
+
+        let snapshot = MempoolSnapshot::from_state(&self.state.read());
+        snapshot.save(path)?;
+        debug!(
+            num_transactions = snapshot.len(),
+            path = %path.display(),
+            "Persisted mempool"
+        );
+        Ok(())
+    }
+
+    /// Restores the transactions from the configured persistence file, if persistence is
+    /// enabled and the file exists.
+    ///
+    /// Every transaction is verified against the current head of the blockchain the same way
+    /// transactions received from the network are. Transactions that expired, were included in
+    /// the meantime or became invalid otherwise are dropped. Returns the number of transactions
+    /// that were restored.
+    ///
+    /// The snapshot is only restored once and deleted afterwards, such that it isn't restored
+    /// again when consensus is re-established. The pending transactions are only persisted
+    /// once the snapshot was restored.
+    pub async fn restore_snapshot(&self) -> Result<usize, MempoolPersistenceError> {
+        let path = match &self.persistence_file {
+            Some(path) => path,
+            None => return Ok(0),
+        };
+
+        if self.snapshot_restoring.swap(true, Ordering::AcqRel) {
+            return Ok(0);
+        }
+
+        let result = self.restore_snapshot_from(path).await;
+        self.snapshot_restored.store(true, Ordering::Release);
+        result
+    }
+
+    async fn restore_snapshot_from(&self, path: &Path) -> Result<usize, MempoolPersistenceError> {
+        let snapshot = match MempoolSnapshot::load(path)? {
+            Some(snapshot) => snapshot,
+            None => return Ok(0),
+        };
+        let num_persisted = snapshot.len();
+
+        let mut num_restored = 0;
+        for persisted in snapshot
+            .control_transactions
+            .into_iter()
+            .chain(snapshot.regular_transactions)
+        {
+            let tx_hash = persisted.transaction.hash::<Blake2bHash>();
+            match self
+                .add_transaction(persisted.transaction, Some(persisted.priority))
+                .await
+            {
+                Ok(()) => num_restored += 1,
+                Err(error) => debug!(%tx_hash, %error, "Dropping persisted transaction"),
+            }
+        }
+
+        // Only delete the snapshot once all transactions were restored, such that they aren't
+        // lost if the node stops in the meantime.
+        MempoolSnapshot::delete(path)?;
+
+        info!(
+            num_restored,
+            num_dropped = num_persisted - num_restored,
+            path = %path.display(),
+            "Restored persisted mempool"
+        );
+        Ok(num_restored)
     }
 
     /// Stops the mempool executor without TX stream
@@ -327,12 +506,22 @@ impl Mempool {
         //    else
         //      we don't care, since it won't affect our senders balance
         let mut affected_senders = HashSet::new();
-        for (_, block) in adopted_blocks {
+        for (block_hash, block) in adopted_blocks {
             if let Some(transactions) = block.transactions() {
                 for tx in transactions {
                     let tx = tx.get_raw_transaction();
                     let tx_hash = tx.hash();
 
+                    mempool_state.notify(|| MempoolEvent::TransactionMined {
+                        transaction: tx.clone(),
+                        block_hash: block_hash.clone(),
+                        block_number: block.block_number(),
+                        timestamp: block.timestamp(),
+                    });
+
+                    // Transactions that depend on this one don't need to wait for it anymore.
+                    mempool_state.dependencies.unlink_children(&tx_hash);
+
                     // Check if we already know this transaction. If yes, a known transaction was
                     // mined so we need to remove it from the mempool.
                     if mempool_state.contains(&tx_hash) {
@@ -460,17 +649,29 @@ impl Mempool {
             //  keep the ones with higher fee
 
             sender_state.txns.retain(|tx_hash| {
+                // The transactions this one depends on are determined anew.
+                mempool_state.dependencies.unlink_parents(tx_hash);
+
                 let tx = match mempool_state.get(tx_hash) {
                     Some(transaction) => transaction,
                     None => return false,
                 };
-                let still_valid = blockchain
-                    .reserve_balance(&sender_account, tx, &mut sender_state.reserved_balance)
-                    .is_ok();
-                if !still_valid {
-                    mempool_state.remove(blockchain, tx_hash, EvictionReason::Invalid);
+                match mempool_state.dependencies.reserve_balance(
+                    blockchain,
+                    &mempool_state.regular_transactions,
+                    &sender_account,
+                    tx,
+                    &mut sender_state.reserved_balance,
+                ) {
+                    Ok(parents) => {
+                        mempool_state.dependencies.link(tx_hash, parents);
+                        true
+                    }
+                    Err(_) => {
+                        mempool_state.remove(blockchain, tx_hash, EvictionReason::Invalid);
+                        false
+                    }
                 }
-                still_valid
             });
 
             if !sender_state.txns.is_empty() {
@@ -514,8 +715,12 @@ impl Mempool {
         max_bytes: usize,
     ) -> (Vec<Transaction>, usize) {
         let mut state = self.state.write();
-        let (txs, size) =
-            Self::get_transactions_for_block_impl(&mut state.regular_transactions, max_bytes);
+        let state = &mut *state;
+        let (txs, size) = Self::get_transactions_for_block_impl(
+            &mut state.regular_transactions,
+            &state.dependencies,
+            max_bytes,
+        );
 
         for tx in &txs {
             state.remove(blockchain, &tx.hash(), EvictionReason::BlockBuilding);
@@ -555,8 +760,12 @@ impl Mempool {
         max_bytes: usize,
     ) -> (Vec<Transaction>, usize) {
         let mut state = self.state.write();
-        let (txs, size) =
-            Self::get_transactions_for_block_impl(&mut state.control_transactions, max_bytes);
+        let state = &mut *state;
+        let (txs, size) = Self::get_transactions_for_block_impl(
+            &mut state.control_transactions,
+            &state.dependencies,
+            max_bytes,
+        );
 
         for tx in &txs {
             state.remove(blockchain, &tx.hash(), EvictionReason::BlockBuilding);
@@ -573,9 +782,12 @@ impl Mempool {
 
     fn get_transactions_for_block_impl(
         transactions: &mut MempoolTransactions,
+        dependencies: &TransactionDependencies,
         max_bytes: usize,
     ) -> (Vec<Transaction>, usize) {
         let mut txs = vec![];
+        let mut included = HashSet::new();
+        let mut deferred = vec![];
         let mut size = 0_usize;
 
         loop {
@@ -585,24 +797,42 @@ impl Mempool {
                 Some((tx_hash, _)) => tx_hash.clone(),
             };
 
-            // Get the transaction.
-            let tx = transactions.get(&tx_hash).unwrap().clone();
+            // Transactions that depend on other pending transactions are packed together with
+            // them, parents first.
+            let package = match dependencies.package(&tx_hash, transactions, &included) {
+                Some(package) => package,
+                None => {
+                    // The transaction depends on one that isn't included in a block yet.
+                    deferred.push(transactions.best_transactions.pop().unwrap());
+                    continue;
+                }
+            };
 
-            // Calculate size. If we can't fit the transaction in the block, then we stop here.
+            // Calculate size. If we can't fit the transactions in the block, then we stop here.
             // TODO: We can optimize this. There might be a smaller transaction that still fits.
             // We need to account for one extra byte per transaction to encode its final execution status
-            let next_size = size + 1 + tx.serialized_size();
+            let next_size = package.iter().fold(size, |size, tx_hash| {
+                size + 1 + transactions.get(tx_hash).unwrap().serialized_size()
+            });
             if next_size > max_bytes {
                 break;
             }
             size = next_size;
 
-            // Remove the transaction from best_transactions so that we can advance.
-            // The caller needs to clean up the rest of the data structures.
-            transactions.best_transactions.pop();
+            for tx_hash in package {
+                // Remove the transaction from best_transactions so that we can advance.
+                // The caller needs to clean up the rest of the data structures.
+                transactions.best_transactions.remove(&tx_hash);
 
-            // Push the transaction to our output vector.
-            txs.push(tx);
+                // Push the transaction to our output vector.
+                txs.push(transactions.get(&tx_hash).unwrap().clone());
+                included.insert(tx_hash);
+            }
+        }
+
+        // The deferred transactions stay in the mempool.
+        for (tx_hash, order) in deferred {
+            transactions.best_transactions.push(tx_hash, order);
         }
 
         (txs, size)
@@ -629,6 +859,12 @@ impl Mempool {
         .await
     }
 
+    /// Subscribes to the events of transactions being added to, evicted from or mined out of the
+    /// mempool.
+    pub fn subscribe_events(&self) -> BroadcastReceiver<MempoolEvent> {
+        self.state.read().events.subscribe()
+    }
+
     /// Checks whether a transaction has been filtered
     pub fn is_filtered(&self, hash: &Blake2bHash) -> bool {
         self.filter.read().blacklisted(hash)
@@ -639,6 +875,57 @@ impl Mempool {
         self.filter.read().rules.clone()
     }
 
+    /// Estimates the fee a regular transaction of the given size (in bytes) needs to pay to be
+    /// included within the next `target_blocks` blocks.
+    ///
+    /// The estimate is based on the transactions currently in the mempool, in the order in which
+    /// they are included in blocks, and on how full the recent blocks were.
+    pub fn estimate_fee(&self, tx_size: usize, target_blocks: u32) -> FeeEstimate {
+        let block_size = MicroBlock::get_available_bytes(0);
+        let recent_blocks = self.recent_blocks(block_size);
+        let min_fee_per_byte = self.get_rules().tx_fee_per_byte;
+
+        let state = self.state.read();
+        estimate_fee(
+            state.regular_transactions.fees_in_block_order(),
+            state.control_transactions.total_size + state.control_transactions.len(),
+            block_size,
+            &recent_blocks,
+            min_fee_per_byte,
+            tx_size,
+            target_blocks,
+        )
+    }
+
+    /// Collects how full the recent micro blocks were.
+    fn recent_blocks(&self, block_size: usize) -> RecentBlocks {
+        let blockchain = self.blockchain.read();
+        let head_number = blockchain.block_number();
+        let first_number = head_number.saturating_sub(FEE_ESTIMATION_BLOCKS - 1).max(1);
+
+        let mut recent_blocks = RecentBlocks::default();
+        for block_number in first_number..=head_number {
+            // Macro blocks and skip blocks can't contain transactions.
+            let block = match blockchain.get_block_at(block_number, true, None) {
+                Ok(Block::Micro(block)) if !block.is_skip_block() => block,
+                _ => continue,
+            };
+
+            recent_blocks.available_bytes += block_size;
+            for tx in block.body.iter().flat_map(|body| &body.transactions) {
+                let tx = tx.get_raw_transaction();
+                recent_blocks.used_bytes += tx.serialized_size() + 1;
+                recent_blocks.min_fee_per_byte = Some(
+                    recent_blocks
+                        .min_fee_per_byte
+                        .map_or(tx.fee_per_byte(), |min| min.min(tx.fee_per_byte())),
+                );
+            }
+        }
+
+        recent_blocks
+    }
+
     /// Checks if a transactions is in the mempool, by its hash.
     pub fn contains_transaction_by_hash(&self, hash: &Blake2bHash) -> bool {
         self.state.read().contains(hash)
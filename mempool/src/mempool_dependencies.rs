@@ -0,0 +1,207 @@
+use std::collections::{HashMap, HashSet};
+
+use nimiq_account::{Account, BasicAccount, ReservedBalance};
+use nimiq_blockchain::Blockchain;
+use nimiq_hash::Blake2bHash;
+use nimiq_primitives::account::AccountType;
+use nimiq_transaction::Transaction;
+
+use crate::mempool_transactions::MempoolTransactions;
+use crate::verify::VerifyErr;
+
+/// Maximum number of pending ancestors a transaction can depend on.
+pub(crate) const MAX_ANCESTORS: usize = 25;
+
+/// Keeps track of pending transactions that spend funds their sender receives from other
+/// pending transactions. Dependencies are only tracked between regular transactions.
+pub(crate) struct TransactionDependencies {
+    // Whether transactions are allowed to depend on other pending transactions.
+    pub(crate) enabled: bool,
+
+    // The pending transactions each transaction depends on.
+    parents: HashMap<Blake2bHash, HashSet<Blake2bHash>>,
+
+    // The pending transactions that depend on each transaction.
+    children: HashMap<Blake2bHash, HashSet<Blake2bHash>>,
+}
+
+impl TransactionDependencies {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            parents: HashMap::new(),
+            children: HashMap::new(),
+        }
+    }
+
+    /// Reserves the balance necessary for the given transaction on the sender account.
+    ///
+    /// If the committed balance of the sender is insufficient and dependencies are enabled, the
+    /// funds the sender is going to receive from pending regular transactions are taken into
+    /// account as well. Only the pending transactions that are needed to cover the balance are
+    /// depended on, preferring the ones of the highest value. Returns the pending transactions
+    /// the given transaction depends on.
+    pub fn reserve_balance(
+        &self,
+        blockchain: &Blockchain,
+        regular_transactions: &MempoolTransactions,
+        sender_account: &Account,
+        tx: &Transaction,
+        reserved_balance: &mut ReservedBalance,
+    ) -> Result<HashSet<Blake2bHash>, VerifyErr> {
+        if blockchain
+            .reserve_balance(sender_account, tx, reserved_balance)
+            .is_ok()
+        {
+            return Ok(HashSet::new());
+        }
+
+        // Only regular transactions sent from basic accounts can depend on other transactions.
+        let balance = match sender_account {
+            Account::Basic(account) if self.enabled && !is_control_tx(tx) => account.balance,
+            _ => return Err(VerifyErr::InsufficientFunds),
+        };
+
+        // Depend on as few pending transactions as possible, starting with the ones that send
+        // the most, such that evicting unrelated transactions doesn't evict this one as well.
+        let mut incoming: Vec<_> = regular_transactions
+            .incoming(&tx.sender)
+            .filter_map(|hash| regular_transactions.get(hash).map(|parent| (hash, parent)))
+            .collect();
+        incoming.sort_by(|(hash_a, a), (hash_b, b)| b.value.cmp(&a.value).then(hash_a.cmp(hash_b)));
+
+        let mut parents = HashSet::new();
+        let mut funded_balance = balance;
+        for (hash, parent) in incoming {
+            funded_balance = funded_balance
+                .checked_add(parent.value)
+                .ok_or(VerifyErr::InsufficientFunds)?;
+            parents.insert(hash.clone());
+
+            let funded_account = Account::Basic(BasicAccount {
+                balance: funded_balance,
+            });
+            if blockchain
+                .reserve_balance(&funded_account, tx, reserved_balance)
+                .is_ok()
+            {
+                if self.num_ancestors(&parents) > MAX_ANCESTORS {
+                    return Err(VerifyErr::InsufficientFunds);
+                }
+                return Ok(parents);
+            }
+        }
+
+        Err(VerifyErr::InsufficientFunds)
+    }
+
+    /// Records that the given transaction depends on the given pending transactions.
+    pub fn link(&mut self, tx_hash: &Blake2bHash, parents: HashSet<Blake2bHash>) {
+        if parents.is_empty() {
+            return;
+        }
+        for parent in &parents {
+            self.children
+                .entry(parent.clone())
+                .or_default()
+                .insert(tx_hash.clone());
+        }
+        self.parents.insert(tx_hash.clone(), parents);
+    }
+
+    /// Forgets about the transactions the given transaction depends on.
+    pub fn unlink_parents(&mut self, tx_hash: &Blake2bHash) {
+        for parent in self.parents.remove(tx_hash).into_iter().flatten() {
+            if let Some(children) = self.children.get_mut(&parent) {
+                children.remove(tx_hash);
+                if children.is_empty() {
+                    self.children.remove(&parent);
+                }
+            }
+        }
+    }
+
+    /// Forgets about the transactions that depend on the given transaction. Returns the
+    /// dependent transactions.
+    pub fn unlink_children(&mut self, tx_hash: &Blake2bHash) -> HashSet<Blake2bHash> {
+        let children = self.children.remove(tx_hash).unwrap_or_default();
+        for child in &children {
+            if let Some(parents) = self.parents.get_mut(child) {
+                parents.remove(tx_hash);
+                if parents.is_empty() {
+                    self.parents.remove(child);
+                }
+            }
+        }
+        children
+    }
+
+    /// Checks whether any pending transactions depend on the given transaction.
+    pub fn has_children(&self, tx_hash: &Blake2bHash) -> bool {
+        self.children.contains_key(tx_hash)
+    }
+
+    /// Returns the transactions that need to be included in a block in order to include the
+    /// given transaction, parents before children and ending with the given transaction itself.
+    /// Transactions that are already `included` are skipped.
+    ///
+    /// Returns `None` if the transaction depends on a transaction that is neither pending nor
+    /// included, i.e. one that was taken out for a block that wasn't adopted (yet).
+    pub fn package(
+        &self,
+        tx_hash: &Blake2bHash,
+        transactions: &MempoolTransactions,
+        included: &HashSet<Blake2bHash>,
+    ) -> Option<Vec<Blake2bHash>> {
+        let mut package = vec![];
+        let mut visited = HashSet::new();
+        if self.collect_package(tx_hash, transactions, included, &mut visited, &mut package) {
+            Some(package)
+        } else {
+            None
+        }
+    }
+
+    fn collect_package(
+        &self,
+        tx_hash: &Blake2bHash,
+        transactions: &MempoolTransactions,
+        included: &HashSet<Blake2bHash>,
+        visited: &mut HashSet<Blake2bHash>,
+        package: &mut Vec<Blake2bHash>,
+    ) -> bool {
+        if included.contains(tx_hash) || !visited.insert(tx_hash.clone()) {
+            return true;
+        }
+        if !transactions.contains_key(tx_hash) {
+            return false;
+        }
+
+        for parent in self.parents.get(tx_hash).into_iter().flatten() {
+            if !self.collect_package(parent, transactions, included, visited, package) {
+                return false;
+            }
+        }
+        package.push(tx_hash.clone());
+        true
+    }
+
+    /// Counts the distinct ancestors of a transaction with the given parents.
+    fn num_ancestors(&self, parents: &HashSet<Blake2bHash>) -> usize {
+        let mut ancestors: HashSet<&Blake2bHash> = parents.iter().collect();
+        let mut queue: Vec<&Blake2bHash> = parents.iter().collect();
+        while let Some(tx_hash) = queue.pop() {
+            for parent in self.parents.get(tx_hash).into_iter().flatten() {
+                if ancestors.insert(parent) {
+                    queue.push(parent);
+                }
+            }
+        }
+        ancestors.len()
+    }
+}
+
+/// Checks whether the given transaction is a control transaction, i.e. a staking transaction.
+pub(crate) fn is_control_tx(tx: &Transaction) -> bool {
+    tx.sender_type == AccountType::Staking || tx.recipient_type == AccountType::Staking
+}
@@ -0,0 +1,55 @@
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_transaction::Transaction;
+
+pub use crate::mempool_state::EvictionReason;
+
+/// Capacity of the mempool event channel. Subscribers that fall behind by more than this many
+/// events miss events.
+pub const MEMPOOL_EVENTS_CAPACITY: usize = 1024;
+
+/// A change of the set of pending transactions.
+#[derive(Clone, Debug)]
+pub enum MempoolEvent {
+    /// A transaction was added to the mempool.
+    TransactionAdded(Transaction),
+    /// A transaction was removed from the mempool without being included in a block.
+    TransactionEvicted {
+        /// The evicted transaction
+        transaction: Transaction,
+        /// Why the transaction was evicted
+        reason: EvictionReason,
+    },
+    /// A transaction was included in a block that was adopted by the blockchain. This is
+    /// emitted for all transactions of adopted blocks, whether they were pending in this
+    /// mempool or not.
+    TransactionMined {
+        /// The included transaction
+        transaction: Transaction,
+        /// The hash of the block that includes the transaction
+        block_hash: Blake2bHash,
+        /// The number of the block that includes the transaction
+        block_number: u32,
+        /// The timestamp of the block that includes the transaction
+        timestamp: u64,
+    },
+}
+
+impl MempoolEvent {
+    /// Returns the transaction the event refers to.
+    pub fn transaction(&self) -> &Transaction {
+        match self {
+            MempoolEvent::TransactionAdded(transaction)
+            | MempoolEvent::TransactionEvicted { transaction, .. }
+            | MempoolEvent::TransactionMined { transaction, .. } => transaction,
+        }
+    }
+
+    /// Checks whether the transaction of the event is sent by or to any of the given addresses.
+    pub fn is_related_to(&self, addresses: &[Address]) -> bool {
+        let transaction = self.transaction();
+        addresses
+            .iter()
+            .any(|address| transaction.sender == *address || transaction.recipient == *address)
+    }
+}
@@ -21,6 +21,7 @@ enum TxRemovedReason {
     AlreadyIncludedTx,
     Invalid,
     TooFull,
+    Replaced,
 }
 
 impl MempoolMetrics {
@@ -38,6 +39,7 @@ impl MempoolMetrics {
             EvictionReason::AlreadyIncluded => TxRemovedReason::AlreadyIncludedTx,
             EvictionReason::Invalid => TxRemovedReason::Invalid,
             EvictionReason::TooFull => TxRemovedReason::TooFull,
+            EvictionReason::Replaced => TxRemovedReason::Replaced,
             _ => return,
         };
         self.evicted_tx
@@ -3,14 +3,16 @@ use nimiq_blockchain::Blockchain;
 use std::collections::{HashMap, HashSet};
 #[cfg(feature = "metrics")]
 use std::sync::Arc;
+use tokio::sync::broadcast::Sender as BroadcastSender;
 
+use crate::mempool_dependencies::{is_control_tx, TransactionDependencies};
+use crate::mempool_events::MempoolEvent;
 #[cfg(feature = "metrics")]
 use crate::mempool_metrics::MempoolMetrics;
 use crate::mempool_transactions::{MempoolTransactions, TxPriority};
 use crate::verify::VerifyErr;
 use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_keys::Address;
-use nimiq_primitives::account::AccountType;
 use nimiq_transaction::Transaction;
 
 pub(crate) struct MempoolState {
@@ -23,16 +25,34 @@ pub(crate) struct MempoolState {
     // The pending balance per sender.
     pub(crate) state_by_sender: HashMap<Address, SenderPendingState>,
 
+    // Minimum increase of the fee per byte for a transaction to replace a pending one.
+    pub(crate) replacement_fee_increment: f64,
+
+    // The pending transactions that spend funds received from other pending transactions.
+    pub(crate) dependencies: TransactionDependencies,
+
+    // Notifies subscribers about transactions being added to or removed from the mempool.
+    pub(crate) events: BroadcastSender<MempoolEvent>,
+
     #[cfg(feature = "metrics")]
     pub(crate) metrics: Arc<MempoolMetrics>,
 }
 
 impl MempoolState {
-    pub fn new(regular_txns_limit: usize, control_txns_limit: usize) -> Self {
+    pub fn new(
+        regular_txns_limit: usize,
+        control_txns_limit: usize,
+        replacement_fee_increment: f64,
+        allow_dependent_transactions: bool,
+        events: BroadcastSender<MempoolEvent>,
+    ) -> Self {
         MempoolState {
             regular_transactions: MempoolTransactions::new(regular_txns_limit),
             control_transactions: MempoolTransactions::new(control_txns_limit),
             state_by_sender: HashMap::new(),
+            replacement_fee_increment,
+            dependencies: TransactionDependencies::new(allow_dependent_transactions),
+            events,
             #[cfg(feature = "metrics")]
             metrics: Default::default(),
         }
@@ -64,37 +84,111 @@ impl MempoolState {
             return Err(VerifyErr::Known);
         }
 
+        // Check if the transaction replaces a pending one.
+        let replaced_tx_hash = self.find_replaced_tx(tx)?;
+
         // Reserve the balance necessary for this transaction on the sender account.
         let sender_account = blockchain
             .get_account_if_complete(&tx.sender)
             .ok_or(VerifyErr::NoConsensus)?;
 
-        if let Some(sender_state) = self.state_by_sender.get_mut(&tx.sender) {
-            let reserved_balance = &mut sender_state.reserved_balance;
+        let parents = if let Some(replaced_tx_hash) = replaced_tx_hash {
+            // Other pending transactions might rely on the funds of the replaced transaction.
+            if self.dependencies.has_children(&replaced_tx_hash) {
+                return Err(VerifyErr::ReplacedTransactionHasDependents);
+            }
+
+            // The replaced transaction is only evicted if the balance it releases is sufficient
+            // for the new transaction.
+            let sender_state = self
+                .state_by_sender
+                .get_mut(&tx.sender)
+                .expect("Sender of replaced transaction must be known");
+            let replaced_tx = self
+                .regular_transactions
+                .get(&replaced_tx_hash)
+                .or_else(|| self.control_transactions.get(&replaced_tx_hash))
+                .expect("Replaced transaction must be known")
+                .clone();
+
+            let mut reserved_balance = sender_state.reserved_balance.clone();
             blockchain
-                .reserve_balance(&sender_account, tx, reserved_balance)
-                .map_err(|_| VerifyErr::InsufficientFunds)?;
+                .release_balance(&sender_account, &replaced_tx, &mut reserved_balance)
+                .map_err(|e| {
+                    warn!(
+                        replaced_tx = %replaced_tx_hash,
+                        error = %e,
+                        "Failed to release balance of replaced transaction"
+                    );
+                    VerifyErr::ReplacedBalanceNotReleased
+                })?;
+            let parents = self.dependencies.reserve_balance(
+                blockchain,
+                &self.regular_transactions,
+                &sender_account,
+                tx,
+                &mut reserved_balance,
+            )?;
+
+            sender_state.reserved_balance = reserved_balance;
+            sender_state.txns.remove(&replaced_tx_hash);
             sender_state.txns.insert(tx.hash());
+
+            self.regular_transactions
+                .delete(&replaced_tx_hash)
+                .or_else(|| self.control_transactions.delete(&replaced_tx_hash));
+            self.dependencies.unlink_parents(&replaced_tx_hash);
+            self.notify_evicted(replaced_tx, EvictionReason::Replaced);
+
+            debug!(
+                replaced_tx = %replaced_tx_hash,
+                replacement_tx = %tx_hash,
+                "Transaction replaced by one with a higher fee"
+            );
+
+            #[cfg(feature = "metrics")]
+            self.metrics.note_evicted(EvictionReason::Replaced);
+
+            parents
+        } else if let Some(sender_state) = self.state_by_sender.get_mut(&tx.sender) {
+            let parents = self.dependencies.reserve_balance(
+                blockchain,
+                &self.regular_transactions,
+                &sender_account,
+                tx,
+                &mut sender_state.reserved_balance,
+            )?;
+            sender_state.txns.insert(tx.hash());
+
+            parents
         } else {
             let mut reserved_balance = ReservedBalance::new(tx.sender.clone());
-            blockchain
-                .reserve_balance(&sender_account, tx, &mut reserved_balance)
-                .map_err(|_| VerifyErr::InsufficientFunds)?;
+            let parents = self.dependencies.reserve_balance(
+                blockchain,
+                &self.regular_transactions,
+                &sender_account,
+                tx,
+                &mut reserved_balance,
+            )?;
 
             let sender_state = SenderPendingState {
                 reserved_balance,
                 txns: HashSet::from([tx.hash()]),
             };
             self.state_by_sender.insert(tx.sender.clone(), sender_state);
-        }
+
+            parents
+        };
 
         // If we are adding a staking transaction we insert it into the control txns container
         // Staking txns are control txns
-        if tx.sender_type == AccountType::Staking || tx.recipient_type == AccountType::Staking {
+        if is_control_tx(tx) {
             self.control_transactions.insert(tx, priority);
         } else {
             self.regular_transactions.insert(tx, priority);
         }
+        self.dependencies.link(&tx_hash, parents);
+        self.notify(|| MempoolEvent::TransactionAdded(tx.clone()));
 
         // After inserting the new txn, check if we need to remove txns
         while self.regular_transactions.total_size > self.regular_transactions.total_size_limit {
@@ -114,16 +208,37 @@ impl MempoolState {
         &mut self,
         blockchain: &Blockchain,
         tx_hash: &Blake2bHash,
-        #[allow(unused_variables)] reason: EvictionReason,
+        reason: EvictionReason,
     ) -> Option<Transaction> {
         let tx = self
             .regular_transactions
             .delete(tx_hash)
             .or_else(|| self.control_transactions.delete(tx_hash))?;
+        self.notify_evicted(tx.clone(), reason.clone());
+
+        let mut dependents = self.remove_dependencies(tx_hash, &reason);
+        dependents.extend(self.release_balance(blockchain, &tx, reason));
+
+        // The transactions that depend on the removed one can't be funded anymore.
+        for dependent in dependents {
+            self.remove(blockchain, &dependent, EvictionReason::Invalid);
+        }
 
+        Some(tx)
+    }
+
+    /// Releases the balance reserved by a removed transaction. Returns the transactions that
+    /// depended on other transactions of the sender that had to be removed as well.
+    fn release_balance(
+        &mut self,
+        blockchain: &Blockchain,
+        tx: &Transaction,
+        #[allow(unused_variables)] reason: EvictionReason,
+    ) -> HashSet<Blake2bHash> {
+        let tx_hash = tx.hash();
         let sender_state = match self.state_by_sender.get_mut(&tx.sender) {
             Some(state) => state,
-            None => return Some(tx),
+            None => return HashSet::new(),
         };
 
         let sender_account = match blockchain.get_account_if_complete(&tx.sender) {
@@ -136,23 +251,26 @@ impl MempoolState {
                     num_transactions = sender_state.txns.len(),
                     "Sender account is gone"
                 );
-                for hash in &sender_state.txns {
-                    self.regular_transactions
-                        .delete(hash)
-                        .or_else(|| self.control_transactions.delete(hash));
-                }
-                self.state_by_sender.remove(&tx.sender);
-                return Some(tx);
+                return self.remove_sender(&tx.sender);
             }
         };
 
-        if !sender_state.txns.remove(tx_hash) {
-            return Some(tx);
+        if !sender_state.txns.remove(&tx_hash) {
+            return HashSet::new();
         }
 
-        blockchain
-            .release_balance(&sender_account, &tx, &mut sender_state.reserved_balance)
-            .expect("Failed to release balance");
+        if let Err(e) =
+            blockchain.release_balance(&sender_account, tx, &mut sender_state.reserved_balance)
+        {
+            // The reserved balance doesn't match the pending transactions anymore.
+            // Throw away all transactions from this sender.
+            warn!(
+                sender_address = %tx.sender,
+                error = %e,
+                "Failed to release balance"
+            );
+            return self.remove_sender(&tx.sender);
+        }
 
         if sender_state.txns.is_empty() {
             self.state_by_sender.remove(&tx.sender);
@@ -161,7 +279,110 @@ impl MempoolState {
         #[cfg(feature = "metrics")]
         self.metrics.note_evicted(reason);
 
-        Some(tx)
+        HashSet::new()
+    }
+
+    /// Removes all transactions of the given sender without tracking their balance. Returns the
+    /// transactions that depended on them and need to be removed as well.
+    fn remove_sender(&mut self, sender: &Address) -> HashSet<Blake2bHash> {
+        let txns = self
+            .state_by_sender
+            .remove(sender)
+            .map(|sender_state| sender_state.txns)
+            .unwrap_or_default();
+        let mut dependents = HashSet::new();
+        for hash in &txns {
+            if let Some(tx) = self
+                .regular_transactions
+                .delete(hash)
+                .or_else(|| self.control_transactions.delete(hash))
+            {
+                self.notify_evicted(tx, EvictionReason::Invalid);
+                dependents.extend(self.remove_dependencies(hash, &EvictionReason::Invalid));
+            }
+        }
+        dependents
+    }
+
+    /// Forgets about the dependencies of a removed transaction. Returns the transactions that
+    /// depended on it and need to be removed as well.
+    fn remove_dependencies(
+        &mut self,
+        tx_hash: &Blake2bHash,
+        reason: &EvictionReason,
+    ) -> HashSet<Blake2bHash> {
+        self.dependencies.unlink_parents(tx_hash);
+        match reason {
+            // The dependent transactions wait until the transaction is included in a block.
+            EvictionReason::BlockBuilding => HashSet::new(),
+            // The funds of the dependent transactions are committed now.
+            EvictionReason::AlreadyIncluded => {
+                self.dependencies.unlink_children(tx_hash);
+                HashSet::new()
+            }
+            _ => self.dependencies.unlink_children(tx_hash),
+        }
+    }
+
+    /// Sends the event created by the given function to the subscribers, if there are any.
+    pub(crate) fn notify<F: FnOnce() -> MempoolEvent>(&self, event: F) {
+        if self.events.receiver_count() > 0 {
+            // Sending only fails if there are no subscribers.
+            self.events.send(event()).ok();
+        }
+    }
+
+    /// Notifies the subscribers about a transaction that was evicted. Transactions that are
+    /// removed because they are (about to be) included in a block are reported once the block
+    /// is adopted instead.
+    fn notify_evicted(&self, transaction: Transaction, reason: EvictionReason) {
+        if let EvictionReason::BlockBuilding | EvictionReason::AlreadyIncluded = reason {
+            return;
+        }
+        self.notify(|| MempoolEvent::TransactionEvicted {
+            transaction,
+            reason,
+        });
+    }
+
+    /// Returns the hash of the pending transaction that the given transaction replaces, if any.
+    ///
+    /// A transaction replaces a pending transaction of the same sender with the same validity
+    /// start height that makes the same payment, i.e. that only differs in its fee. The
+    /// replacement needs to pay a fee per byte that is higher by at least the replacement fee
+    /// increment. To make the same payment again, a different validity start height needs to
+    /// be used.
+    fn find_replaced_tx(&self, tx: &Transaction) -> Result<Option<Blake2bHash>, VerifyErr> {
+        let sender_state = match self.state_by_sender.get(&tx.sender) {
+            Some(sender_state) => sender_state,
+            None => return Ok(None),
+        };
+
+        let replaced_tx = sender_state.txns.iter().find_map(|hash| {
+            self.get(hash)
+                .filter(|pending_tx| is_replacement(pending_tx, tx))
+                .map(|pending_tx| (hash, pending_tx))
+        });
+
+        match replaced_tx {
+            Some((hash, replaced_tx)) => {
+                let fee_per_byte = replaced_tx.fee_per_byte();
+                if tx.fee_per_byte() > fee_per_byte
+                    && tx.fee_per_byte() >= fee_per_byte + self.replacement_fee_increment
+                {
+                    Ok(Some(hash.clone()))
+                } else {
+                    debug!(
+                        pending_tx = %hash,
+                        pending_fee_per_byte = fee_per_byte,
+                        fee_per_byte = tx.fee_per_byte(),
+                        "Replacement transaction doesn't pay enough fees"
+                    );
+                    Err(VerifyErr::ReplacementFeeTooLow)
+                }
+            }
+            None => Ok(None),
+        }
     }
 
     /// Retrieves all expired transaction hashes from both the `regular_transactions` and `control_transactions` vectors
@@ -172,13 +393,35 @@ impl MempoolState {
     }
 }
 
-#[derive(Clone)]
-pub(crate) enum EvictionReason {
+/// The reason a transaction was removed from the mempool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EvictionReason {
+    /// The transaction was taken out to be included in a block.
     BlockBuilding,
+    /// The transaction is no longer valid at the current block height.
     Expired,
+    /// The transaction was included in a block.
     AlreadyIncluded,
+    /// The transaction became invalid, e.g. because the sender lacks the funds.
     Invalid,
+    /// The mempool was full and the transaction paid the lowest fee.
     TooFull,
+    /// The transaction was replaced by one with a higher fee.
+    Replaced,
+}
+
+/// Checks whether a transaction replaces another one, i.e. whether they only differ in their fee
+/// and proof.
+fn is_replacement(tx1: &Transaction, tx2: &Transaction) -> bool {
+    tx1.validity_start_height == tx2.validity_start_height
+        && tx1.sender == tx2.sender
+        && tx1.sender_type == tx2.sender_type
+        && tx1.recipient == tx2.recipient
+        && tx1.recipient_type == tx2.recipient_type
+        && tx1.value == tx2.value
+        && tx1.data == tx2.data
+        && tx1.network_id == tx2.network_id
+        && tx1.flags == tx2.flags
 }
 
 pub(crate) struct SenderPendingState {
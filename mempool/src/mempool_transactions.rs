@@ -1,17 +1,20 @@
 use std::{
     cmp::{Ordering, Reverse},
-    collections::HashMap,
+    collections::{HashMap, HashSet},
 };
 
-use beserial::Serialize;
+use beserial::{Deserialize, Serialize};
 use keyed_priority_queue::KeyedPriorityQueue;
 
 use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_keys::Address;
+use nimiq_primitives::account::AccountType;
 use nimiq_transaction::Transaction;
 
 /// TxPriority that is used when adding transactions into the mempool
 /// Higher Priority transactions are returned first from the mempool
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[repr(u8)]
 pub enum TxPriority {
     /// Low Priority transactions
     Low = 1,
@@ -109,6 +112,10 @@ pub(crate) struct MempoolTransactions {
     // This ordering is used to evict expired transactions from the mempool.
     pub(crate) oldest_transactions: KeyedPriorityQueue<Blake2bHash, Reverse<u32>>,
 
+    // Transactions to basic accounts indexed by their recipient.
+    // This is used to find the pending funds an account is going to receive.
+    pub(crate) incoming_transactions: HashMap<Address, HashSet<Blake2bHash>>,
+
     // Maximum allowed total size (in bytes) of all transactions in the mempool.
     pub(crate) total_size_limit: usize,
 
@@ -126,6 +133,7 @@ impl MempoolTransactions {
             best_transactions: KeyedPriorityQueue::new(),
             worst_transactions: KeyedPriorityQueue::new(),
             oldest_transactions: KeyedPriorityQueue::new(),
+            incoming_transactions: HashMap::new(),
             total_size_limit: size_limit,
             total_size: 0,
             tx_counter: 0,
@@ -170,6 +178,43 @@ impl MempoolTransactions {
         self.transactions.get(hash)
     }
 
+    /// Returns the hashes of the pending transactions to the given basic account.
+    pub(crate) fn incoming(&self, address: &Address) -> impl Iterator<Item = &Blake2bHash> {
+        self.incoming_transactions
+            .get(address)
+            .into_iter()
+            .flatten()
+    }
+
+    /// Returns the fee per byte and size of all transactions, in the order in which they are
+    /// included in blocks.
+    pub(crate) fn fees_in_block_order(&self) -> Vec<(f64, usize)> {
+        let mut transactions: Vec<_> = self.best_transactions.iter().collect();
+        transactions.sort_unstable_by(|(_, a), (_, b)| b.cmp(a));
+
+        transactions
+            .into_iter()
+            .map(|(tx_hash, order)| {
+                (
+                    order.fee_per_byte,
+                    self.transactions[tx_hash].serialized_size(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns all transactions together with their priority, in the order in which they are
+    /// included in blocks.
+    pub(crate) fn in_block_order(&self) -> Vec<(&Transaction, TxPriority)> {
+        let mut transactions: Vec<_> = self.best_transactions.iter().collect();
+        transactions.sort_unstable_by(|(_, a), (_, b)| b.cmp(a));
+
+        transactions
+            .into_iter()
+            .map(|(tx_hash, order)| (&self.transactions[tx_hash], order.priority))
+            .collect()
+    }
+
     pub(crate) fn insert(&mut self, tx: &Transaction, priority: TxPriority) -> bool {
         let tx_hash = tx.hash();
 
@@ -198,6 +243,13 @@ impl MempoolTransactions {
 
         self.tx_counter += 1;
 
+        if tx.recipient_type == AccountType::Basic {
+            self.incoming_transactions
+                .entry(tx.recipient.clone())
+                .or_default()
+                .insert(tx_hash.clone());
+        }
+
         self.oldest_transactions
             .push(tx_hash, Reverse(tx.validity_start_height));
 
@@ -214,6 +266,13 @@ impl MempoolTransactions {
         self.worst_transactions.remove(tx_hash);
         self.oldest_transactions.remove(tx_hash);
 
+        if let Some(incoming) = self.incoming_transactions.get_mut(&tx.recipient) {
+            incoming.remove(tx_hash);
+            if incoming.is_empty() {
+                self.incoming_transactions.remove(&tx.recipient);
+            }
+        }
+
         self.total_size -= tx.serialized_size();
 
         Some(tx)
@@ -0,0 +1,115 @@
+use std::{fs, io, path::Path};
+
+use beserial::{Deserialize, Serialize, SerializingError};
+use thiserror::Error;
+
+use nimiq_transaction::Transaction;
+
+use crate::mempool_state::MempoolState;
+use crate::mempool_transactions::{MempoolTransactions, TxPriority};
+
+/// Version of the snapshot format. Snapshots of other versions are discarded.
+const SNAPSHOT_VERSION: u8 = 1;
+
+/// Errors that can occur while saving or loading a mempool snapshot.
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+pub enum MempoolPersistenceError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] SerializingError),
+    #[error("Unsupported snapshot version: {0}")]
+    UnsupportedVersion(u8),
+}
+
+/// A pending transaction together with the priority it was added with.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PersistedTransaction {
+    /// The transaction
+    pub transaction: Transaction,
+    /// The priority the transaction was added to the mempool with
+    pub priority: TxPriority,
+}
+
+/// The pending transactions of a mempool, as they are stored on disk.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MempoolSnapshot {
+    /// The control transactions
+    #[beserial(len_type(u32))]
+    pub control_transactions: Vec<PersistedTransaction>,
+    /// The regular transactions
+    #[beserial(len_type(u32))]
+    pub regular_transactions: Vec<PersistedTransaction>,
+}
+
+impl MempoolSnapshot {
+    /// Takes a snapshot of the given mempool state. The transactions are listed in the order in
+    /// which they would be included in blocks.
+    pub(crate) fn from_state(state: &MempoolState) -> Self {
+        Self {
+            control_transactions: Self::collect(&state.control_transactions),
+            regular_transactions: Self::collect(&state.regular_transactions),
+        }
+    }
+
+    fn collect(transactions: &MempoolTransactions) -> Vec<PersistedTransaction> {
+        transactions
+            .in_block_order()
+            .into_iter()
+            .map(|(transaction, priority)| PersistedTransaction {
+                transaction: transaction.clone(),
+                priority,
+            })
+            .collect()
+    }
+
+    /// Returns the total number of transactions in the snapshot.
+    pub fn len(&self) -> usize {
+        self.control_transactions.len() + self.regular_transactions.len()
+    }
+
+    /// Returns whether the snapshot doesn't contain any transactions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the snapshot to the given file. The snapshot is first written to a temporary file
+    /// next to it, such that an interrupted write doesn't destroy the previous snapshot.
+    pub fn save(&self, path: &Path) -> Result<(), MempoolPersistenceError> {
+        let mut data = Vec::with_capacity(1 + self.serialized_size());
+        Serialize::serialize(&SNAPSHOT_VERSION, &mut data)?;
+        Serialize::serialize(self, &mut data)?;
+
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, data)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Reads a snapshot from the given file. Returns `None` if the file doesn't exist.
+    pub fn load(path: &Path) -> Result<Option<Self>, MempoolPersistenceError> {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut reader = &data[..];
+        let version: u8 = Deserialize::deserialize(&mut reader)?;
+        if version != SNAPSHOT_VERSION {
+            return Err(MempoolPersistenceError::UnsupportedVersion(version));
+        }
+
+        Ok(Some(Deserialize::deserialize(&mut reader)?))
+    }
+
+    /// Deletes the snapshot stored in the given file, if it exists.
+    pub fn delete(path: &Path) -> Result<(), MempoolPersistenceError> {
+        match fs::remove_file(path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
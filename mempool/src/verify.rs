@@ -28,6 +28,12 @@ pub enum VerifyErr {
     InsufficientFunds,
     #[error("Transaction already in mempool")]
     Known,
+    #[error("Fee of replacement transaction is too low")]
+    ReplacementFeeTooLow,
+    #[error("Replaced transaction funds other pending transactions")]
+    ReplacedTransactionHasDependents,
+    #[error("Balance of replaced transaction couldn't be released")]
+    ReplacedBalanceNotReleased,
     #[error("Transaction is filtered")]
     Filtered,
     #[error("Can't verify transaction without consensus")]
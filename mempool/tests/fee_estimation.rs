@@ -0,0 +1,90 @@
+use nimiq_mempool::fee_estimation::{estimate_fee, RecentBlocks};
+use nimiq_primitives::coin::Coin;
+use nimiq_test_log::test;
+
+const BLOCK_SIZE: usize = 1000;
+
+#[test]
+fn it_suggests_the_minimum_fee_if_there_is_enough_space() {
+    let pending = vec![(5.0, 100), (3.0, 100), (2.0, 100)];
+
+    let estimate = estimate_fee(
+        pending,
+        0,
+        BLOCK_SIZE,
+        &RecentBlocks::default(),
+        1.0,
+        150,
+        1,
+    );
+
+    assert_eq!(estimate.fee, Coin::from_u64_unchecked(150));
+    assert_eq!(estimate.fee_per_byte, 1.0);
+}
+
+#[test]
+fn it_outbids_transactions_that_would_not_fit() {
+    // Each transaction takes up 200 bytes (plus one byte for its execution status).
+    let pending = vec![(5.0, 200), (4.0, 200), (3.0, 200), (2.0, 200), (1.5, 200)];
+
+    // With 99 bytes, only four of the pending transactions fit into a block together with ours.
+    let estimate = estimate_fee(
+        pending.clone(),
+        0,
+        BLOCK_SIZE,
+        &RecentBlocks::default(),
+        1.0,
+        99,
+        1,
+    );
+    assert_eq!(estimate.fee, Coin::from_u64_unchecked(149));
+
+    // Control transactions are included first and take up space as well.
+    let estimate = estimate_fee(
+        pending.clone(),
+        201,
+        BLOCK_SIZE,
+        &RecentBlocks::default(),
+        1.0,
+        99,
+        1,
+    );
+    assert_eq!(estimate.fee, Coin::from_u64_unchecked(199));
+
+    // Two blocks fit all pending transactions.
+    let estimate = estimate_fee(pending, 0, BLOCK_SIZE, &RecentBlocks::default(), 1.0, 99, 2);
+    assert_eq!(estimate.fee, Coin::from_u64_unchecked(99));
+}
+
+#[test]
+fn it_outbids_recently_included_transactions_if_congested() {
+    let mut recent_blocks = RecentBlocks {
+        used_bytes: 950,
+        available_bytes: BLOCK_SIZE,
+        min_fee_per_byte: Some(3.0),
+    };
+
+    let estimate = estimate_fee(vec![], 0, BLOCK_SIZE, &recent_blocks, 1.0, 100, 1);
+    assert_eq!(estimate.fee, Coin::from_u64_unchecked(301));
+
+    // Blocks that were not full don't indicate any congestion.
+    recent_blocks.used_bytes = 500;
+    let estimate = estimate_fee(vec![], 0, BLOCK_SIZE, &recent_blocks, 1.0, 100, 1);
+    assert_eq!(estimate.fee, Coin::from_u64_unchecked(100));
+}
+
+#[test]
+fn it_caps_the_fee_at_the_maximum_amount_of_coins() {
+    let pending = vec![(1e12, 100)];
+
+    let estimate = estimate_fee(
+        pending,
+        0,
+        BLOCK_SIZE,
+        &RecentBlocks::default(),
+        1e12,
+        usize::MAX,
+        u32::MAX,
+    );
+    assert_eq!(estimate.fee, Coin::MAX);
+}
@@ -17,7 +17,14 @@ use nimiq_keys::{
     Address, KeyPair as SchnorrKeyPair, PrivateKey as SchnorrPrivateKey,
     PublicKey as SchnorrPublicKey, SecureGenerate,
 };
-use nimiq_mempool::{config::MempoolConfig, mempool::Mempool, mempool_transactions::TxPriority};
+use nimiq_mempool::{
+    config::MempoolConfig,
+    mempool::Mempool,
+    mempool_events::{EvictionReason, MempoolEvent},
+    mempool_transactions::TxPriority,
+    persistence::{MempoolSnapshot, PersistedTransaction},
+    verify::VerifyErr,
+};
 use nimiq_network_mock::{MockHub, MockId, MockNetwork, MockPeerId};
 use nimiq_primitives::{coin::Coin, networks::NetworkId, policy::Policy};
 use nimiq_test_log::test;
@@ -308,7 +315,7 @@ async fn push_same_tx_twice() {
     log::debug!("Done generating transactions and accounts");
 
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
 
     // Add a validator
     genesis_builder.with_genesis_validator(
@@ -369,7 +376,7 @@ async fn valid_tx_not_in_blockchain() {
     log::debug!("Done generating transactions and accounts");
 
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
 
     // Create an empty blockchain
     let blockchain = Arc::new(RwLock::new(
@@ -416,7 +423,7 @@ async fn push_tx_with_wrong_signature() {
     txns[0].proof = hex::decode("0222666efadc937148a6d61589ce6d4aeecca97fda4c32348d294eab582f14a0003fecb82d3aef4be76853d5c5b263754b7d495d9838f6ae5df60cf3addd3512a82988db0056059c7a52ae15285983ef0db8229ae446c004559147686d28f0a30b").unwrap();
 
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
 
     // Add a validator
     genesis_builder.with_genesis_validator(
@@ -477,7 +484,7 @@ async fn mempool_get_txn_max_size() {
     log::debug!("Done generating transactions and accounts");
 
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
 
     // Add a validator to genesis
     genesis_builder.with_genesis_validator(
@@ -548,7 +555,7 @@ async fn mempool_get_txn_ordered() {
     log::debug!("Done generating transactions and accounts");
 
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
 
     // Add a validator to genesis
     genesis_builder.with_genesis_validator(
@@ -620,7 +627,7 @@ async fn push_tx_with_insufficient_balance() {
     log::debug!("Done generating transactions and accounts");
 
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
 
     // Add a validator to genesis
     genesis_builder.with_genesis_validator(
@@ -682,7 +689,7 @@ async fn multiple_transactions_multiple_senders() {
     log::debug!("Done generating transactions and accounts");
 
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
 
     // Add a validator to genesis
     genesis_builder.with_genesis_validator(
@@ -726,7 +733,7 @@ async fn multiple_transactions_multiple_senders() {
 async fn mempool_tps() {
     let mut rng = test_rng(true);
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let mut genesis_builder = GenesisBuilder::default();
 
     // Generate and sign transaction from address_a using a balance that will be used to create the account later
@@ -803,7 +810,7 @@ async fn mempool_tps() {
 async fn multiple_start_stop() {
     let mut rng = test_rng(true);
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let mut genesis_builder = GenesisBuilder::default();
 
     log::debug!("Generating transactions and accounts");
@@ -864,7 +871,7 @@ async fn multiple_start_stop() {
 async fn mempool_update() {
     let mut rng = test_rng(true);
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let mut genesis_builder = GenesisBuilder::default();
 
     // Generate and sign transactions
@@ -1050,7 +1057,7 @@ async fn mempool_update() {
 async fn mempool_update_aged_transaction() {
     let mut rng = test_rng(true);
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let mut genesis_builder = GenesisBuilder::default();
 
     // Generate and sign transactions
@@ -1164,7 +1171,7 @@ async fn mempool_update_aged_transaction() {
 async fn mempool_update_not_enough_balance() {
     let mut rng = test_rng(true);
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let mut genesis_builder = GenesisBuilder::default();
 
     // Generate and sign transactions
@@ -1315,7 +1322,7 @@ async fn mempool_update_not_enough_balance() {
 async fn mempool_update_pruned_account() {
     let mut rng = test_rng(true);
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let mut genesis_builder = GenesisBuilder::default();
 
     // Generate and sign transactions
@@ -1464,7 +1471,7 @@ async fn mempool_update_pruned_account() {
 #[test(tokio::test(flavor = "multi_thread", worker_threads = 10))]
 async fn mempool_basic_prioritization_control_tx() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
 
     let key_pair = ed25519_key_pair(ACCOUNT_SECRET_KEY);
     let validator_signing_key = ed25519_key_pair(VALIDATOR_SECRET_KEY);
@@ -1577,7 +1584,7 @@ async fn mempool_regular_and_control_tx() {
     log::debug!("Done generating transactions and accounts");
 
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
 
     // Add a validator to genesis
     genesis_builder.with_genesis_validator(
@@ -1679,7 +1686,7 @@ async fn mempool_regular_and_control_tx() {
 
 #[tokio::test]
 async fn applies_total_tx_size_limits() {
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let mut genesis_builder = GenesisBuilder::default();
 
     // Generate transactions
@@ -1755,7 +1762,7 @@ async fn applies_total_tx_size_limits() {
 #[tokio::test]
 async fn it_can_reject_invalid_vesting_contract_transaction() {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     let blockchain = Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -1839,3 +1846,484 @@ async fn it_can_reject_invalid_vesting_contract_transaction() {
         "Number of txns in the mempools is not what is expected"
     );
 }
+
+#[test(tokio::test)]
+async fn it_replaces_transactions_with_higher_fee() {
+    let env = VolatileEnvironment::new(11).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+    let mut rng = test_rng(false);
+
+    let recipient_accounts = generate_accounts(vec![0], &mut genesis_builder, false, &mut rng);
+    let sender_accounts = generate_accounts(vec![1000], &mut genesis_builder, true, &mut rng);
+
+    // The same payment with increasing fees, plus a different payment.
+    let fees = [138, 200, 300, 1000];
+    let mut mempool_transactions: Vec<_> = fees
+        .iter()
+        .map(|fee| TestTransaction {
+            fee: *fee,
+            value: 100,
+            recipient: recipient_accounts[0].clone(),
+            sender: sender_accounts[0].clone(),
+        })
+        .collect();
+    mempool_transactions.push(TestTransaction {
+        fee: 138,
+        value: 50,
+        recipient: recipient_accounts[0].clone(),
+        sender: sender_accounts[0].clone(),
+    });
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let mut rng = test_rng(true);
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+    let mempool = Mempool::new(blockchain, MempoolConfig::default());
+
+    mempool
+        .add_transaction(txns[0].clone(), None)
+        .await
+        .unwrap();
+
+    // The fee per byte doesn't increase by the minimum increment.
+    assert_eq!(
+        mempool.add_transaction(txns[1].clone(), None).await,
+        Err(VerifyErr::ReplacementFeeTooLow)
+    );
+    assert!(mempool.contains_transaction_by_hash(&txns[0].hash()));
+
+    // The replacement evicts the original transaction.
+    mempool
+        .add_transaction(txns[2].clone(), None)
+        .await
+        .unwrap();
+    assert!(!mempool.contains_transaction_by_hash(&txns[0].hash()));
+    assert!(mempool.contains_transaction_by_hash(&txns[2].hash()));
+
+    // The replacement needs to be covered by the balance.
+    assert_eq!(
+        mempool.add_transaction(txns[3].clone(), None).await,
+        Err(VerifyErr::InsufficientFunds)
+    );
+    assert!(mempool.contains_transaction_by_hash(&txns[2].hash()));
+
+    // A different payment doesn't replace any transaction.
+    mempool
+        .add_transaction(txns[4].clone(), None)
+        .await
+        .unwrap();
+    assert!(mempool.contains_transaction_by_hash(&txns[2].hash()));
+    assert_eq!(mempool.num_transactions(), 2);
+
+    // Neither does the same payment with a different validity start height.
+    let tx = TransactionBuilder::new_basic(
+        &sender_accounts[0].keypair,
+        recipient_accounts[0].address.clone(),
+        Coin::from_u64_unchecked(100),
+        Coin::from_u64_unchecked(138),
+        0,
+        NetworkId::UnitAlbatross,
+    )
+    .unwrap();
+    mempool.add_transaction(tx, None).await.unwrap();
+    assert!(mempool.contains_transaction_by_hash(&txns[2].hash()));
+    assert_eq!(mempool.num_transactions(), 3);
+}
+
+#[test(tokio::test)]
+async fn it_restores_persisted_transactions() {
+    let env = VolatileEnvironment::new(11).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+    let mut rng = test_rng(false);
+
+    let recipient_accounts = generate_accounts(vec![0], &mut genesis_builder, false, &mut rng);
+    let sender_accounts = generate_accounts(vec![1000; 3], &mut genesis_builder, true, &mut rng);
+
+    let mempool_transactions = sender_accounts
+        .iter()
+        .map(|sender| TestTransaction {
+            fee: 200,
+            value: 100,
+            recipient: recipient_accounts[0].clone(),
+            sender: sender.clone(),
+        })
+        .collect();
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let mut rng = test_rng(true);
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+
+    let dir = tempfile::tempdir().unwrap();
+    let persistence_file = dir.path().join("mempool.dat");
+    let config = MempoolConfig {
+        persistence_file: Some(persistence_file.clone()),
+        ..Default::default()
+    };
+
+    let mempool = Mempool::new(Arc::clone(&blockchain), config.clone());
+
+    // Nothing is persisted before the persisted transactions were restored.
+    mempool.save_snapshot().unwrap();
+    assert!(!persistence_file.exists());
+    assert_eq!(mempool.restore_snapshot().await.unwrap(), 0);
+
+    mempool
+        .add_transaction(txns[0].clone(), None)
+        .await
+        .unwrap();
+    mempool
+        .add_transaction(txns[1].clone(), None)
+        .await
+        .unwrap();
+    mempool
+        .add_transaction(txns[2].clone(), Some(TxPriority::High))
+        .await
+        .unwrap();
+    mempool.save_snapshot().unwrap();
+
+    // Add a transaction that is no longer valid to the snapshot.
+    let mut snapshot = MempoolSnapshot::load(&persistence_file).unwrap().unwrap();
+    assert_eq!(snapshot.len(), 3);
+    let mut invalid_tx = txns[0].clone();
+    invalid_tx.value = Coin::from_u64_unchecked(50);
+    snapshot.regular_transactions.push(PersistedTransaction {
+        transaction: invalid_tx.clone(),
+        priority: TxPriority::Medium,
+    });
+    snapshot.save(&persistence_file).unwrap();
+
+    // Restore the snapshot into a fresh mempool.
+    let mempool = Mempool::new(blockchain, config);
+    assert_eq!(mempool.restore_snapshot().await.unwrap(), 3);
+    assert_eq!(mempool.num_transactions(), 3);
+    for tx in &txns {
+        assert!(mempool.contains_transaction_by_hash(&tx.hash()));
+    }
+    assert!(!mempool.contains_transaction_by_hash(&invalid_tx.hash()));
+
+    // The priority is preserved.
+    let (block_txns, _) = mempool.get_transactions_for_block(10_000);
+    assert_eq!(block_txns[0], txns[2]);
+
+    // The snapshot is only restored once.
+    assert!(!persistence_file.exists());
+    snapshot.save(&persistence_file).unwrap();
+    assert_eq!(mempool.restore_snapshot().await.unwrap(), 0);
+    assert_eq!(mempool.num_transactions(), 0);
+}
+
+#[test(tokio::test)]
+async fn it_ignores_missing_persistence_file() {
+    let env = VolatileEnvironment::new(11).unwrap();
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::new(
+            env,
+            BlockchainConfig::default(),
+            NetworkId::UnitAlbatross,
+            Arc::new(OffsetTime::new()),
+        )
+        .unwrap(),
+    ));
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = MempoolConfig {
+        persistence_file: Some(dir.path().join("mempool.dat")),
+        ..Default::default()
+    };
+    let mempool = Mempool::new(blockchain, config);
+    assert_eq!(mempool.restore_snapshot().await.unwrap(), 0);
+}
+
+#[test(tokio::test)]
+async fn it_notifies_about_mempool_events() {
+    let env = VolatileEnvironment::new(11).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+    let mut rng = test_rng(false);
+
+    let recipient_accounts = generate_accounts(vec![0], &mut genesis_builder, false, &mut rng);
+    let sender_accounts = generate_accounts(vec![1000], &mut genesis_builder, true, &mut rng);
+
+    // The same payment with a higher fee replaces the first transaction.
+    let mempool_transactions = [138, 300]
+        .iter()
+        .map(|fee| TestTransaction {
+            fee: *fee,
+            value: 100,
+            recipient: recipient_accounts[0].clone(),
+            sender: sender_accounts[0].clone(),
+        })
+        .collect();
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let mut rng = test_rng(true);
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+    let mempool = Mempool::new(blockchain, MempoolConfig::default());
+    let mut events = mempool.subscribe_events();
+
+    mempool
+        .add_transaction(txns[0].clone(), None)
+        .await
+        .unwrap();
+    mempool
+        .add_transaction(txns[1].clone(), None)
+        .await
+        .unwrap();
+
+    match events.recv().await.unwrap() {
+        MempoolEvent::TransactionAdded(tx) => assert_eq!(tx, txns[0]),
+        event => panic!("Unexpected event: {event:?}"),
+    }
+    match events.recv().await.unwrap() {
+        MempoolEvent::TransactionEvicted {
+            transaction,
+            reason,
+        } => {
+            assert_eq!(transaction, txns[0]);
+            assert_eq!(reason, EvictionReason::Replaced);
+        }
+        event => panic!("Unexpected event: {event:?}"),
+    }
+    let event = events.recv().await.unwrap();
+    assert!(event.is_related_to(&[txns[1].recipient.clone()]));
+    assert!(!event.is_related_to(&[Address::default()]));
+    match event {
+        MempoolEvent::TransactionAdded(tx) => assert_eq!(tx, txns[1]),
+        event => panic!("Unexpected event: {event:?}"),
+    }
+
+    // Transactions taken out for block building are only reported once they are mined.
+    mempool.get_transactions_for_block(10_000);
+    assert!(events.try_recv().is_err());
+}
+
+#[test(tokio::test)]
+async fn it_accepts_transactions_funded_by_pending_transactions() {
+    let env = VolatileEnvironment::new(11).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+    let mut rng = test_rng(false);
+
+    let funder_accounts = generate_accounts(vec![1000], &mut genesis_builder, true, &mut rng);
+    let unfunded_accounts = generate_accounts(vec![0, 0], &mut genesis_builder, false, &mut rng);
+
+    // The first transaction funds the second one, the third one exceeds the pending funds.
+    let mempool_transactions = vec![
+        TestTransaction {
+            fee: 138,
+            value: 500,
+            sender: funder_accounts[0].clone(),
+            recipient: unfunded_accounts[0].clone(),
+        },
+        TestTransaction {
+            fee: 200,
+            value: 100,
+            sender: unfunded_accounts[0].clone(),
+            recipient: unfunded_accounts[1].clone(),
+        },
+        TestTransaction {
+            fee: 200,
+            value: 300,
+            sender: unfunded_accounts[0].clone(),
+            recipient: unfunded_accounts[1].clone(),
+        },
+        TestTransaction {
+            fee: 1000,
+            value: 500,
+            sender: funder_accounts[0].clone(),
+            recipient: unfunded_accounts[0].clone(),
+        },
+    ];
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let mut rng = test_rng(true);
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+
+    // Without dependencies, the committed balance needs to cover the transaction.
+    let mempool = Mempool::new(Arc::clone(&blockchain), MempoolConfig::default());
+    mempool
+        .add_transaction(txns[0].clone(), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        mempool.add_transaction(txns[1].clone(), None).await,
+        Err(VerifyErr::InsufficientFunds)
+    );
+
+    let config = MempoolConfig {
+        allow_dependent_transactions: true,
+        ..Default::default()
+    };
+    let mempool = Mempool::new(blockchain, config);
+    mempool
+        .add_transaction(txns[0].clone(), None)
+        .await
+        .unwrap();
+    mempool
+        .add_transaction(txns[1].clone(), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        mempool.add_transaction(txns[2].clone(), None).await,
+        Err(VerifyErr::InsufficientFunds)
+    );
+
+    // A transaction that funds others can't be replaced.
+    assert_eq!(
+        mempool.add_transaction(txns[3].clone(), None).await,
+        Err(VerifyErr::ReplacedTransactionHasDependents)
+    );
+
+    // The parent is packed before the child, even though the child pays a higher fee.
+    let (block_txns, _) = mempool.get_transactions_for_block(10_000);
+    assert_eq!(block_txns, vec![txns[0].clone(), txns[1].clone()]);
+    assert_eq!(mempool.num_transactions(), 0);
+}
+
+#[test(tokio::test)]
+async fn it_only_depends_on_the_necessary_pending_transactions() {
+    let env = VolatileEnvironment::new(11).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+    let mut rng = test_rng(false);
+
+    let funder_accounts = generate_accounts(vec![1000, 1000], &mut genesis_builder, true, &mut rng);
+    let unfunded_accounts = generate_accounts(vec![0, 0], &mut genesis_builder, false, &mut rng);
+
+    // The first transaction suffices to fund the third one, the second one is unrelated to it
+    // and gets replaced by the fourth one.
+    let mempool_transactions = vec![
+        TestTransaction {
+            fee: 138,
+            value: 500,
+            sender: funder_accounts[0].clone(),
+            recipient: unfunded_accounts[0].clone(),
+        },
+        TestTransaction {
+            fee: 138,
+            value: 100,
+            sender: funder_accounts[1].clone(),
+            recipient: unfunded_accounts[0].clone(),
+        },
+        TestTransaction {
+            fee: 200,
+            value: 300,
+            sender: unfunded_accounts[0].clone(),
+            recipient: unfunded_accounts[1].clone(),
+        },
+        TestTransaction {
+            fee: 500,
+            value: 100,
+            sender: funder_accounts[1].clone(),
+            recipient: unfunded_accounts[0].clone(),
+        },
+    ];
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let mut rng = test_rng(true);
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+
+    let config = MempoolConfig {
+        allow_dependent_transactions: true,
+        ..Default::default()
+    };
+    let mempool = Mempool::new(blockchain, config);
+    for tx in &txns[..3] {
+        mempool.add_transaction(tx.clone(), None).await.unwrap();
+    }
+
+    // Evicting the unrelated transaction doesn't evict the dependent one.
+    mempool
+        .add_transaction(txns[3].clone(), None)
+        .await
+        .unwrap();
+    assert!(!mempool.contains_transaction_by_hash(&txns[1].hash()));
+    assert!(mempool.contains_transaction_by_hash(&txns[2].hash()));
+    assert_eq!(mempool.num_transactions(), 3);
+}
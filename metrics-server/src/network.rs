@@ -12,8 +12,21 @@ impl NetworkMetrics {
 
         network.metrics().register(sub_registry);
 
-        let closure =
-            NumericClosureMetric::new_gauge(Box::new(move || network.peer_count() as i64));
+        let net = network.clone();
+        let closure = NumericClosureMetric::new_gauge(Box::new(move || net.peer_count() as i64));
         sub_registry.register("peer_count", "Number of peers", closure);
+
+        let closure = NumericClosureMetric::new_gauge(Box::new(move || {
+            network
+                .peer_reputations()
+                .iter()
+                .filter(|reputation| reputation.banned_for.is_some())
+                .count() as i64
+        }));
+        sub_registry.register(
+            "banned_peer_count",
+            "Number of peers banned because of their reputation",
+            closure,
+        );
     }
 }
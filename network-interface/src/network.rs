@@ -59,6 +59,19 @@ pub enum CloseReason {
     MaliciousPeer,
 }
 
+/// Misbehaviour of a peer that affects its reputation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PeerReputationEvent {
+    /// The peer sent us a block that turned out to be invalid.
+    InvalidBlock,
+    /// The peer replied to a request with an invalid or inconsistent response.
+    BadResponse,
+    /// A request to the peer timed out.
+    Timeout,
+    /// The peer exceeded the rate limit of a request type.
+    RateLimitExceeded,
+}
+
 #[derive(Debug, Error)]
 pub enum SendError {
     #[error("{0}")]
@@ -109,6 +122,10 @@ pub trait Network: Send + Sync + Unpin + 'static {
     /// Disconnects a peer with a close reason
     async fn disconnect_peer(&self, peer_id: Self::PeerId, close_reason: CloseReason);
 
+    /// Reports a misbehaviour of a peer, lowering its reputation. Peers with a bad reputation get
+    /// temporarily banned.
+    fn report_peer(&self, peer_id: Self::PeerId, event: PeerReputationEvent);
+
     /// Subscribes to network events
     fn subscribe_events(&self) -> SubscribeEvents<Self::PeerId>;
 
@@ -35,6 +35,7 @@ use crate::{
         peer_contacts::PeerContactBook,
     },
     dispatch::codecs::typed::{IncomingRequest, MessageCodec, OutgoingResponse, ReqResProtocol},
+    reputation::PeerReputation,
     Config,
 };
 
@@ -127,6 +128,7 @@ impl NimiqBehaviour {
         config: Config,
         clock: Arc<OffsetTime>,
         contacts: Arc<RwLock<PeerContactBook>>,
+        reputation: Arc<RwLock<PeerReputation>>,
         peer_score_params: PeerScoreParams,
     ) -> Self {
         let public_key = config.keypair.public();
@@ -167,6 +169,8 @@ impl NimiqBehaviour {
             peer_id,
             config.seeds,
             config.discovery.required_services,
+            reputation,
+            config.connection_pool,
         );
 
         // Request Response behaviour
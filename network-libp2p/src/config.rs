@@ -13,7 +13,14 @@ use std::{
 use nimiq_hash::Blake2bHash;
 use nimiq_network_interface::peer_info::Services;
 
-use crate::discovery::{behaviour::DiscoveryConfig, peer_contacts::PeerContact};
+use crate::{
+    connection_pool::behaviour::ConnectionPoolConfig,
+    discovery::{
+        behaviour::DiscoveryConfig, peer_contact_store::PeerContactStore,
+        peer_contacts::PeerContact,
+    },
+    reputation::ReputationConfig,
+};
 
 /// TLS settings for configuring a secure WebSocket
 pub struct TlsConfig {
@@ -35,9 +42,66 @@ pub struct Config {
     pub memory_transport: bool,
     pub required_services: Services,
     pub tls: Option<TlsConfig>,
+    /// Optional store to persist the peer contact book across restarts.
+    pub peer_contact_store: Option<Box<dyn PeerContactStore>>,
+    /// Weights, decay and ban durations of the peer reputation.
+    pub reputation: ReputationConfig,
+    /// Desired and maximum numbers of peers, as well as the limits per IP, subnet and direction.
+    pub connection_pool: ConnectionPoolConfig,
+}
+
+/// Tunable parameters of the gossipsub mesh
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GossipsubParams {
+    /// Target number of peers in the mesh of a topic
+    pub mesh_n: usize,
+    /// Minimum number of peers in the mesh of a topic before adding more
+    pub mesh_n_low: usize,
+    /// Maximum number of peers in the mesh of a topic before removing some
+    pub mesh_n_high: usize,
+    /// Maximum size of a gossipsub message in bytes
+    pub max_transmit_size: usize,
+    /// Interval of the gossipsub heartbeat, which maintains the meshes
+    pub heartbeat_interval: Duration,
+}
+
+impl Default for GossipsubParams {
+    fn default() -> Self {
+        Self {
+            mesh_n: 6,
+            mesh_n_low: 3,
+            mesh_n_high: 12,
+            max_transmit_size: 1_000_000,
+            heartbeat_interval: Duration::from_millis(700),
+        }
+    }
+}
+
+/// Tunable parameters of the Kademlia DHT
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DhtParams {
+    /// Time after which records expire
+    pub record_ttl: Duration,
+    /// Interval in which our own records are re-published
+    pub publication_interval: Duration,
+    /// Interval in which the records we store are replicated to other peers. `None` disables
+    /// replication.
+    pub replication_interval: Option<Duration>,
+}
+
+impl Default for DhtParams {
+    fn default() -> Self {
+        Self {
+            record_ttl: Duration::from_secs(5 * 60),
+            publication_interval: Duration::from_secs(60),
+            // Since we have a record TTL of 5 minutes, record replication is not needed right now
+            replication_interval: None,
+        }
+    }
 }
 
 impl Config {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         keypair: Keypair,
         peer_contact: PeerContact,
@@ -46,15 +110,17 @@ impl Config {
         memory_transport: bool,
         required_services: Services,
         tls_settings: Option<TlsConfig>,
+        gossipsub_params: GossipsubParams,
+        dht_params: DhtParams,
     ) -> Self {
-        // Hardcoding the minimum number of peers in mesh network before adding more
-        // TODO: Maybe change this to a mesh limits configuration argument of this function
         let gossipsub = GossipsubConfigBuilder::default()
-            .mesh_n_low(3)
+            .mesh_n(gossipsub_params.mesh_n)
+            .mesh_n_low(gossipsub_params.mesh_n_low)
+            .mesh_n_high(gossipsub_params.mesh_n_high)
             .validate_messages()
-            .max_transmit_size(1_000_000) // TODO find a reasonable value for this parameter
+            .max_transmit_size(gossipsub_params.max_transmit_size)
             .validation_mode(libp2p::gossipsub::ValidationMode::Permissive)
-            .heartbeat_interval(Duration::from_millis(700))
+            .heartbeat_interval(gossipsub_params.heartbeat_interval)
             // Use the message hash as the message ID instead of the default PeerId + sequence_number
             // to avoid duplicated messages
             .message_id_fn(|message| {
@@ -67,11 +133,9 @@ impl Config {
 
         let mut kademlia = KademliaConfig::default();
         kademlia.set_kbucket_inserts(KademliaBucketInserts::OnConnected);
-        kademlia.set_record_ttl(Some(Duration::from_secs(5 * 60)));
-        kademlia.set_publication_interval(Some(Duration::from_secs(60)));
-
-        // Since we have a record TTL of 5 minutes, record replication is not needed right now
-        kademlia.set_replication_interval(None);
+        kademlia.set_record_ttl(Some(dht_params.record_ttl));
+        kademlia.set_publication_interval(Some(dht_params.publication_interval));
+        kademlia.set_replication_interval(dht_params.replication_interval);
         kademlia.set_record_filtering(KademliaStoreInserts::FilterBoth);
 
         Self {
@@ -84,6 +148,9 @@ impl Config {
             memory_transport,
             required_services,
             tls: tls_settings,
+            peer_contact_store: None,
+            reputation: ReputationConfig::default(),
+            connection_pool: ConnectionPoolConfig::default(),
         }
     }
 }
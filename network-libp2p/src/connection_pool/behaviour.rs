@@ -1,5 +1,6 @@
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    net::IpAddr,
     sync::Arc,
     task::{Context, Poll, Waker},
     time::Duration,
@@ -23,30 +24,115 @@ use rand::thread_rng;
 use wasm_timer::Interval;
 
 use nimiq_macros::store_waker;
-use nimiq_network_interface::{network::CloseReason, peer_info::Services};
+use nimiq_network_interface::{
+    network::{CloseReason, PeerReputationEvent},
+    peer_info::Services,
+};
 
-use crate::discovery::peer_contacts::PeerContactBook;
+use crate::{discovery::peer_contacts::PeerContactBook, reputation::PeerReputation};
 
 use super::handler::{ConnectionPoolHandler, ConnectionPoolHandlerError};
 
+/// A connected peer as accounted for in the connection limits
 #[derive(Clone, Debug)]
+struct CountedPeer {
+    /// IP address and subnet of the peer's connection, if it has one
+    ip_and_subnet: Option<(IpAddr, IpNetwork)>,
+    /// Whether we dialed the peer
+    outbound: bool,
+}
+
+/// Counters per connection limits
+#[derive(Clone, Debug, Default)]
 struct ConnectionPoolLimits {
-    ip_count: HashMap<IpNetwork, usize>,
-    ipv4_count: usize,
-    ipv6_count: usize,
+    ip_count: HashMap<IpAddr, usize>,
+    subnet_count: HashMap<IpNetwork, usize>,
+    inbound_count: usize,
+    outbound_count: usize,
+    peers: HashMap<PeerId, CountedPeer>,
+}
+
+impl ConnectionPoolLimits {
+    fn num_connected(&self) -> usize {
+        self.inbound_count + self.outbound_count
+    }
+
+    fn num_per_ip(&self, ip: &IpAddr) -> usize {
+        self.ip_count.get(ip).copied().unwrap_or(0)
+    }
+
+    fn num_per_subnet(&self, subnet: &IpNetwork) -> usize {
+        self.subnet_count.get(subnet).copied().unwrap_or(0)
+    }
+
+    /// Increments the counters for a newly connected peer
+    fn add(&mut self, peer_id: PeerId, peer: CountedPeer) {
+        if let Some((ip, subnet)) = peer.ip_and_subnet {
+            *self.ip_count.entry(ip).or_insert(0) += 1;
+            *self.subnet_count.entry(subnet).or_insert(0) += 1;
+        }
+        if peer.outbound {
+            self.outbound_count += 1;
+        } else {
+            self.inbound_count += 1;
+        }
+        self.peers.insert(peer_id, peer);
+    }
+
+    /// Decrements the counters for a disconnected peer, if it was counted
+    fn remove(&mut self, peer_id: &PeerId) {
+        let peer = match self.peers.remove(peer_id) {
+            Some(peer) => peer,
+            None => return,
+        };
+
+        if let Some((ip, subnet)) = peer.ip_and_subnet {
+            decrement(&mut self.ip_count, ip);
+            decrement(&mut self.subnet_count, subnet);
+        }
+        if peer.outbound {
+            self.outbound_count = self.outbound_count.saturating_sub(1);
+        } else {
+            self.inbound_count = self.inbound_count.saturating_sub(1);
+        }
+    }
+}
+
+/// Decrements a counter, removing it once it reaches zero
+fn decrement<K: std::hash::Hash + Eq>(counts: &mut HashMap<K, usize>, key: K) {
+    if let Some(count) = counts.get_mut(&key) {
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            counts.remove(&key);
+        }
+    }
 }
 
+/// Configuration of the connection pool behaviour
 #[derive(Clone, Debug)]
-struct ConnectionPoolConfig {
-    peer_count_desired: usize,
-    peer_count_max: usize,
-    peer_count_per_ip_max: usize,
-    peer_count_per_subnet_max: usize,
-    ipv4_subnet_mask: u8,
-    ipv6_subnet_mask: u8,
-    dialing_count_max: usize,
-    retry_down_after: Duration,
-    housekeeping_interval: Duration,
+pub struct ConnectionPoolConfig {
+    /// Number of peers we try to stay connected to
+    pub peer_count_desired: usize,
+    /// Maximum number of connected peers
+    pub peer_count_max: usize,
+    /// Maximum number of connected peers per IP address
+    pub peer_count_per_ip_max: usize,
+    /// Maximum number of connected peers per subnet
+    pub peer_count_per_subnet_max: usize,
+    /// Prefix length of the IPv4 subnets that `peer_count_per_subnet_max` applies to
+    pub ipv4_subnet_mask: u8,
+    /// Prefix length of the IPv6 subnets that `peer_count_per_subnet_max` applies to
+    pub ipv6_subnet_mask: u8,
+    /// Maximum number of connected peers that dialed us
+    pub inbound_peer_count_max: usize,
+    /// Maximum number of connected peers that we dialed
+    pub outbound_peer_count_max: usize,
+    /// Maximum number of peers being dialed at the same time
+    pub dialing_count_max: usize,
+    /// Time after which peers and addresses that are down are dialed again
+    pub retry_down_after: Duration,
+    /// Interval in which the connection pool housekeeping is run
+    pub housekeeping_interval: Duration,
 }
 
 impl Default for ConnectionPoolConfig {
@@ -58,6 +144,8 @@ impl Default for ConnectionPoolConfig {
             peer_count_per_subnet_max: 20,
             ipv4_subnet_mask: 24,
             ipv6_subnet_mask: 96,
+            inbound_peer_count_max: 4000,
+            outbound_peer_count_max: 4000,
             dialing_count_max: 3,
             retry_down_after: Duration::from_secs(60 * 10), // 10 minutes
             housekeeping_interval: Duration::from_secs(60 * 2), // 2 minutes
@@ -218,6 +306,12 @@ impl<T> std::fmt::Display for ConnectionState<T> {
 pub enum ConnectionPoolEvent {
     /// A peer has joined
     PeerJoined { peer_id: PeerId },
+    /// A connection was closed right after it was established, because the peer is banned or
+    /// a connection limit was reached
+    ConnectionRejected {
+        peer_id: PeerId,
+        reason: ConnectionPoolHandlerError,
+    },
 }
 
 type PoolNetworkBehaviourAction =
@@ -229,7 +323,7 @@ type PoolNetworkBehaviourAction =
 /// another peer. For this it maintains state about other peers such as if
 /// the peer is connected, is being dialed, is down or has failed.
 /// Also watches if we have received more connections than the allowed
-/// configured maximum per peer, IP or subnet.
+/// configured maximum per peer, IP, subnet or direction.
 pub struct ConnectionPoolBehaviour {
     /// Peer contact book. This is the data structure where information of all
     /// known peers is store. This information includes known addresses and
@@ -251,6 +345,13 @@ pub struct ConnectionPoolBehaviour {
     /// Connection state per address
     addresses: ConnectionState<Multiaddr>,
 
+    /// Reputation of misbehaving peers, used to ban them temporarily.
+    reputation: Arc<RwLock<PeerReputation>>,
+
+    /// Peers that are banned permanently. Their bans are only lifted when they are un-banned
+    /// explicitly, not when a temporary ban because of their reputation expires.
+    permanently_banned: HashSet<PeerId>,
+
     /// Queue of actions this behaviour will emit for handler execution.
     actions: VecDeque<PoolNetworkBehaviourAction>,
 
@@ -276,13 +377,10 @@ impl ConnectionPoolBehaviour {
         own_peer_id: PeerId,
         seeds: Vec<Multiaddr>,
         required_services: Services,
+        reputation: Arc<RwLock<PeerReputation>>,
+        config: ConnectionPoolConfig,
     ) -> Self {
-        let limits = ConnectionPoolLimits {
-            ip_count: HashMap::new(),
-            ipv4_count: 0,
-            ipv6_count: 0,
-        };
-        let config = ConnectionPoolConfig::default();
+        let limits = ConnectionPoolLimits::default();
         let housekeeping_timer = wasm_timer::Interval::new(config.housekeeping_interval);
 
         Self {
@@ -292,6 +390,8 @@ impl ConnectionPoolBehaviour {
             required_services,
             peer_ids: ConnectionState::new(2, config.retry_down_after),
             addresses: ConnectionState::new(4, config.retry_down_after),
+            reputation,
+            permanently_banned: HashSet::new(),
             actions: VecDeque::new(),
             active: false,
             limits,
@@ -324,6 +424,8 @@ impl ConnectionPoolBehaviour {
         if self.active
             && self.peer_ids.num_connected() < self.config.peer_count_desired
             && self.peer_ids.num_dialing() < self.config.dialing_count_max
+            && self.limits.outbound_count < self.config.outbound_peer_count_max
+            && self.limits.num_connected() < self.config.peer_count_max
         {
             // Dial peers from the contact book.
             for peer_id in self.choose_peers_to_dial() {
@@ -391,16 +493,71 @@ impl ConnectionPoolBehaviour {
         self.wake();
 
         match reason {
-            CloseReason::MaliciousPeer => self.ban_connection(peer_id),
+            CloseReason::MaliciousPeer => {
+                self.permanently_banned.insert(peer_id);
+                self.ban_connection(peer_id)
+            }
             CloseReason::GoingOffline => self.stop_connecting(),
             _ => {}
         }
     }
 
+    /// Records a misbehaviour of a peer. If this makes its reputation drop too low, the peer is
+    /// disconnected and banned until the ban duration returned here has passed.
+    pub fn report_peer(&mut self, peer_id: PeerId, event: PeerReputationEvent) -> Option<Duration> {
+        let ban_duration = self
+            .reputation
+            .write()
+            .record(peer_id, event, Instant::now());
+
+        if let Some(duration) = ban_duration {
+            info!(%peer_id, ?event, ?duration, "Banning peer because of its reputation");
+            self.disconnect_and_ban(peer_id);
+        } else {
+            debug!(%peer_id, ?event, "Lowered peer reputation");
+        }
+
+        ban_duration
+    }
+
+    /// Disconnects and bans a peer. Without a duration, the ban is permanent until the peer is
+    /// un-banned, otherwise it is lifted during housekeeping once the duration has passed.
+    pub fn ban_peer(&mut self, peer_id: PeerId, duration: Option<Duration>) {
+        match duration {
+            Some(duration) => {
+                info!(%peer_id, ?duration, "Banning peer");
+                self.reputation
+                    .write()
+                    .ban(peer_id, duration, Instant::now());
+                self.disconnect_and_ban(peer_id);
+            }
+            None => {
+                info!(%peer_id, "Banning peer permanently");
+                self.close_connection(peer_id, CloseReason::MaliciousPeer);
+            }
+        }
+    }
+
+    fn disconnect_and_ban(&mut self, peer_id: PeerId) {
+        self.actions
+            .push_back(NetworkBehaviourAction::NotifyHandler {
+                peer_id,
+                handler: NotifyHandler::Any,
+                event: ConnectionPoolHandlerError::Other(CloseReason::MaliciousPeer),
+            });
+        self.wake();
+        self.ban_connection(peer_id);
+    }
+
     fn choose_peers_to_dial(&self) -> Vec<PeerId> {
         let num_peers = usize::min(
             self.config.peer_count_desired - self.peer_ids.num_connected(),
             self.config.dialing_count_max - self.peer_ids.num_dialing(),
+        )
+        .min(
+            self.config
+                .outbound_peer_count_max
+                .saturating_sub(self.limits.outbound_count),
         );
         let contacts = self.contacts.read();
         let own_contact = contacts.get_own_contact();
@@ -412,7 +569,9 @@ impl ConnectionPoolBehaviour {
                 let peer_id = contact.peer_id();
                 if peer_id != own_peer_id
                     && self.peer_ids.can_dial(peer_id)
-                    && contact.addresses().count() > 0
+                    && contact
+                        .addresses()
+                        .any(|address| self.exceeded_limit(address, true).is_none())
                 {
                     Some(*peer_id)
                 } else {
@@ -440,7 +599,9 @@ impl ConnectionPoolBehaviour {
                 let peer_id = contact.peer_id();
                 if peer_id != own_peer_id
                     && self.peer_ids.can_dial(peer_id)
-                    && contact.addresses().count() > 0
+                    && contact
+                        .addresses()
+                        .any(|address| self.exceeded_limit(address, true).is_none())
                 {
                     Some(*peer_id)
                 } else {
@@ -462,7 +623,11 @@ impl ConnectionPoolBehaviour {
         let own_addresses: HashSet<&Multiaddr> = contacts.get_own_contact().addresses().collect();
         self.seeds
             .iter()
-            .filter(|address| !own_addresses.contains(address) && self.addresses.can_dial(*address))
+            .filter(|address| {
+                !own_addresses.contains(address)
+                    && self.addresses.can_dial(*address)
+                    && self.exceeded_limit(address, true).is_none()
+            })
             .cloned()
             .choose_multiple(&mut thread_rng(), num_seeds)
     }
@@ -482,6 +647,14 @@ impl ConnectionPoolBehaviour {
         }
         drop(contacts);
 
+        // Lift the temporary bans that expired, unless the peer is also banned permanently.
+        let expired_bans = self.reputation.write().house_keeping(Instant::now());
+        for peer_id in expired_bans {
+            if !self.permanently_banned.contains(&peer_id) {
+                self.unban_connection(peer_id);
+            }
+        }
+
         self.peer_ids.housekeeping();
         self.addresses.housekeeping();
 
@@ -503,10 +676,66 @@ impl ConnectionPoolBehaviour {
         }
     }
 
+    /// Returns the IP address of a multiaddress and the subnet it belongs to, if it has one.
+    fn ip_and_subnet(&self, address: &Multiaddr) -> Option<(IpAddr, IpNetwork)> {
+        let (ip, mask) = match address.iter().next() {
+            Some(Protocol::Ip4(ip)) => (IpAddr::V4(ip), self.config.ipv4_subnet_mask),
+            Some(Protocol::Ip6(ip)) => (IpAddr::V6(ip), self.config.ipv6_subnet_mask),
+            _ => return None,
+        };
+        IpNetwork::new_truncate(ip, mask)
+            .ok()
+            .map(|subnet| (ip, subnet))
+    }
+
+    /// Returns the limit that another connection to or from the given address would exceed,
+    /// if any.
+    fn exceeded_limit(
+        &self,
+        address: &Multiaddr,
+        outbound: bool,
+    ) -> Option<ConnectionPoolHandlerError> {
+        if self.limits.num_connected() >= self.config.peer_count_max {
+            return Some(ConnectionPoolHandlerError::MaxPeerConnectionsReached);
+        }
+
+        if outbound && self.limits.outbound_count >= self.config.outbound_peer_count_max {
+            return Some(ConnectionPoolHandlerError::MaxOutboundConnectionsReached);
+        }
+        if !outbound && self.limits.inbound_count >= self.config.inbound_peer_count_max {
+            return Some(ConnectionPoolHandlerError::MaxInboundConnectionsReached);
+        }
+
+        if let Some((ip, subnet)) = self.ip_and_subnet(address) {
+            if self.limits.num_per_ip(&ip) >= self.config.peer_count_per_ip_max {
+                return Some(ConnectionPoolHandlerError::MaxPeerPerIPConnectionsReached);
+            }
+            if self.limits.num_per_subnet(&subnet) >= self.config.peer_count_per_subnet_max {
+                return Some(match subnet {
+                    IpNetwork::V4(..) => {
+                        ConnectionPoolHandlerError::MaxIpv4SubnetConnectionsReached
+                    }
+                    IpNetwork::V6(..) => {
+                        ConnectionPoolHandlerError::MaxIpv6SubnetConnectionsReached
+                    }
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Returns the current reputation score of a peer
+    pub fn reputation_score(&self, peer_id: &PeerId) -> f64 {
+        self.reputation.read().score(peer_id, Instant::now())
+    }
+
     /// Un-bans a peer connection and its IP if we have the address for such peer ID
     pub fn unban_connection(&mut self, peer_id: PeerId) {
         // Unmark the peer ID as banned
         self.peer_ids.unmark_banned(peer_id);
+        self.permanently_banned.remove(&peer_id);
+        self.reputation.write().unban(&peer_id);
         debug!(%peer_id, "Un-banned peer");
 
         // Mark its addresses as unbanned if we have them
@@ -588,83 +817,26 @@ impl NetworkBehaviour for ConnectionPoolBehaviour {
             return;
         }
 
-        let mut close_reason = None;
-        if self.addresses.is_banned(address.clone()) {
+        let outbound = endpoint.is_dialer();
+        let close_reason = if self.addresses.is_banned(address.clone()) {
             debug!(%address, "Address is banned");
-            close_reason = Some(ConnectionPoolHandlerError::BannedIp);
+            Some(ConnectionPoolHandlerError::BannedIp)
         } else if self.peer_ids.is_banned(*peer_id) {
             debug!(%peer_id, "Peer is banned");
-            close_reason = Some(ConnectionPoolHandlerError::BannedPeer);
-        }
-
-        // Get IP from multiaddress if it exists.
-        let ip = match address.iter().next() {
-            Some(Protocol::Ip4(ip)) => {
-                IpNetwork::new_truncate(ip, self.config.ipv4_subnet_mask).ok()
-            }
-            Some(Protocol::Ip6(ip)) => {
-                IpNetwork::new_truncate(ip, self.config.ipv6_subnet_mask).ok()
-            }
-            _ => None,
+            Some(ConnectionPoolHandlerError::BannedPeer)
+        } else {
+            self.exceeded_limit(address, outbound)
         };
 
-        // If we have an IP, check connection limits per IP/subnet.
-        if let Some(ip) = ip {
-            if self.config.peer_count_per_ip_max
-                < self
-                    .limits
-                    .ip_count
-                    .get(&ip)
-                    .unwrap_or(&0)
-                    .saturating_add(1)
-            {
-                debug!(%ip, "Max peer connections per IP limit reached");
-                close_reason = Some(ConnectionPoolHandlerError::MaxPeerPerIPConnectionsReached);
-            }
-
-            if ip.is_ipv4()
-                && (self.config.peer_count_per_subnet_max
-                    < self.limits.ipv4_count.saturating_add(1))
-            {
-                debug!("Max peer connections per IPv4 subnet limit reached");
-                close_reason = Some(ConnectionPoolHandlerError::MaxIpv4SubnetConnectionsReached);
-            }
-
-            if ip.is_ipv6()
-                && (self.config.peer_count_per_subnet_max
-                    < self.limits.ipv6_count.saturating_add(1))
-            {
-                debug!("Max peer connections per IPv6 subnet limit reached");
-                close_reason = Some(ConnectionPoolHandlerError::MaxIpv6SubnetConnectionsReached);
-            }
-
-            if self.config.peer_count_max
-                < self
-                    .limits
-                    .ipv4_count
-                    .saturating_add(self.limits.ipv6_count)
-                    .saturating_add(1)
-            {
-                debug!("Max peer connections limit reached");
-                close_reason = Some(ConnectionPoolHandlerError::MaxPeerConnectionsReached);
-            }
-
-            if close_reason.is_none() {
-                // Increment peer counts per IP if we are not going to close the connection
-                let value = self.limits.ip_count.entry(ip).or_insert(0);
-                *value = value.saturating_add(1);
-                match ip {
-                    IpNetwork::V4(..) => {
-                        self.limits.ipv4_count = self.limits.ipv4_count.saturating_add(1)
-                    }
-                    IpNetwork::V6(..) => {
-                        self.limits.ipv6_count = self.limits.ipv6_count.saturating_add(1)
-                    }
-                };
-            }
-        }
-
         if let Some(close_reason) = close_reason {
+            debug!(%peer_id, %address, reason = %close_reason, "Rejecting connection");
+            self.actions
+                .push_back(NetworkBehaviourAction::GenerateEvent(
+                    ConnectionPoolEvent::ConnectionRejected {
+                        peer_id: *peer_id,
+                        reason: close_reason.clone(),
+                    },
+                ));
             // Notify the handler that the connection must be closed
             self.actions
                 .push_back(NetworkBehaviourAction::NotifyHandler {
@@ -676,6 +848,16 @@ impl NetworkBehaviour for ConnectionPoolBehaviour {
             return;
         }
 
+        // Count the connection against the connection limits.
+        let ip_and_subnet = self.ip_and_subnet(address);
+        self.limits.add(
+            *peer_id,
+            CountedPeer {
+                ip_and_subnet,
+                outbound,
+            },
+        );
+
         // Peer is connected, mark it as such.
         self.peer_ids.mark_connected(*peer_id);
         self.addresses.mark_connected(address.clone());
@@ -704,33 +886,8 @@ impl NetworkBehaviour for ConnectionPoolBehaviour {
 
         let address = endpoint.get_remote_address();
 
-        let ip = match address.iter().next() {
-            Some(Protocol::Ip4(ip)) => {
-                Some(IpNetwork::new_truncate(ip, self.config.ipv4_subnet_mask).unwrap())
-            }
-            Some(Protocol::Ip6(ip)) => {
-                Some(IpNetwork::new_truncate(ip, self.config.ipv6_subnet_mask).unwrap())
-            }
-            _ => None,
-        };
-
-        // Decrement IP counters if needed
-        if let Some(ip) = ip {
-            let value = self.limits.ip_count.entry(ip).or_insert(1);
-            *value = value.saturating_sub(1);
-            if *self.limits.ip_count.get(&ip).unwrap() == 0 {
-                self.limits.ip_count.remove(&ip);
-            }
-
-            match ip {
-                IpNetwork::V4(..) => {
-                    self.limits.ipv4_count = self.limits.ipv4_count.saturating_sub(1)
-                }
-                IpNetwork::V6(..) => {
-                    self.limits.ipv6_count = self.limits.ipv6_count.saturating_sub(1)
-                }
-            };
-        }
+        // Decrement the counters of the connection limits, if the peer was counted.
+        self.limits.remove(peer_id);
 
         self.addresses.mark_closed(address.clone());
         self.peer_ids.mark_closed(*peer_id);
@@ -808,3 +965,288 @@ impl NetworkBehaviour for ConnectionPoolBehaviour {
         Poll::Pending
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use libp2p::{
+        core::{connection::ConnectionId, ConnectedPoint, Endpoint},
+        identity::Keypair,
+        swarm::{NetworkBehaviour, NetworkBehaviourAction},
+        Multiaddr, PeerId,
+    };
+    use parking_lot::RwLock;
+
+    use nimiq_network_interface::{
+        network::{CloseReason, PeerReputationEvent},
+        peer_info::Services,
+    };
+    use nimiq_test_log::test;
+
+    use super::{ConnectionPoolBehaviour, ConnectionPoolConfig, ConnectionPoolEvent};
+    use crate::{
+        connection_pool::handler::ConnectionPoolHandlerError,
+        discovery::peer_contacts::{PeerContact, PeerContactBook},
+        reputation::{PeerReputation, ReputationConfig},
+    };
+
+    fn connection_pool(config: ConnectionPoolConfig) -> ConnectionPoolBehaviour {
+        let keypair = Keypair::generate_ed25519();
+        let own_contact =
+            PeerContact::new(vec![], keypair.public(), Services::FULL_BLOCKS, None).sign(&keypair);
+        ConnectionPoolBehaviour::new(
+            Arc::new(RwLock::new(PeerContactBook::new(own_contact))),
+            keypair.public().to_peer_id(),
+            vec![],
+            Services::FULL_BLOCKS,
+            Arc::new(RwLock::new(
+                PeerReputation::new(ReputationConfig::default()),
+            )),
+            config,
+        )
+    }
+
+    fn endpoint(address: &str, outbound: bool) -> ConnectedPoint {
+        let address: Multiaddr = address.parse().unwrap();
+        if outbound {
+            ConnectedPoint::Dialer {
+                address,
+                role_override: Endpoint::Dialer,
+            }
+        } else {
+            ConnectedPoint::Listener {
+                local_addr: "/ip4/127.0.0.1/tcp/8443".parse().unwrap(),
+                send_back_addr: address,
+            }
+        }
+    }
+
+    /// Establishes a connection to the given peer and returns the reason it was rejected with,
+    /// if any.
+    fn connect(
+        pool: &mut ConnectionPoolBehaviour,
+        peer_id: PeerId,
+        endpoint: &ConnectedPoint,
+    ) -> Option<ConnectionPoolHandlerError> {
+        pool.inject_connection_established(&peer_id, &ConnectionId::new(0), endpoint, None, 0);
+        pool.actions.drain(..).find_map(|action| match action {
+            NetworkBehaviourAction::GenerateEvent(ConnectionPoolEvent::ConnectionRejected {
+                reason,
+                ..
+            }) => Some(reason),
+            _ => None,
+        })
+    }
+
+    fn disconnect(pool: &mut ConnectionPoolBehaviour, peer_id: PeerId, endpoint: &ConnectedPoint) {
+        let handler = pool.new_handler();
+        pool.inject_connection_closed(&peer_id, &ConnectionId::new(0), endpoint, handler, 0);
+    }
+
+    #[test]
+    fn it_bans_and_unbans_peers_by_reputation() {
+        let mut pool = connection_pool(ConnectionPoolConfig::default());
+        let peer_id = PeerId::random();
+        let endpoint = endpoint("/ip4/10.0.0.1/tcp/8443", false);
+        assert!(connect(&mut pool, peer_id, &endpoint).is_none());
+
+        // The first invalid block only lowers the reputation.
+        assert_eq!(
+            pool.report_peer(peer_id, PeerReputationEvent::InvalidBlock),
+            None
+        );
+        assert!(pool.reputation_score(&peer_id) < 0.0);
+        assert!(!pool.peer_ids.is_banned(peer_id));
+
+        // The second one bans the peer, which gets disconnected and can't reconnect.
+        assert!(pool
+            .report_peer(peer_id, PeerReputationEvent::InvalidBlock)
+            .is_some());
+        assert!(pool.peer_ids.is_banned(peer_id));
+        assert!(pool.reputation.read().is_banned(&peer_id));
+        assert!(pool.actions.iter().any(|action| matches!(
+            action,
+            NetworkBehaviourAction::NotifyHandler {
+                peer_id: notified,
+                event: ConnectionPoolHandlerError::Other(_),
+                ..
+            } if *notified == peer_id
+        )));
+        disconnect(&mut pool, peer_id, &endpoint);
+        assert!(matches!(
+            connect(&mut pool, peer_id, &endpoint),
+            Some(ConnectionPoolHandlerError::BannedPeer)
+        ));
+
+        // Un-banning lifts the ban in the pool and in the reputation.
+        pool.unban_connection(peer_id);
+        assert!(!pool.peer_ids.is_banned(peer_id));
+        assert!(!pool.reputation.read().is_banned(&peer_id));
+        assert!(connect(&mut pool, peer_id, &endpoint).is_none());
+    }
+
+    #[test]
+    fn it_lifts_expired_bans_during_housekeeping() {
+        let mut pool = connection_pool(ConnectionPoolConfig::default());
+        let temporary = PeerId::random();
+        let permanent = PeerId::random();
+
+        pool.ban_peer(temporary, Some(Duration::ZERO));
+        pool.ban_peer(permanent, None);
+        assert!(pool.peer_ids.is_banned(temporary));
+        assert!(pool.peer_ids.is_banned(permanent));
+
+        // Only the temporary ban expires.
+        pool.housekeeping();
+        assert!(!pool.peer_ids.is_banned(temporary));
+        assert!(pool.peer_ids.is_banned(permanent));
+    }
+
+    #[test]
+    fn it_keeps_permanent_bans_when_temporary_bans_expire() {
+        let mut pool = connection_pool(ConnectionPoolConfig::default());
+        let peer_id = PeerId::random();
+
+        // A temporary ban that expires after the peer got banned permanently doesn't lift the
+        // permanent ban.
+        pool.ban_peer(peer_id, None);
+        pool.ban_peer(peer_id, Some(Duration::ZERO));
+        pool.housekeeping();
+        assert!(pool.peer_ids.is_banned(peer_id));
+
+        // Neither does one that was issued before.
+        let other = PeerId::random();
+        pool.ban_peer(other, Some(Duration::ZERO));
+        pool.close_connection(other, CloseReason::MaliciousPeer);
+        pool.housekeeping();
+        assert!(pool.peer_ids.is_banned(other));
+
+        // Un-banning explicitly lifts the permanent ban.
+        pool.unban_connection(peer_id);
+        assert!(!pool.peer_ids.is_banned(peer_id));
+        pool.ban_peer(peer_id, Some(Duration::ZERO));
+        pool.housekeeping();
+        assert!(!pool.peer_ids.is_banned(peer_id));
+    }
+
+    #[test]
+    fn it_rejects_connections_per_ip() {
+        let mut pool = connection_pool(ConnectionPoolConfig {
+            peer_count_per_ip_max: 1,
+            ..Default::default()
+        });
+
+        let first = endpoint("/ip4/10.0.0.1/tcp/8443", false);
+        assert!(connect(&mut pool, PeerId::random(), &first).is_none());
+
+        let same_ip = endpoint("/ip4/10.0.0.1/tcp/8444", true);
+        assert!(matches!(
+            connect(&mut pool, PeerId::random(), &same_ip),
+            Some(ConnectionPoolHandlerError::MaxPeerPerIPConnectionsReached)
+        ));
+
+        let other_ip = endpoint("/ip4/10.0.0.2/tcp/8443", false);
+        assert!(connect(&mut pool, PeerId::random(), &other_ip).is_none());
+    }
+
+    #[test]
+    fn it_rejects_connections_per_subnet() {
+        let mut pool = connection_pool(ConnectionPoolConfig {
+            peer_count_per_subnet_max: 1,
+            ..Default::default()
+        });
+
+        let ipv4 = endpoint("/ip4/10.0.0.1/tcp/8443", false);
+        assert!(connect(&mut pool, PeerId::random(), &ipv4).is_none());
+        let same_ipv4_subnet = endpoint("/ip4/10.0.0.2/tcp/8443", false);
+        assert!(matches!(
+            connect(&mut pool, PeerId::random(), &same_ipv4_subnet),
+            Some(ConnectionPoolHandlerError::MaxIpv4SubnetConnectionsReached)
+        ));
+        let other_ipv4_subnet = endpoint("/ip4/10.0.1.1/tcp/8443", false);
+        assert!(connect(&mut pool, PeerId::random(), &other_ipv4_subnet).is_none());
+
+        let ipv6 = endpoint("/ip6/2001:db8::1/tcp/8443", false);
+        assert!(connect(&mut pool, PeerId::random(), &ipv6).is_none());
+        let same_ipv6_subnet = endpoint("/ip6/2001:db8::2/tcp/8443", false);
+        assert!(matches!(
+            connect(&mut pool, PeerId::random(), &same_ipv6_subnet),
+            Some(ConnectionPoolHandlerError::MaxIpv6SubnetConnectionsReached)
+        ));
+    }
+
+    #[test]
+    fn it_rejects_connections_per_direction() {
+        let mut pool = connection_pool(ConnectionPoolConfig {
+            inbound_peer_count_max: 1,
+            outbound_peer_count_max: 1,
+            ..Default::default()
+        });
+
+        let inbound = endpoint("/ip4/10.0.0.1/tcp/8443", false);
+        assert!(connect(&mut pool, PeerId::random(), &inbound).is_none());
+        let inbound = endpoint("/ip4/10.0.1.1/tcp/8443", false);
+        assert!(matches!(
+            connect(&mut pool, PeerId::random(), &inbound),
+            Some(ConnectionPoolHandlerError::MaxInboundConnectionsReached)
+        ));
+
+        let outbound = endpoint("/ip4/10.0.2.1/tcp/8443", true);
+        assert!(connect(&mut pool, PeerId::random(), &outbound).is_none());
+        let outbound = endpoint("/ip4/10.0.3.1/tcp/8443", true);
+        assert!(matches!(
+            connect(&mut pool, PeerId::random(), &outbound),
+            Some(ConnectionPoolHandlerError::MaxOutboundConnectionsReached)
+        ));
+    }
+
+    #[test]
+    fn it_decrements_the_counters_on_close() {
+        let mut pool = connection_pool(ConnectionPoolConfig {
+            peer_count_per_ip_max: 1,
+            ..Default::default()
+        });
+        let peer_id = PeerId::random();
+        let endpoint = endpoint("/ip4/10.0.0.1/tcp/8443", false);
+
+        assert!(connect(&mut pool, peer_id, &endpoint).is_none());
+        assert_eq!(pool.limits.inbound_count, 1);
+        assert_eq!(pool.limits.ip_count.len(), 1);
+        assert_eq!(pool.limits.subnet_count.len(), 1);
+
+        disconnect(&mut pool, peer_id, &endpoint);
+        assert_eq!(pool.limits.num_connected(), 0);
+        assert!(pool.limits.ip_count.is_empty());
+        assert!(pool.limits.subnet_count.is_empty());
+        assert!(pool.limits.peers.is_empty());
+
+        // Another peer can connect from the same IP again.
+        assert!(connect(&mut pool, PeerId::random(), &endpoint).is_none());
+    }
+
+    #[test]
+    fn it_does_not_count_rejected_connections() {
+        let mut pool = connection_pool(ConnectionPoolConfig {
+            peer_count_per_ip_max: 1,
+            ..Default::default()
+        });
+        let accepted = PeerId::random();
+        let rejected = PeerId::random();
+        let endpoint = endpoint("/ip4/10.0.0.1/tcp/8443", false);
+
+        assert!(connect(&mut pool, accepted, &endpoint).is_none());
+        assert!(connect(&mut pool, rejected, &endpoint).is_some());
+        assert_eq!(pool.limits.inbound_count, 1);
+        assert_eq!(pool.limits.peers.len(), 1);
+
+        // Closing the rejected connection leaves the counters of the accepted one untouched.
+        disconnect(&mut pool, rejected, &endpoint);
+        assert_eq!(pool.limits.inbound_count, 1);
+        assert_eq!(pool.limits.num_per_ip(&"10.0.0.1".parse().unwrap()), 1);
+
+        disconnect(&mut pool, accepted, &endpoint);
+        assert_eq!(pool.limits.num_connected(), 0);
+        assert!(pool.limits.ip_count.is_empty());
+    }
+}
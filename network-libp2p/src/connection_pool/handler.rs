@@ -48,6 +48,14 @@ pub enum ConnectionPoolHandlerError {
     #[error("Maximum peers connections per IP has been reached")]
     MaxPeerPerIPConnectionsReached,
 
+    /// Maximum inbound peer connections has been reached
+    #[error("Maximum inbound peer connections has been reached")]
+    MaxInboundConnectionsReached,
+
+    /// Maximum outbound peer connections has been reached
+    #[error("Maximum outbound peer connections has been reached")]
+    MaxOutboundConnectionsReached,
+
     /// The application sent the network to close the connection with the
     /// provided reason
     #[error("Application sent a close action with reason: {0:?}")]
@@ -238,6 +238,7 @@ impl NetworkBehaviour for DiscoveryBehaviour {
                 peer_contact: signed_peer_contact,
             } => {
                 if let Some(peer_contact) = self.peer_contact_book.read().get(&peer_id) {
+                    peer_contact.set_last_seen();
                     self.events.push_back(NetworkBehaviourAction::GenerateEvent(
                         DiscoveryEvent::Established {
                             peer_id: signed_peer_contact.public_key().clone().to_peer_id(),
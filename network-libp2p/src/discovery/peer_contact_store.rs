@@ -0,0 +1,105 @@
+use std::fmt::Debug;
+#[cfg(feature = "database-storage")]
+use std::{borrow::Cow, io};
+
+use beserial::{Deserialize, Serialize};
+use libp2p::PeerId;
+#[cfg(feature = "database-storage")]
+use nimiq_database::{
+    cursor::ReadCursor, Database, Environment, ReadTransaction, WriteTransaction,
+};
+#[cfg(feature = "database-storage")]
+use nimiq_database_value::{AsDatabaseBytes, FromDatabaseValue};
+
+use super::peer_contacts::SignedPeerContact;
+
+/// A peer contact as persisted by a [`PeerContactStore`], together with its meta-data.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct StoredPeerContact {
+    /// The signed peer contact.
+    pub contact: SignedPeerContact,
+
+    /// The last known score of the peer.
+    pub score: f64,
+
+    /// When the peer was last seen in *seconds* since unix epoch. `None` if we never connected to it.
+    pub last_seen: Option<u64>,
+}
+
+/// Defines an interface for persisting the peer contact book across restarts.
+pub trait PeerContactStore: Debug + Send + Sync {
+    /// Loads all stored peer contacts.
+    fn load(&self) -> Vec<StoredPeerContact>;
+
+    /// Inserts the given peer contacts, replacing the stored ones of the same peers, and removes
+    /// the stored peer contacts of the given peers.
+    fn update(&self, changed: Vec<StoredPeerContact>, removed: Vec<PeerId>);
+}
+
+#[cfg(feature = "database-storage")]
+impl AsDatabaseBytes for StoredPeerContact {
+    fn as_database_bytes(&self) -> Cow<[u8]> {
+        let v = Serialize::serialize_to_vec(&self);
+        Cow::Owned(v)
+    }
+}
+
+#[cfg(feature = "database-storage")]
+impl FromDatabaseValue for StoredPeerContact {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = io::Cursor::new(bytes);
+        Ok(Deserialize::deserialize(&mut cursor)?)
+    }
+}
+
+#[cfg(feature = "database-storage")]
+/// DB implementation of a PeerContactStore meant for persistent storage
+#[derive(Debug)]
+pub struct DBPeerContactStore {
+    /// Environment for the DB creation and transaction handling.
+    env: Environment,
+    // A database of peer contacts indexed by peer ID.
+    contacts_db: Database,
+}
+
+#[cfg(feature = "database-storage")]
+impl DBPeerContactStore {
+    const CONTACTS_DB_NAME: &'static str = "PeerContacts";
+
+    pub fn new(env: Environment) -> Self {
+        let contacts_db = env.open_database(Self::CONTACTS_DB_NAME.to_string());
+
+        Self { env, contacts_db }
+    }
+}
+
+#[cfg(feature = "database-storage")]
+impl PeerContactStore for DBPeerContactStore {
+    fn load(&self) -> Vec<StoredPeerContact> {
+        let txn = ReadTransaction::new(&self.env);
+        let mut cursor = txn.cursor(&self.contacts_db);
+
+        let mut contacts = vec![];
+        let mut entry = cursor.first::<Vec<u8>, StoredPeerContact>();
+        while let Some((_, contact)) = entry {
+            contacts.push(contact);
+            entry = cursor.next::<Vec<u8>, StoredPeerContact>();
+        }
+        contacts
+    }
+
+    fn update(&self, changed: Vec<StoredPeerContact>, removed: Vec<PeerId>) {
+        let mut txn = WriteTransaction::new(&self.env);
+        for peer_id in removed {
+            txn.remove(&self.contacts_db, &peer_id.to_bytes());
+        }
+        for contact in changed {
+            let key = contact.contact.inner.peer_id().to_bytes();
+            txn.put(&self.contacts_db, &key, &contact);
+        }
+        txn.commit();
+    }
+}
@@ -11,11 +11,14 @@ use libp2p::{
     Multiaddr, PeerId,
 };
 use parking_lot::RwLock;
+use tokio::{runtime::Handle, task::JoinHandle};
 
 use beserial::{Deserialize, Serialize};
 use nimiq_network_interface::peer_info::Services;
 use nimiq_utils::tagged_signing::{TaggedKeypair, TaggedSignable, TaggedSignature};
 
+use super::peer_contact_store::{PeerContactStore, StoredPeerContact};
+
 /// A plain peer contact. This contains:
 ///
 ///  - A set of multi-addresses for the peer.
@@ -156,6 +159,8 @@ impl SignedPeerContact {
 )]
 struct PeerContactMeta {
     score: f64,
+    /// When we last established a peer exchange with the peer, in *seconds* since unix epoch.
+    last_seen: Option<u64>,
 }
 
 /// This encapsulates a peer contact (signed), but also pre-computes frequently used values such as `peer_id` and
@@ -179,7 +184,10 @@ impl From<SignedPeerContact> for PeerContactInfo {
         Self {
             peer_id,
             contact,
-            meta: RwLock::new(PeerContactMeta { score: 0. }),
+            meta: RwLock::new(PeerContactMeta {
+                score: 0.,
+                last_seen: None,
+            }),
         }
     }
 }
@@ -243,6 +251,19 @@ impl PeerContactInfo {
     pub fn set_score(&self, score: f64) {
         self.meta.write().score = score;
     }
+
+    /// Gets the time the peer was last seen in seconds since unix epoch
+    pub fn last_seen(&self) -> Option<u64> {
+        self.meta.read().last_seen
+    }
+
+    /// Sets the time the peer was last seen to the current system time
+    pub fn set_last_seen(&self) {
+        self.meta.write().last_seen = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .ok()
+            .map(|time| time.as_secs());
+    }
 }
 
 /// Main structure that holds the peer information that has been obtained or
@@ -254,6 +275,12 @@ pub struct PeerContactBook {
     /// Contact information for other peers in the network indexed by their
     /// peer ID.
     peer_contacts: HashMap<PeerId, Arc<PeerContactInfo>>,
+    /// Optional store the peer contacts are persisted to during house-keeping.
+    store: Option<Arc<dyn PeerContactStore>>,
+    /// The peer contacts as last persisted to the store, indexed by their peer ID.
+    persisted_contacts: HashMap<PeerId, StoredPeerContact>,
+    /// The task persisting the latest changes to the store, if any.
+    pending_store: Option<JoinHandle<()>>,
 }
 
 impl PeerContactBook {
@@ -265,9 +292,55 @@ impl PeerContactBook {
         Self {
             own_peer_contact: own_peer_contact.into(),
             peer_contacts: HashMap::new(),
+            store: None,
+            persisted_contacts: HashMap::new(),
+            pending_store: None,
         }
     }
 
+    /// Creates a new `PeerContactBook` given our own peer contact information and a store to
+    /// persist the peer contacts to. The peer contacts previously persisted in the store are
+    /// loaded, skipping seeds and contacts with an invalid signature, and then subjected to
+    /// house-keeping.
+    pub fn with_store(
+        own_peer_contact: SignedPeerContact,
+        store: Box<dyn PeerContactStore>,
+    ) -> Self {
+        let mut peer_contact_book = Self::new(own_peer_contact);
+        let own_peer_id = peer_contact_book.own_peer_contact.peer_id;
+
+        for stored in store.load() {
+            let peer_id = stored.contact.inner.peer_id();
+            peer_contact_book
+                .persisted_contacts
+                .insert(peer_id, stored.clone());
+
+            if stored.contact.inner.is_seed() || !stored.contact.verify() {
+                continue;
+            }
+
+            let info = PeerContactInfo::from(stored.contact);
+            if info.peer_id == own_peer_id {
+                continue;
+            }
+            *info.meta.write() = PeerContactMeta {
+                score: stored.score,
+                last_seen: stored.last_seen,
+            };
+            peer_contact_book
+                .peer_contacts
+                .insert(info.peer_id, Arc::new(info));
+        }
+        debug!(
+            num_peer_contacts = peer_contact_book.peer_contacts.len(),
+            "Loaded persisted peer contacts"
+        );
+
+        peer_contact_book.store = Some(Arc::from(store));
+        peer_contact_book.house_keeping();
+        peer_contact_book
+    }
+
     /// Insert a peer contact or update an existing one
     ///
     /// # TODO
@@ -416,7 +489,8 @@ impl PeerContactBook {
     }
 
     /// Removes peer contacts that have already exceeded the maximum age as
-    /// defined in `MAX_PEER_AGE`.
+    /// defined in `MAX_PEER_AGE` and persists the changed ones, if a store
+    /// is configured.
     pub fn house_keeping(&mut self) {
         if let Ok(unix_time) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
             let delete_peers = self
@@ -440,6 +514,72 @@ impl PeerContactBook {
                 self.peer_contacts.remove(&peer_id);
             }
         }
+
+        self.persist();
+    }
+
+    /// Persists the peer contacts that changed since they were last persisted and removes the
+    /// ones that are gone from the store, if a store is configured. Writing to the store may
+    /// block, so it is done on the blocking thread pool of the runtime, if there is one. If the
+    /// previous changes are still being written, the changes are persisted next time.
+    fn persist(&mut self) {
+        let store = match &self.store {
+            Some(store) => Arc::clone(store),
+            None => return,
+        };
+
+        if let Some(pending_store) = &self.pending_store {
+            if !pending_store.is_finished() {
+                debug!("Peer contacts are still being persisted");
+                return;
+            }
+        }
+
+        let own_peer_id = self.own_peer_contact.peer_id;
+        let contacts: HashMap<PeerId, StoredPeerContact> = self
+            .peer_contacts
+            .values()
+            .filter(|info| !info.is_seed() && info.peer_id != own_peer_id)
+            .map(|info| {
+                let meta = info.meta.read();
+                let contact = StoredPeerContact {
+                    contact: info.contact.clone(),
+                    score: meta.score,
+                    last_seen: meta.last_seen,
+                };
+                (info.peer_id, contact)
+            })
+            .collect();
+
+        let changed: Vec<StoredPeerContact> = contacts
+            .iter()
+            .filter(|(peer_id, contact)| self.persisted_contacts.get(peer_id) != Some(contact))
+            .map(|(_, contact)| contact.clone())
+            .collect();
+        let removed: Vec<PeerId> = self
+            .persisted_contacts
+            .keys()
+            .filter(|peer_id| !contacts.contains_key(peer_id))
+            .cloned()
+            .collect();
+        self.persisted_contacts = contacts;
+
+        if changed.is_empty() && removed.is_empty() {
+            return;
+        }
+
+        debug!(
+            num_changed = changed.len(),
+            num_removed = removed.len(),
+            "Persisting peer contacts"
+        );
+        match Handle::try_current() {
+            Ok(handle) => {
+                self.pending_store =
+                    Some(handle.spawn_blocking(move || store.update(changed, removed)));
+            }
+            Err(_) => store.update(changed, removed),
+        }
     }
 }
 
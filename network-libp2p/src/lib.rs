@@ -11,6 +11,7 @@ mod network;
 #[cfg(feature = "metrics")]
 mod network_metrics;
 mod rate_limiting;
+mod reputation;
 
 pub const REQRES_PROTOCOL: &[u8] = b"/nimiq/reqres/0.0.1";
 pub const MESSAGE_PROTOCOL: &[u8] = b"/nimiq/message/0.0.1";
@@ -18,6 +19,8 @@ pub const DISCOVERY_PROTOCOL: &[u8] = b"/nimiq/discovery/0.0.1";
 
 pub use libp2p::{self, identity::Keypair, swarm::NetworkInfo, PeerId};
 
-pub use config::{Config, TlsConfig};
+pub use config::{Config, DhtParams, GossipsubParams, TlsConfig};
+pub use connection_pool::behaviour::ConnectionPoolConfig;
 pub use error::NetworkError;
-pub use network::Network;
+pub use network::{ConnectionDirection, Network, PeerDetails};
+pub use reputation::{PeerReputationInfo, ReputationConfig};
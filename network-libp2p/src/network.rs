@@ -3,6 +3,7 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
 use base64::Engine;
@@ -15,6 +16,7 @@ use libp2p::{
     core::{
         muxing::StreamMuxerBox,
         transport::{Boxed, MemoryTransport},
+        ConnectedPoint,
     },
     gossipsub::{
         error::PublishError, GossipsubEvent, GossipsubMessage, IdentTopic, MessageAcceptance,
@@ -52,8 +54,8 @@ use beserial::{Deserialize, Serialize};
 use nimiq_bls::CompressedPublicKey;
 use nimiq_network_interface::{
     network::{
-        CloseReason, MsgAcceptance, Network as NetworkInterface, NetworkEvent, PubsubId,
-        SubscribeEvents, Topic,
+        CloseReason, MsgAcceptance, Network as NetworkInterface, NetworkEvent, PeerReputationEvent,
+        PubsubId, SubscribeEvents, Topic,
     },
     peer_info::{PeerInfo, Services},
     request::{
@@ -73,6 +75,7 @@ use crate::{
     discovery::{behaviour::DiscoveryEvent, peer_contacts::PeerContactBook},
     dispatch::codecs::typed::{IncomingRequest, OutgoingResponse},
     rate_limiting::{PendingDeletion, RateLimit},
+    reputation::{PeerReputation, PeerReputationInfo},
     Config, NetworkError, TlsConfig,
 };
 
@@ -150,9 +153,55 @@ pub(crate) enum NetworkAction {
         peer_id: PeerId,
         reason: CloseReason,
     },
+    BanPeer {
+        peer_id: PeerId,
+        duration: Option<Duration>,
+    },
     UnbanPeer {
         peer_id: PeerId,
     },
+    PeerConnection {
+        peer_id: PeerId,
+        output: oneshot::Sender<Option<PeerConnection>>,
+    },
+}
+
+/// Direction of the connection to a peer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionDirection {
+    /// The peer dialed us.
+    Inbound,
+    /// We dialed the peer.
+    Outbound,
+}
+
+/// What the swarm task knows about the connection to a peer.
+#[derive(Clone, Debug)]
+pub(crate) struct PeerConnection {
+    direction: ConnectionDirection,
+    /// When the first connection to the peer was established, in seconds since unix epoch.
+    connected_since: u64,
+    /// The agent version the peer sent us via the identify protocol.
+    user_agent: Option<String>,
+    /// The listen addresses the peer sent us via the identify protocol.
+    listen_addresses: Vec<Multiaddr>,
+}
+
+/// Details about a connected peer.
+#[derive(Clone, Debug)]
+pub struct PeerDetails {
+    pub peer_id: PeerId,
+    /// The address we are connected to the peer at.
+    pub address: Multiaddr,
+    /// The addresses the peer listens on, as far as it told us.
+    pub listen_addresses: Vec<Multiaddr>,
+    pub services: Services,
+    pub user_agent: Option<String>,
+    pub direction: ConnectionDirection,
+    /// When the first connection to the peer was established, in seconds since unix epoch.
+    pub connected_since: u64,
+    /// The reputation score of the peer.
+    pub score: f64,
 }
 
 struct ValidateMessage<P: Clone> {
@@ -189,6 +238,7 @@ struct TaskState {
     requests_initiated: HashMap<RequestId, Instant>,
     response_channels: HashMap<RequestId, ResponseChannel<OutgoingResponse>>,
     receive_requests: HashMap<RequestType, mpsc::Sender<(Bytes, RequestId, PeerId)>>,
+    peer_connections: HashMap<PeerId, PeerConnection>,
 }
 
 #[derive(Clone, Debug)]
@@ -215,6 +265,10 @@ pub struct Network {
     action_tx: mpsc::Sender<NetworkAction>,
     /// Stream used to send validation messages
     validate_tx: mpsc::UnboundedSender<ValidateMessage<PeerId>>,
+    /// Stream used to report misbehaving peers
+    report_tx: mpsc::UnboundedSender<(PeerId, PeerReputationEvent)>,
+    /// Reputation of misbehaving peers
+    reputation: Arc<RwLock<PeerReputation>>,
     /// Maintains the rate limits being enforced for our peers. The limits are enforced by
     /// peer_id and request type.
     peer_request_limits: Arc<Mutex<HashMap<PeerId, HashMap<u16, RateLimit>>>>,
@@ -236,15 +290,16 @@ impl Network {
     ///
     pub async fn new(
         clock: Arc<OffsetTime>,
-        config: Config,
+        mut config: Config,
         executor: impl TaskExecutor + Send + Clone + 'static,
     ) -> Self {
         let required_services = config.required_services;
-        // TODO: persist to disk
-        let own_peer_contact = config.peer_contact.clone();
-        let contacts = Arc::new(RwLock::new(PeerContactBook::new(
-            own_peer_contact.sign(&config.keypair),
-        )));
+        let reputation = Arc::new(RwLock::new(PeerReputation::new(config.reputation.clone())));
+        let own_peer_contact = config.peer_contact.clone().sign(&config.keypair);
+        let contacts = Arc::new(RwLock::new(match config.peer_contact_store.take() {
+            Some(store) => PeerContactBook::with_store(own_peer_contact, store),
+            None => PeerContactBook::new(own_peer_contact),
+        }));
         let params = PeerScoreParams {
             ip_colocation_factor_threshold: 20.0,
             ..Default::default()
@@ -253,6 +308,7 @@ impl Network {
             clock,
             config,
             Arc::clone(&contacts),
+            Arc::clone(&reputation),
             params.clone(),
             executor.clone(),
         );
@@ -263,6 +319,7 @@ impl Network {
         let (events_tx, _) = broadcast::channel(64);
         let (action_tx, action_rx) = mpsc::channel(64);
         let (validate_tx, validate_rx) = mpsc::unbounded_channel();
+        let (report_tx, report_rx) = mpsc::unbounded_channel();
         let peer_request_limits = Arc::new(Mutex::new(HashMap::new()));
         let rate_limits_pending_deletion = Arc::new(Mutex::new(PendingDeletion::default()));
 
@@ -279,6 +336,7 @@ impl Network {
             events_tx.clone(),
             action_rx,
             validate_rx,
+            report_rx,
             Arc::clone(&connected_peers),
             Arc::clone(&peer_request_limits),
             Arc::clone(&rate_limits_pending_deletion),
@@ -294,6 +352,8 @@ impl Network {
             events_tx,
             action_tx,
             validate_tx,
+            report_tx,
+            reputation,
             peer_request_limits,
             #[cfg(feature = "metrics")]
             metrics,
@@ -395,6 +455,7 @@ impl Network {
         clock: Arc<OffsetTime>,
         config: Config,
         contacts: Arc<RwLock<PeerContactBook>>,
+        reputation: Arc<RwLock<PeerReputation>>,
         peer_score_params: PeerScoreParams,
         executor: impl TaskExecutor + Clone + Send + 'static,
     ) -> Swarm<NimiqBehaviour> {
@@ -403,7 +464,7 @@ impl Network {
         let transport =
             Self::new_transport(&config.keypair, config.memory_transport, &config.tls).unwrap();
 
-        let behaviour = NimiqBehaviour::new(config, clock, contacts, peer_score_params);
+        let behaviour = NimiqBehaviour::new(config, clock, contacts, reputation, peer_score_params);
 
         let limits = ConnectionLimits::default()
             .with_max_pending_incoming(Some(16))
@@ -435,6 +496,7 @@ impl Network {
         events_tx: broadcast::Sender<NetworkEvent<PeerId>>,
         mut action_rx: mpsc::Receiver<NetworkAction>,
         mut validate_rx: mpsc::UnboundedReceiver<ValidateMessage<PeerId>>,
+        mut report_rx: mpsc::UnboundedReceiver<(PeerId, PeerReputationEvent)>,
         connected_peers: Arc<RwLock<HashMap<PeerId, PeerInfo>>>,
         peer_request_limits: Arc<Mutex<HashMap<PeerId, HashMap<u16, RateLimit>>>>,
         rate_limits_pending_deletion: Arc<Mutex<PendingDeletion>>,
@@ -469,6 +531,11 @@ impl Network {
                             }
                         }
                     },
+                    report = report_rx.recv() => {
+                        if let Some((peer_id, event)) = report {
+                            Self::report_peer_impl(&mut swarm, peer_id, event, #[cfg(feature = "metrics")] &metrics);
+                        }
+                    },
                     event = swarm.next() => {
                         if let Some(event) = event {
                             Self::handle_event(event, &events_tx, &mut swarm, &mut task_state, &connected_peers, Arc::clone(&peer_request_limits), Arc::clone(&rate_limits_pending_deletion), #[cfg( feature = "metrics")] &metrics);
@@ -503,6 +570,7 @@ impl Network {
         events_tx: broadcast::Sender<NetworkEvent<PeerId>>,
         mut action_rx: mpsc::Receiver<NetworkAction>,
         mut validate_rx: mpsc::UnboundedReceiver<ValidateMessage<PeerId>>,
+        mut report_rx: mpsc::UnboundedReceiver<(PeerId, PeerReputationEvent)>,
         connected_peers: Arc<RwLock<HashMap<PeerId, PeerInfo>>>,
         peer_request_limits: Arc<Mutex<HashMap<PeerId, HashMap<u16, RateLimit>>>>,
         rate_limits_pending_deletion: Arc<Mutex<PendingDeletion>>,
@@ -537,6 +605,11 @@ impl Network {
                             }
                         }
                     },
+                    report = report_rx.recv() => {
+                        if let Some((peer_id, event)) = report {
+                            Self::report_peer_impl(&mut swarm, peer_id, event, #[cfg(feature = "metrics")] &metrics);
+                        }
+                    },
                     event = swarm.next() => {
                         if let Some(event) = event {
                             Self::handle_event(event, &events_tx, &mut swarm, &mut task_state, &connected_peers, Arc::clone(&peer_request_limits), Arc::clone(&rate_limits_pending_deletion), #[cfg( feature = "metrics")] &metrics);
@@ -585,6 +658,25 @@ impl Network {
                     "Connection established",
                 );
 
+                if num_established.get() == 1 {
+                    let direction = match endpoint {
+                        ConnectedPoint::Dialer { .. } => ConnectionDirection::Outbound,
+                        ConnectedPoint::Listener { .. } => ConnectionDirection::Inbound,
+                    };
+                    let connected_since = SystemTime::now()
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .map_or(0, |time| time.as_secs());
+                    state.peer_connections.insert(
+                        peer_id,
+                        PeerConnection {
+                            direction,
+                            connected_since,
+                            user_agent: None,
+                            listen_addresses: vec![],
+                        },
+                    );
+                }
+
                 if let Some(dial_errors) = concurrent_dial_errors {
                     for (addr, error) in dial_errors {
                         debug!(
@@ -638,6 +730,7 @@ impl Network {
                 // Remove Peer
                 if num_established == 0 {
                     connected_peers.write().remove(&peer_id);
+                    state.peer_connections.remove(&peer_id);
                     swarm.behaviour_mut().remove_peer(peer_id);
 
                     // Removes or marks to remove the respective rate limits.
@@ -884,6 +977,11 @@ impl Network {
                                     "Received identity",
                                 );
 
+                                if let Some(connection) = state.peer_connections.get_mut(&peer_id) {
+                                    connection.user_agent = Some(info.agent_version.clone());
+                                    connection.listen_addresses = info.listen_addrs.clone();
+                                }
+
                                 // Save identified peer listen addresses
                                 for listen_addr in info.listen_addrs {
                                     swarm.behaviour_mut().add_peer_address(peer_id, listen_addr);
@@ -933,6 +1031,11 @@ impl Network {
                     NimiqEvent::Pool(event) => {
                         match event {
                             ConnectionPoolEvent::PeerJoined { peer_id: _ } => {}
+                            ConnectionPoolEvent::ConnectionRejected { peer_id, reason } => {
+                                debug!(%peer_id, %reason, "Connection rejected by connection pool");
+                                #[cfg(feature = "metrics")]
+                                metrics.note_rejected_connection(&reason);
+                            }
                         };
                     }
                     NimiqEvent::RequestResponse(event) => match event {
@@ -1398,9 +1501,43 @@ impl Network {
             NetworkAction::DisconnectPeer { peer_id, reason } => {
                 swarm.behaviour_mut().pool.close_connection(peer_id, reason)
             }
+            NetworkAction::BanPeer { peer_id, duration } => {
+                swarm.behaviour_mut().pool.ban_peer(peer_id, duration)
+            }
             NetworkAction::UnbanPeer { peer_id } => {
                 swarm.behaviour_mut().pool.unban_connection(peer_id)
             }
+            NetworkAction::PeerConnection { peer_id, output } => {
+                if output
+                    .send(state.peer_connections.get(&peer_id).cloned())
+                    .is_err()
+                {
+                    error!(%peer_id, error = "receiver hung up", "could not send peer connection to channel");
+                }
+            }
+        }
+    }
+
+    fn report_peer_impl(
+        swarm: &mut NimiqSwarm,
+        peer_id: PeerId,
+        event: PeerReputationEvent,
+        #[cfg(feature = "metrics")] metrics: &Arc<NetworkMetrics>,
+    ) {
+        let pool = &mut swarm.behaviour_mut().pool;
+
+        #[cfg(feature = "metrics")]
+        {
+            let ban_duration = pool.report_peer(peer_id, event);
+            metrics.note_peer_reputation_event(
+                event,
+                pool.reputation_score(&peer_id),
+                ban_duration.is_some(),
+            );
+        }
+        #[cfg(not(feature = "metrics"))]
+        {
+            pool.report_peer(peer_id, event);
         }
     }
 
@@ -1455,6 +1592,19 @@ impl Network {
         }
     }
 
+    /// Tells the network to disconnect and ban a peer ID. Without a duration, the ban lasts
+    /// until the peer is un-banned.
+    pub async fn ban_peer(&self, peer_id: PeerId, duration: Option<Duration>) {
+        if let Err(error) = self
+            .action_tx
+            .clone()
+            .send(NetworkAction::BanPeer { peer_id, duration })
+            .await
+        {
+            error!(%error, "Failed to send NetworkAction::BanPeer");
+        }
+    }
+
     /// Tells the network to un-ban a peer ID
     pub async fn unban_peer(&self, peer_id: PeerId) {
         if let Err(error) = self
@@ -1507,6 +1657,11 @@ impl Network {
                     OutboundRequestError::SenderFutureDropped,
                 )),
                 Ok(result) => {
+                    if let Err(RequestError::OutboundRequest(OutboundRequestError::Timeout)) =
+                        result
+                    {
+                        self.report_peer(peer_id, PeerReputationEvent::Timeout);
+                    }
                     let data = result?;
                     if let Ok(message) =
                         <Result<Req::Response, InboundRequestError> as Deserialize>::deserialize(
@@ -1527,6 +1682,7 @@ impl Network {
                         type_id = std::any::type_name::<Req::Response>(),
                         "Failed to deserialize response from peer",
                         );
+                        self.report_peer(peer_id, PeerReputationEvent::BadResponse);
                         Err(RequestError::InboundRequest(
                             InboundRequestError::DeSerializationError,
                         ))
@@ -1574,6 +1730,7 @@ impl Network {
         let peer_request_limits = Arc::clone(&self.peer_request_limits);
         let action_tx = self.action_tx.clone();
         let action_tx2 = self.action_tx.clone();
+        let report_tx = self.report_tx.clone();
         ReceiveStream::WaitingForRegister(Box::pin(async move {
             // TODO Make buffer size configurable
             let (tx, rx) = mpsc::channel(1024);
@@ -1591,6 +1748,7 @@ impl Network {
         .filter_map(move |(data, request_id, peer_id)| {
             let peer_request_limits = Arc::clone(&peer_request_limits);
             let action_tx2 = action_tx2.clone();
+            let report_tx = report_tx.clone();
             async move {
                 // If the request is not respecting the rate limits for its request type, filters the request out
                 // and replies with the respective error message.
@@ -1602,6 +1760,11 @@ impl Network {
                         type_id = std::any::type_name::<Req>(),
                         "Rate limit was exceeded!",
                     );
+                    if let Err(error) =
+                        report_tx.send((peer_id, PeerReputationEvent::RateLimitExceeded))
+                    {
+                        error!(%peer_id, %error, "could not send peer report to channel");
+                    }
                     if let Err(e) = Self::respond_with_error::<Req>(
                         action_tx2,
                         request_id,
@@ -1658,6 +1821,42 @@ impl Network {
         self.connected_peers.read().len()
     }
 
+    /// Returns the details of a connected peer, or `None` if we aren't connected to it.
+    pub async fn peer_details(&self, peer_id: PeerId) -> Result<Option<PeerDetails>, NetworkError> {
+        let peer_info = match self.get_peer_info(peer_id) {
+            Some(peer_info) => peer_info,
+            None => return Ok(None),
+        };
+
+        let (output_tx, output_rx) = oneshot::channel();
+        self.action_tx
+            .clone()
+            .send(NetworkAction::PeerConnection {
+                peer_id,
+                output: output_tx,
+            })
+            .await?;
+
+        Ok(output_rx.await?.map(|connection| PeerDetails {
+            peer_id,
+            address: peer_info.get_address(),
+            listen_addresses: connection.listen_addresses,
+            services: peer_info.get_services(),
+            user_agent: connection.user_agent,
+            direction: connection.direction,
+            connected_since: connection.connected_since,
+            score: self
+                .reputation
+                .read()
+                .score(&peer_id, instant::Instant::now()),
+        }))
+    }
+
+    /// Gets the reputation of all peers that misbehaved recently
+    pub fn peer_reputations(&self) -> Vec<PeerReputationInfo> {
+        self.reputation.read().peers(instant::Instant::now())
+    }
+
     /// Disconnects from (closes the connection to) all peers with a reason
     pub async fn disconnect(&self, reason: CloseReason) {
         for peer_id in self.get_peers() {
@@ -1980,6 +2179,12 @@ impl NetworkInterface for Network {
         }
     }
 
+    fn report_peer(&self, peer_id: PeerId, event: PeerReputationEvent) {
+        if let Err(error) = self.report_tx.send((peer_id, event)) {
+            error!(%peer_id, %error, "could not send peer report to channel");
+        }
+    }
+
     fn subscribe_events(&self) -> SubscribeEvents<PeerId> {
         Box::pin(BroadcastStream::new(self.events_tx.subscribe()))
     }
@@ -6,10 +6,18 @@ use prometheus_client::{
 };
 use std::time::Duration;
 
+use nimiq_network_interface::network::PeerReputationEvent;
+
+use crate::connection_pool::handler::ConnectionPoolHandlerError;
+
 pub struct NetworkMetrics {
     gossipsub_messages_received: Family<TopicLabels, Counter>,
     gossipsub_messages_published: Family<TopicLabels, Counter>,
     response_times: Histogram,
+    peer_reputation_events: Family<ReputationEventLabels, Counter>,
+    peer_reputation_scores: Histogram,
+    peer_bans: Counter,
+    rejected_connections: Family<RejectedConnectionLabels, Counter>,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
@@ -17,6 +25,16 @@ struct TopicLabels {
     topic: String,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct ReputationEventLabels {
+    event: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct RejectedConnectionLabels {
+    reason: String,
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
 struct RequestIdLabels {
     request_id: String,
@@ -28,6 +46,12 @@ impl Default for NetworkMetrics {
             gossipsub_messages_received: Default::default(),
             gossipsub_messages_published: Default::default(),
             response_times: Histogram::new([0.01, 0.02, 0.05, 0.1, 0.2, 0.5, 1.0, 2.0].into_iter()),
+            peer_reputation_events: Default::default(),
+            peer_reputation_scores: Histogram::new(
+                [-200.0, -100.0, -75.0, -50.0, -25.0, -10.0, 0.0].into_iter(),
+            ),
+            peer_bans: Default::default(),
+            rejected_connections: Default::default(),
         }
     }
 }
@@ -51,6 +75,30 @@ impl NetworkMetrics {
             "Time between requests and responses",
             self.response_times.clone(),
         );
+
+        registry.register(
+            "peer_reputation_events",
+            "Number of reported peer misbehaviours",
+            self.peer_reputation_events.clone(),
+        );
+
+        registry.register(
+            "peer_reputation_scores",
+            "Reputation scores of peers after a reported misbehaviour",
+            self.peer_reputation_scores.clone(),
+        );
+
+        registry.register(
+            "peer_bans",
+            "Number of peers banned because of their reputation",
+            self.peer_bans.clone(),
+        );
+
+        registry.register(
+            "rejected_connections",
+            "Number of connections rejected because of bans or connection limits",
+            self.rejected_connections.clone(),
+        );
     }
 
     pub(crate) fn note_received_pubsub_message(&self, topic: &TopicHash) {
@@ -80,4 +128,29 @@ impl NetworkMetrics {
     pub(crate) fn note_response_time(&self, duration: Duration) {
         self.response_times.observe(duration.as_secs_f64());
     }
+
+    pub(crate) fn note_peer_reputation_event(
+        &self,
+        event: PeerReputationEvent,
+        score: f64,
+        banned: bool,
+    ) {
+        self.peer_reputation_events
+            .get_or_create(&ReputationEventLabels {
+                event: format!("{event:?}"),
+            })
+            .inc();
+        self.peer_reputation_scores.observe(score);
+        if banned {
+            self.peer_bans.inc();
+        }
+    }
+
+    pub(crate) fn note_rejected_connection(&self, reason: &ConnectionPoolHandlerError) {
+        self.rejected_connections
+            .get_or_create(&RejectedConnectionLabels {
+                reason: format!("{reason:?}"),
+            })
+            .inc();
+    }
 }
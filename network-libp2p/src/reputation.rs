@@ -0,0 +1,338 @@
+use std::{collections::HashMap, time::Duration};
+
+use instant::Instant;
+use libp2p::PeerId;
+
+use nimiq_network_interface::network::PeerReputationEvent;
+
+/// Configuration of the peer reputation.
+///
+/// Every reported misbehaviour of a peer lowers its score by the weight of the event. Scores decay
+/// back towards zero over time, so that occasional misbehaviour is forgiven. A peer whose score
+/// drops to or below the ban threshold is banned temporarily.
+#[derive(Clone, Debug)]
+pub struct ReputationConfig {
+    /// Penalty for sending an invalid block.
+    pub invalid_block_weight: f64,
+    /// Penalty for replying with an invalid or inconsistent response.
+    pub bad_response_weight: f64,
+    /// Penalty for a request that timed out. Requests may as well time out because our own node
+    /// is slow, so timeouts aren't penalized by default.
+    pub timeout_weight: f64,
+    /// Penalty for exceeding a request rate limit.
+    pub rate_limit_weight: f64,
+    /// Time after which half of the penalties of a peer are forgiven.
+    pub decay_half_life: Duration,
+    /// Score at or below which a peer gets banned. Must be negative.
+    pub ban_threshold: f64,
+    /// Duration of the first ban of a peer. Every further ban doubles the duration, up to
+    /// `max_ban_duration`.
+    pub ban_duration: Duration,
+    /// Maximum duration of a ban.
+    pub max_ban_duration: Duration,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            invalid_block_weight: 50.0,
+            bad_response_weight: 20.0,
+            timeout_weight: 0.0,
+            rate_limit_weight: 10.0,
+            decay_half_life: Duration::from_secs(60 * 10), // 10 minutes
+            ban_threshold: -100.0,
+            ban_duration: Duration::from_secs(60 * 10), // 10 minutes
+            max_ban_duration: Duration::from_secs(60 * 60 * 24), // 24 hours
+        }
+    }
+}
+
+impl ReputationConfig {
+    /// Returns the penalty for the given event.
+    pub fn weight(&self, event: PeerReputationEvent) -> f64 {
+        match event {
+            PeerReputationEvent::InvalidBlock => self.invalid_block_weight,
+            PeerReputationEvent::BadResponse => self.bad_response_weight,
+            PeerReputationEvent::Timeout => self.timeout_weight,
+            PeerReputationEvent::RateLimitExceeded => self.rate_limit_weight,
+        }
+    }
+
+    /// Returns the duration of a ban, given the number of times the peer was banned before.
+    fn ban_duration(&self, previous_bans: u32) -> Duration {
+        self.ban_duration
+            .checked_mul(2u32.saturating_pow(previous_bans))
+            .map_or(self.max_ban_duration, |duration| {
+                duration.min(self.max_ban_duration)
+            })
+    }
+}
+
+/// The reputation of a single peer.
+#[derive(Debug)]
+struct ReputationEntry {
+    /// The score as of `updated`.
+    score: f64,
+    /// When the score was last decayed.
+    updated: Instant,
+    /// Number of times the peer got banned.
+    bans: u32,
+    /// When the current ban of the peer ends, if it is banned.
+    banned_until: Option<Instant>,
+}
+
+impl ReputationEntry {
+    fn new(now: Instant) -> Self {
+        Self {
+            score: 0.0,
+            updated: now,
+            bans: 0,
+            banned_until: None,
+        }
+    }
+
+    /// Returns the score decayed to the given time.
+    fn score_at(&self, now: Instant, half_life: Duration) -> f64 {
+        if now <= self.updated || half_life.is_zero() {
+            return self.score;
+        }
+        let elapsed = now - self.updated;
+        self.score * 0.5f64.powf(elapsed.as_secs_f64() / half_life.as_secs_f64())
+    }
+
+    fn decay(&mut self, now: Instant, half_life: Duration) {
+        self.score = self.score_at(now, half_life);
+        self.updated = self.updated.max(now);
+    }
+}
+
+/// A snapshot of the reputation of a peer.
+#[derive(Clone, Debug)]
+pub struct PeerReputationInfo {
+    /// The peer ID.
+    pub peer_id: PeerId,
+    /// The current score of the peer. Zero is neutral, misbehaviour makes it negative.
+    pub score: f64,
+    /// Number of times the peer got banned.
+    pub bans: u32,
+    /// The remaining duration of the current ban, if the peer is banned.
+    pub banned_for: Option<Duration>,
+}
+
+/// Keeps track of the reputation of peers that misbehaved.
+#[derive(Debug)]
+pub struct PeerReputation {
+    config: ReputationConfig,
+    peers: HashMap<PeerId, ReputationEntry>,
+}
+
+impl PeerReputation {
+    /// Scores closer to zero than this are considered neutral. Peers with such a score are
+    /// forgotten during house-keeping, unless they are banned.
+    const NEUTRAL_SCORE: f64 = 1.0;
+
+    pub fn new(config: ReputationConfig) -> Self {
+        Self {
+            config,
+            peers: HashMap::new(),
+        }
+    }
+
+    /// Records a misbehaviour of a peer. Returns the duration for which the peer must be banned, if
+    /// its score dropped to or below the ban threshold and it isn't already banned.
+    pub fn record(
+        &mut self,
+        peer_id: PeerId,
+        event: PeerReputationEvent,
+        now: Instant,
+    ) -> Option<Duration> {
+        let entry = self
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| ReputationEntry::new(now));
+        entry.decay(now, self.config.decay_half_life);
+        entry.score -= self.config.weight(event);
+
+        if entry.banned_until.is_some() || entry.score > self.config.ban_threshold {
+            return None;
+        }
+
+        let duration = self.config.ban_duration(entry.bans);
+        entry.bans += 1;
+        entry.banned_until = Some(now + duration);
+        Some(duration)
+    }
+
+    /// Bans a peer for the given duration, regardless of its score. This counts as a ban of the
+    /// peer, so the durations of the bans because of its reputation grow.
+    pub fn ban(&mut self, peer_id: PeerId, duration: Duration, now: Instant) {
+        let entry = self
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| ReputationEntry::new(now));
+        entry.bans += 1;
+        entry.banned_until = Some(now + duration);
+    }
+
+    /// Returns the current score of a peer.
+    pub fn score(&self, peer_id: &PeerId, now: Instant) -> f64 {
+        self.peers.get(peer_id).map_or(0.0, |entry| {
+            entry.score_at(now, self.config.decay_half_life)
+        })
+    }
+
+    /// Returns whether a peer is currently banned because of its reputation.
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.peers
+            .get(peer_id)
+            .map_or(false, |entry| entry.banned_until.is_some())
+    }
+
+    /// Lifts the ban of a peer. Its score and number of bans are kept.
+    pub fn unban(&mut self, peer_id: &PeerId) {
+        if let Some(entry) = self.peers.get_mut(peer_id) {
+            entry.banned_until = None;
+        }
+    }
+
+    /// Decays all scores, lifts the bans that expired and forgets the peers that are neither
+    /// banned nor have a significant score anymore. Returns the peers whose ban expired.
+    pub fn house_keeping(&mut self, now: Instant) -> Vec<PeerId> {
+        let half_life = self.config.decay_half_life;
+        let mut expired_bans = vec![];
+
+        self.peers.retain(|peer_id, entry| {
+            entry.decay(now, half_life);
+            if matches!(entry.banned_until, Some(until) if until <= now) {
+                entry.banned_until = None;
+                expired_bans.push(*peer_id);
+            }
+            entry.banned_until.is_some() || entry.score.abs() >= Self::NEUTRAL_SCORE
+        });
+
+        expired_bans
+    }
+
+    /// Returns the reputation of all peers that are tracked.
+    pub fn peers(&self, now: Instant) -> Vec<PeerReputationInfo> {
+        self.peers
+            .iter()
+            .map(|(peer_id, entry)| PeerReputationInfo {
+                peer_id: *peer_id,
+                score: entry.score_at(now, self.config.decay_half_life),
+                bans: entry.bans,
+                banned_for: entry.banned_until.map(|until| {
+                    if until > now {
+                        until - now
+                    } else {
+                        Duration::ZERO
+                    }
+                }),
+            })
+            .collect()
+    }
+
+    /// Returns the number of peers that are currently banned because of their reputation.
+    pub fn num_banned(&self) -> usize {
+        self.peers
+            .values()
+            .filter(|entry| entry.banned_until.is_some())
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use instant::Instant;
+    use libp2p::PeerId;
+
+    use super::{PeerReputation, ReputationConfig};
+    use nimiq_network_interface::network::PeerReputationEvent;
+    use nimiq_test_log::test;
+
+    #[test]
+    fn it_bans_peers_with_graduated_durations() {
+        let config = ReputationConfig::default();
+        let mut reputation = PeerReputation::new(config.clone());
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        // The first invalid block doesn't reach the ban threshold.
+        assert_eq!(
+            reputation.record(peer_id, PeerReputationEvent::InvalidBlock, now),
+            None
+        );
+        assert_eq!(
+            reputation.score(&peer_id, now),
+            -config.invalid_block_weight
+        );
+
+        // The second one does.
+        assert_eq!(
+            reputation.record(peer_id, PeerReputationEvent::InvalidBlock, now),
+            Some(config.ban_duration)
+        );
+        assert!(reputation.is_banned(&peer_id));
+
+        // Further events while banned don't extend the ban.
+        assert_eq!(
+            reputation.record(peer_id, PeerReputationEvent::Timeout, now),
+            None
+        );
+
+        // The ban expires during house-keeping.
+        let later = now + config.ban_duration;
+        assert_eq!(reputation.house_keeping(later), vec![peer_id]);
+        assert!(!reputation.is_banned(&peer_id));
+
+        // The score only decayed to half of it meanwhile, so another invalid block bans the peer
+        // again, for twice as long.
+        assert_eq!(
+            reputation.record(peer_id, PeerReputationEvent::InvalidBlock, later),
+            Some(config.ban_duration * 2)
+        );
+
+        let info = &reputation.peers(later)[0];
+        assert_eq!(info.bans, 2);
+        assert_eq!(info.banned_for, Some(config.ban_duration * 2));
+    }
+
+    #[test]
+    fn it_lifts_manual_bans() {
+        let mut reputation = PeerReputation::new(ReputationConfig::default());
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        let duration = Duration::from_secs(5);
+        reputation.ban(peer_id, duration, now);
+        assert!(reputation.is_banned(&peer_id));
+        assert_eq!(reputation.score(&peer_id, now), 0.0);
+        assert_eq!(reputation.num_banned(), 1);
+
+        // Once the ban expired, the peer is forgotten since its score is neutral.
+        assert_eq!(reputation.house_keeping(now + duration), vec![peer_id]);
+        assert!(!reputation.is_banned(&peer_id));
+        assert!(reputation.peers(now + duration).is_empty());
+    }
+
+    #[test]
+    fn it_decays_and_forgets_scores() {
+        let config = ReputationConfig {
+            decay_half_life: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let mut reputation = PeerReputation::new(config.clone());
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        reputation.record(peer_id, PeerReputationEvent::BadResponse, now);
+        let score = reputation.score(&peer_id, now + Duration::from_secs(60));
+        assert!((score + config.bad_response_weight / 2.0).abs() < 1e-9);
+
+        // Once the score decayed to almost zero, the peer is forgotten.
+        reputation.house_keeping(now + Duration::from_secs(60 * 10));
+        assert!(reputation.peers(now).is_empty());
+    }
+}
@@ -23,6 +23,7 @@ use nimiq_hash::Blake2bHash;
 use nimiq_network_interface::peer_info::Services;
 use nimiq_network_libp2p::discovery::{
     behaviour::{DiscoveryBehaviour, DiscoveryConfig, DiscoveryEvent},
+    peer_contact_store::{PeerContactStore, StoredPeerContact},
     peer_contacts::PeerContact,
     peer_contacts::{PeerContactBook, SignedPeerContact},
 };
@@ -128,6 +129,47 @@ fn random_peer_contact(n: usize, services: Services) -> SignedPeerContact {
     peer_contact.sign(&keypair)
 }
 
+fn old_peer_contact() -> SignedPeerContact {
+    let keypair = Keypair::generate_ed25519();
+
+    let mut peer_contact = PeerContact {
+        addresses: vec!["/dns/test_old.local/tcp/443/wss".parse().unwrap()],
+        public_key: keypair.public(),
+        services: Services::FULL_BLOCKS,
+        timestamp: None,
+    };
+
+    peer_contact.set_current_time();
+    peer_contact
+        .timestamp
+        .as_mut()
+        .map(|t| *t -= PeerContactBook::MAX_PEER_AGE * 2); // twice as older
+
+    peer_contact.sign(&keypair)
+}
+
+/// A peer contact store keeping the contacts in memory, shared between its clones.
+#[derive(Clone, Debug, Default)]
+struct TestPeerContactStore(Arc<RwLock<Vec<StoredPeerContact>>>);
+
+impl PeerContactStore for TestPeerContactStore {
+    fn load(&self) -> Vec<StoredPeerContact> {
+        self.0.read().clone()
+    }
+
+    fn update(&self, changed: Vec<StoredPeerContact>, removed: Vec<PeerId>) {
+        let mut contacts = self.0.write();
+        contacts.retain(|stored| {
+            let peer_id = stored.contact.inner.peer_id();
+            !removed.contains(&peer_id)
+                && !changed
+                    .iter()
+                    .any(|contact| contact.contact.inner.peer_id() == peer_id)
+        });
+        contacts.extend(changed);
+    }
+}
+
 fn test_peers_in_contact_book(
     peer_contact_book: &PeerContactBook,
     peer_contacts: &[SignedPeerContact],
@@ -242,24 +284,7 @@ fn test_housekeeping() {
 
     let fresh_contact = random_peer_contact(1, Services::FULL_BLOCKS);
 
-    let old_contact = {
-        let keypair = Keypair::generate_ed25519();
-
-        let mut peer_contact = PeerContact {
-            addresses: vec!["/dns/test_old.local/tcp/443/wss".parse().unwrap()],
-            public_key: keypair.public(),
-            services: Services::FULL_BLOCKS,
-            timestamp: None,
-        };
-
-        peer_contact.set_current_time();
-        peer_contact
-            .timestamp
-            .as_mut()
-            .map(|t| *t -= PeerContactBook::MAX_PEER_AGE * 2); // twice as older
-
-        peer_contact.sign(&keypair)
-    };
+    let old_contact = old_peer_contact();
 
     // Insert fresh contact and check that it was inserted
     peer_contact_book.insert(fresh_contact.clone());
@@ -289,3 +314,55 @@ fn test_housekeeping() {
         .get(&old_contact.public_key().clone().to_peer_id())
         .is_none());
 }
+
+#[test]
+fn test_persisting_peer_contacts() {
+    let store = TestPeerContactStore::default();
+    let own_contact = random_peer_contact(1, Services::FULL_BLOCKS);
+
+    let mut peer_contact_book =
+        PeerContactBook::with_store(own_contact.clone(), Box::new(store.clone()));
+    assert!(store.load().is_empty());
+
+    let contact = random_peer_contact(2, Services::FULL_BLOCKS);
+    let peer_id = contact.public_key().clone().to_peer_id();
+    peer_contact_book.insert(contact.clone());
+    peer_contact_book.get(&peer_id).unwrap().set_score(2.);
+    peer_contact_book.get(&peer_id).unwrap().set_last_seen();
+
+    // Our own contact is not persisted.
+    peer_contact_book.insert(own_contact.clone());
+
+    // House-keeping persists the contacts.
+    peer_contact_book.house_keeping();
+    let stored = store.load();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].contact, contact);
+    assert_eq!(stored[0].score, 2.);
+    assert!(stored[0].last_seen.is_some());
+
+    // Add an expired contact and one with an invalid signature to the store.
+    let mut tampered_contact = random_peer_contact(3, Services::FULL_BLOCKS);
+    tampered_contact.inner.services = Services::HISTORY;
+    let mut stored_contacts = vec![];
+    for contact in [old_peer_contact(), tampered_contact.clone()] {
+        stored_contacts.push(StoredPeerContact {
+            contact,
+            score: 0.,
+            last_seen: None,
+        });
+    }
+    store.update(stored_contacts, vec![]);
+
+    // Only the valid contact is reloaded, with its meta-data.
+    let peer_contact_book = PeerContactBook::with_store(own_contact, Box::new(store.clone()));
+    let peer_contact = peer_contact_book.get(&peer_id).unwrap();
+    assert_eq!(peer_contact.signed(), &contact);
+    assert_eq!(peer_contact.get_score(), 2.);
+    assert_eq!(peer_contact.last_seen(), stored[0].last_seen);
+    assert!(peer_contact_book
+        .get(&tampered_contact.public_key().clone().to_peer_id())
+        .is_none());
+    assert_eq!(peer_contact_book.query(Services::FULL_BLOCKS).count(), 1);
+    assert_eq!(store.load(), stored);
+}
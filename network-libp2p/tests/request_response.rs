@@ -325,6 +325,9 @@ fn network_config(address: Multiaddr) -> Config {
         memory_transport: true,
         required_services: Services::all(),
         tls: None,
+        peer_contact_store: None,
+        reputation: Default::default(),
+        connection_pool: Default::default(),
     }
 }
 
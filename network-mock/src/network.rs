@@ -14,7 +14,8 @@ use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream,
 use beserial::{Deserialize, Serialize};
 use nimiq_network_interface::{
     network::{
-        CloseReason, MsgAcceptance, Network, NetworkEvent, PubsubId, SubscribeEvents, Topic,
+        CloseReason, MsgAcceptance, Network, NetworkEvent, PeerReputationEvent, PubsubId,
+        SubscribeEvents, Topic,
     },
     peer_info::{PeerInfo, Services},
     request::{
@@ -454,6 +455,10 @@ impl Network for MockNetwork {
             .retain(|k, _| k.network_recipient != peer_id.into());
     }
 
+    fn report_peer(&self, peer_id: MockPeerId, event: PeerReputationEvent) {
+        log::debug!(%peer_id, ?event, "Peer reputation event reported");
+    }
+
     fn subscribe_events(&self) -> SubscribeEvents<MockPeerId> {
         Box::pin(
             BroadcastStream::new(self.peers.read().subscribe()).map(|maybe_ev| {
@@ -34,9 +34,12 @@ pub struct Accounts {
 }
 
 impl Accounts {
+    /// The name of the database the accounts tree is stored in.
+    pub const TREE_DB_NAME: &'static str = "AccountsTrie";
+
     /// Creates a new Accounts.
     pub fn new(env: Environment) -> Self {
-        let tree = AccountsTrie::new(env.clone(), "AccountsTrie");
+        let tree = AccountsTrie::new(env.clone(), Self::TREE_DB_NAME);
         Accounts { env, tree }
     }
 
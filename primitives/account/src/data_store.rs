@@ -106,7 +106,7 @@ mod tests {
 
     #[test]
     fn data_store_works() {
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let tree = AccountsTrie::new(env.clone(), "accounts_trie");
         let store = DataStore::new(&tree, &Policy::STAKING_CONTRACT_ADDRESS);
 
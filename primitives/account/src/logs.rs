@@ -429,6 +429,18 @@ impl BlockLogger {
         })
     }
 
+    /// Returns the logs of the inherents recorded so far.
+    pub fn inherent_logs(&self) -> &[Log] {
+        match self.block_log {
+            BlockLog::RevertedBlock {
+                ref inherent_logs, ..
+            }
+            | BlockLog::AppliedBlock {
+                ref inherent_logs, ..
+            } => inherent_logs,
+        }
+    }
+
     #[cfg(feature = "interaction-traits")]
     pub(crate) fn inherent_logger(&mut self) -> InherentLogger {
         match self.block_log {
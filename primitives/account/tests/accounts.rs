@@ -392,7 +392,7 @@ fn it_checks_for_sufficient_funds() {
 fn accounts_performance() {
     let (env, num_txns) = if VOLATILE_ENV {
         let num_txns = 1_000;
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
 
         (env, num_txns)
     } else {
@@ -504,7 +504,7 @@ fn accounts_performance_history_sync_batches_single_sender() {
 
     let (env, num_txns) = if VOLATILE_ENV {
         let num_txns = 25;
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
 
         (env, num_txns)
     } else {
@@ -629,7 +629,7 @@ fn accounts_performance_history_sync_batches_many_to_many() {
 
     let (env, num_txns) = if VOLATILE_ENV {
         let num_txns = 25;
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
 
         (env, num_txns)
     } else {
@@ -124,6 +124,11 @@ impl MerkleRadixTrie {
         tree
     }
 
+    /// Returns the database the trie is stored in.
+    pub fn database(&self) -> &Database {
+        &self.db
+    }
+
     fn init_root(&self, txn: &mut WriteTransaction, incomplete: bool) {
         if self.get_root(txn).is_none() {
             let root = if incomplete {
@@ -98,6 +98,11 @@ pub enum AccountCommand {
     Get {
         /// The account's address.
         address: Address,
+
+        /// Returns the account state as it was right after this block. Only blocks of the
+        /// current and the previous batch can be queried.
+        #[clap(long)]
+        at_block: Option<u32>,
     },
 }
 
@@ -171,12 +176,19 @@ impl HandleSubcommand for AccountCommand {
                         .await?
                 );
             }
-            AccountCommand::Get { address } => {
-                println!(
+            AccountCommand::Get { address, at_block } => match at_block {
+                Some(block_number) => println!(
+                    "{:#?}",
+                    client
+                        .blockchain
+                        .get_account_by_address_at_block(address, block_number)
+                        .await?
+                ),
+                None => println!(
                     "{:#?}",
                     client.blockchain.get_account_by_address(address).await?
-                );
-            }
+                ),
+            },
         }
         Ok(())
     }
@@ -6,7 +6,9 @@ use futures::StreamExt;
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 use nimiq_rpc_interface::blockchain::BlockchainInterface;
-use nimiq_rpc_interface::types::LogType;
+use nimiq_rpc_interface::types::{
+    AddressHistoryFilter, LogType, TransactionKind, TransferDirection,
+};
 
 use crate::Client;
 
@@ -98,6 +100,75 @@ pub enum BlockchainCommand {
         /// If set true only the hash of the transactions will be fetched. Otherwise the full transactions will be retrieved.
         #[clap(short = 'h')]
         just_hash: bool,
+
+        /// Only fetch transactions after the transaction with this hash, i.e. the last one of the previous page.
+        #[clap(long)]
+        start_after: Option<Blake2bHash>,
+
+        /// Fetch the transactions from oldest to newest instead of from newest to oldest.
+        #[clap(long)]
+        oldest_first: bool,
+
+        /// Only fetch transactions included at or after this block.
+        #[clap(long)]
+        min_block: Option<u32>,
+
+        /// Only fetch transactions included at or before this block.
+        #[clap(long)]
+        max_block: Option<u32>,
+
+        /// Only fetch incoming or outgoing transactions.
+        #[clap(long, value_enum)]
+        direction: Option<TransferDirection>,
+
+        /// Only fetch transactions of the given kinds.
+        #[clap(long, value_enum)]
+        kinds: Vec<TransactionKind>,
+    },
+
+    /// Returns the balances of the given address after each block that changed it. The first
+    /// entry is the balance at the start of the range. Only available if the node indexes the
+    /// balance history.
+    BalanceHistory {
+        /// The address to query by.
+        address: Address,
+
+        /// The first block of the range. If absent it defaults to the genesis block.
+        #[clap(long)]
+        from_block: Option<u32>,
+
+        /// The last block of the range. If absent it defaults to the current head.
+        #[clap(long)]
+        to_block: Option<u32>,
+    },
+
+    /// Returns the blocks produced by the given validator. Only available if the node indexes the
+    /// validator history.
+    BlocksByProducer {
+        /// The address of the validator.
+        validator: Address,
+
+        /// The first block of the range. If absent it defaults to the genesis block.
+        #[clap(long)]
+        from_block: Option<u32>,
+
+        /// The last block of the range. If absent it defaults to the current head.
+        #[clap(long)]
+        to_block: Option<u32>,
+    },
+
+    /// Returns the skip blocks, slashes and parks of the given validator. Only available if the
+    /// node indexes the validator history.
+    ValidatorPunishmentHistory {
+        /// The address of the validator.
+        validator: Address,
+    },
+
+    /// Returns the most recent rebranches of the main chain, newest first.
+    RecentRebranches {
+        /// Max number of rebranches to fetch. If absent all recorded ones are fetched.
+        #[clap(long)]
+        max: Option<u16>,
     },
 
     /// Returns the information for the slot owner at the given block height and offset. The
@@ -140,6 +211,11 @@ pub enum BlockchainCommand {
     Staker {
         /// The address to query by.
         address: Address,
+
+        /// Returns the staker as it was right after this block. Only blocks of the current and
+        /// the previous batch can be queried.
+        #[clap(long)]
+        at_block: Option<u32>,
     },
 
     /// Lists the current stakes from the staking contract.
@@ -268,13 +344,27 @@ impl HandleSubcommand for BlockchainCommand {
                 address,
                 max,
                 just_hash,
+                start_after,
+                oldest_first,
+                min_block,
+                max_block,
+                direction,
+                kinds,
             } => {
+                let filter = Some(AddressHistoryFilter {
+                    start_after,
+                    oldest_first,
+                    min_block,
+                    max_block,
+                    direction,
+                    kinds,
+                });
                 if just_hash {
                     println!(
                         "{:#?}",
                         client
                             .blockchain
-                            .get_transaction_hashes_by_address(address, max)
+                            .get_transaction_hashes_by_address(address, max, filter)
                             .await?
                     )
                 } else {
@@ -282,11 +372,49 @@ impl HandleSubcommand for BlockchainCommand {
                         "{:#?}",
                         client
                             .blockchain
-                            .get_transactions_by_address(address, max)
+                            .get_transactions_by_address(address, max, filter)
                             .await?
                     )
                 }
             }
+            BlockchainCommand::BalanceHistory {
+                address,
+                from_block,
+                to_block,
+            } => {
+                println!(
+                    "{:#?}",
+                    client
+                        .blockchain
+                        .get_balance_history(address, from_block, to_block)
+                        .await?
+                )
+            }
+            BlockchainCommand::BlocksByProducer {
+                validator,
+                from_block,
+                to_block,
+            } => {
+                println!(
+                    "{:#?}",
+                    client
+                        .blockchain
+                        .get_blocks_by_producer(validator, from_block, to_block)
+                        .await?
+                )
+            }
+            BlockchainCommand::ValidatorPunishmentHistory { validator } => {
+                println!(
+                    "{:#?}",
+                    client
+                        .blockchain
+                        .get_validator_punishment_history(validator)
+                        .await?
+                )
+            }
+            BlockchainCommand::RecentRebranches { max } => {
+                println!("{:#?}", client.blockchain.get_recent_rebranches(max).await?)
+            }
             BlockchainCommand::SlashedSlots { previous_slashed } => {
                 if previous_slashed {
                     println!(
@@ -315,12 +443,19 @@ impl HandleSubcommand for BlockchainCommand {
                     .get_stakers_by_validator_address(address)
                     .await?
             ),
-            BlockchainCommand::Staker { address } => {
-                println!(
+            BlockchainCommand::Staker { address, at_block } => match at_block {
+                Some(block_number) => println!(
+                    "{:#?}",
+                    client
+                        .blockchain
+                        .get_staker_by_address_at_block(address, block_number)
+                        .await?
+                ),
+                None => println!(
                     "{:#?}",
                     client.blockchain.get_staker_by_address(address).await?
-                )
-            }
+                ),
+            },
             BlockchainCommand::Stakes {} => {
                 println!("{:#?}", client.blockchain.get_active_validators().await?);
             }
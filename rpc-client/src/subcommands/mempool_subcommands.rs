@@ -1,6 +1,8 @@
 use anyhow::Error;
 use async_trait::async_trait;
 use clap::Parser;
+use futures::StreamExt;
+use nimiq_keys::Address;
 use nimiq_rpc_interface::mempool::MempoolInterface;
 
 use super::accounts_subcommands::HandleSubcommand;
@@ -30,6 +32,25 @@ pub enum MempoolCommand {
 
     /// Returns the minimum fee per byte of the local mempool.
     MinFeePerByte {},
+
+    /// Suggests the fee a transaction needs to pay to be included within the given number of
+    /// blocks.
+    EstimateFee {
+        /// The size of the transaction in bytes.
+        tx_size: usize,
+
+        /// The number of blocks the transaction should be included in.
+        #[clap(short, long, default_value_t = 1)]
+        target_blocks: u32,
+    },
+
+    /// Follow transactions being added to, evicted from or mined out of the local mempool.
+    /// If no addresses are provided it follows all transactions.
+    FollowEvents {
+        /// List of addresses whose transactions to follow. If empty it does not filter by address.
+        #[clap(short = 'a', long)]
+        addresses: Vec<Address>,
+    },
 }
 
 #[async_trait]
@@ -66,6 +87,25 @@ impl HandleSubcommand for MempoolCommand {
             MempoolCommand::MinFeePerByte {} => {
                 println!("{:#?}", client.mempool.get_min_fee_per_byte().await?);
             }
+            MempoolCommand::EstimateFee {
+                tx_size,
+                target_blocks,
+            } => {
+                println!(
+                    "{:#?}",
+                    client.mempool.estimate_fee(tx_size, target_blocks).await?
+                );
+            }
+            MempoolCommand::FollowEvents { addresses } => {
+                let mut stream = client
+                    .mempool
+                    .subscribe_for_mempool_events_by_addresses(addresses)
+                    .await?;
+
+                while let Some(event) = stream.next().await {
+                    println!("{event:#?}");
+                }
+            }
         }
         Ok(())
     }
@@ -18,6 +18,43 @@ pub enum NetworkCommand {
         #[clap(short, long)]
         count: bool,
     },
+
+    /// Returns the reputation of all peers that misbehaved recently.
+    PeerReputations {},
+
+    /// Returns the details of a connected peer.
+    PeerInfo {
+        /// The ID of the peer.
+        peer_id: String,
+    },
+
+    /// Disconnects and bans a peer.
+    Ban {
+        /// The ID of the peer.
+        peer_id: String,
+
+        /// The duration of the ban in seconds. The ban is permanent if not given.
+        #[clap(short, long)]
+        duration: Option<u64>,
+    },
+
+    /// Lifts the ban of a peer.
+    Unban {
+        /// The ID of the peer.
+        peer_id: String,
+    },
+
+    /// Connects to the given multiaddress.
+    Connect {
+        /// The multiaddress to dial.
+        address: String,
+    },
+
+    /// Disconnects a peer without banning it.
+    Disconnect {
+        /// The ID of the peer.
+        peer_id: String,
+    },
 }
 
 #[async_trait]
@@ -34,6 +71,24 @@ impl HandleSubcommand for NetworkCommand {
                     println!("{:#?}", client.network.get_peer_list().await?);
                 }
             }
+            NetworkCommand::PeerReputations {} => {
+                println!("{:#?}", client.network.get_peer_reputations().await?);
+            }
+            NetworkCommand::PeerInfo { peer_id } => {
+                println!("{:#?}", client.network.get_peer_info(peer_id).await?);
+            }
+            NetworkCommand::Ban { peer_id, duration } => {
+                println!("{:#?}", client.network.ban_peer(peer_id, duration).await?);
+            }
+            NetworkCommand::Unban { peer_id } => {
+                println!("{:#?}", client.network.unban_peer(peer_id).await?);
+            }
+            NetworkCommand::Connect { address } => {
+                println!("{:#?}", client.network.connect_to_address(address).await?);
+            }
+            NetworkCommand::Disconnect { peer_id } => {
+                println!("{:#?}", client.network.disconnect_peer(peer_id).await?);
+            }
         }
         Ok(())
     }
@@ -5,8 +5,9 @@ use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 
 use crate::types::{
-    Account, Block, BlockLog, BlockchainState, ExecutedTransaction, Inherent, LogType, ParkedSet,
-    RPCData, RPCResult, SlashedSlots, Slot, Staker, Validator,
+    Account, AddressHistoryFilter, AddressHistoryMetadata, BalanceHistoryEntry, Block, BlockLog,
+    BlockchainState, ExecutedTransaction, Inherent, LogType, ParkedSet, ProducedBlock, RPCData,
+    RPCResult, Rebranch, SlashedSlots, Slot, Staker, Validator, ValidatorPunishment,
 };
 
 #[nimiq_jsonrpc_derive::proxy(name = "BlockchainProxy", rename_all = "camelCase")]
@@ -73,19 +74,61 @@ pub trait BlockchainInterface {
         &mut self,
         address: Address,
         max: Option<u16>,
-    ) -> RPCResult<Vec<Blake2bHash>, (), Self::Error>;
+        filter: Option<AddressHistoryFilter>,
+    ) -> RPCResult<Vec<Blake2bHash>, AddressHistoryMetadata, Self::Error>;
 
     async fn get_transactions_by_address(
         &mut self,
         address: Address,
         max: Option<u16>,
-    ) -> RPCResult<Vec<ExecutedTransaction>, (), Self::Error>;
+        filter: Option<AddressHistoryFilter>,
+    ) -> RPCResult<Vec<ExecutedTransaction>, AddressHistoryMetadata, Self::Error>;
 
     async fn get_account_by_address(
         &mut self,
         address: Address,
     ) -> RPCResult<Account, BlockchainState, Self::Error>;
 
+    /// Returns the account at the given address as it was right after the given block. Only
+    /// blocks of the current batch, starting with the last macro block, can be queried.
+    async fn get_account_by_address_at_block(
+        &mut self,
+        address: Address,
+        block_number: u32,
+    ) -> RPCResult<Account, BlockchainState, Self::Error>;
+
+    /// Returns the balances of the given address right after each block in the given range that
+    /// touched it. The first entry is the balance at the start of the range. Only available if
+    /// the node indexes the balance history.
+    async fn get_balance_history(
+        &mut self,
+        address: Address,
+        from_block: Option<u32>,
+        to_block: Option<u32>,
+    ) -> RPCResult<Vec<BalanceHistoryEntry>, BlockchainState, Self::Error>;
+
+    /// Returns the blocks in the given range that were produced by the given validator. Only
+    /// available if the node indexes the validator history.
+    async fn get_blocks_by_producer(
+        &mut self,
+        validator: Address,
+        from_block: Option<u32>,
+        to_block: Option<u32>,
+    ) -> RPCResult<Vec<ProducedBlock>, BlockchainState, Self::Error>;
+
+    /// Returns the skip blocks that replaced the given validator and the slashes and parks that
+    /// hit it. Only available if the node indexes the validator history.
+    async fn get_validator_punishment_history(
+        &mut self,
+        validator: Address,
+    ) -> RPCResult<Vec<ValidatorPunishment>, BlockchainState, Self::Error>;
+
+    /// Returns the most recent rebranches of the main chain, newest first.
+    async fn get_recent_rebranches(
+        &mut self,
+        max: Option<u16>,
+    ) -> RPCResult<Vec<Rebranch>, (), Self::Error>;
+
     async fn get_active_validators(
         &mut self,
     ) -> RPCResult<Vec<Validator>, BlockchainState, Self::Error>;
@@ -119,6 +162,14 @@ pub trait BlockchainInterface {
         address: Address,
     ) -> RPCResult<Staker, BlockchainState, Self::Error>;
 
+    /// Returns the staker with the given address as it was right after the given block. Only
+    /// blocks of the current batch, starting with the last macro block, can be queried.
+    async fn get_staker_by_address_at_block(
+        &mut self,
+        address: Address,
+        block_number: u32,
+    ) -> RPCResult<Staker, BlockchainState, Self::Error>;
+
     #[stream]
     async fn subscribe_for_head_block(
         &mut self,
@@ -1,6 +1,8 @@
-use crate::types::{HashOrTx, MempoolInfo, RPCResult};
+use crate::types::{FeeEstimate, HashOrTx, MempoolEvent, MempoolInfo, RPCData, RPCResult};
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
 
 #[nimiq_jsonrpc_derive::proxy(name = "MempoolProxy", rename_all = "camelCase")]
 #[async_trait]
@@ -23,4 +25,22 @@ pub trait MempoolInterface {
     async fn mempool(&mut self) -> RPCResult<MempoolInfo, (), Self::Error>;
 
     async fn get_min_fee_per_byte(&mut self) -> RPCResult<f64, (), Self::Error>;
+
+    /// Suggests the fee a transaction of the given size (in bytes) needs to pay to be included
+    /// within the next `target_blocks` blocks.
+    async fn estimate_fee(
+        &mut self,
+        tx_size: usize,
+        target_blocks: u32,
+    ) -> RPCResult<FeeEstimate, (), Self::Error>;
+
+    /// Notifies about transactions sent by or to any of the given addresses being added to,
+    /// evicted from or mined out of the mempool. If no addresses are given, it notifies about
+    /// all transactions. If the subscriber lags behind, it is notified about the number of
+    /// events it missed.
+    #[stream]
+    async fn subscribe_for_mempool_events_by_addresses(
+        &mut self,
+        addresses: Vec<Address>,
+    ) -> Result<BoxStream<'static, RPCData<MempoolEvent, ()>>, Self::Error>;
 }
@@ -1,4 +1,4 @@
-use crate::types::RPCResult;
+use crate::types::{PeerInfo, PeerReputation, RPCResult};
 use async_trait::async_trait;
 
 #[nimiq_jsonrpc_derive::proxy(name = "NetworkProxy", rename_all = "camelCase")]
@@ -11,4 +11,20 @@ pub trait NetworkInterface {
     async fn get_peer_count(&mut self) -> RPCResult<usize, (), Self::Error>;
 
     async fn get_peer_list(&mut self) -> RPCResult<Vec<String>, (), Self::Error>;
+
+    async fn get_peer_reputations(&mut self) -> RPCResult<Vec<PeerReputation>, (), Self::Error>;
+
+    async fn get_peer_info(&mut self, peer_id: String) -> RPCResult<PeerInfo, (), Self::Error>;
+
+    async fn ban_peer(
+        &mut self,
+        peer_id: String,
+        duration: Option<u64>,
+    ) -> RPCResult<(), (), Self::Error>;
+
+    async fn unban_peer(&mut self, peer_id: String) -> RPCResult<(), (), Self::Error>;
+
+    async fn connect_to_address(&mut self, address: String) -> RPCResult<(), (), Self::Error>;
+
+    async fn disconnect_peer(&mut self, peer_id: String) -> RPCResult<(), (), Self::Error>;
 }
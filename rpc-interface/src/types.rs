@@ -589,6 +589,99 @@ pub struct Account {
     pub account_additional_fields: AccountAdditionalFields,
 }
 
+/// The balance of an address right after the given block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceHistoryEntry {
+    pub block_number: u32,
+    pub balance: Coin,
+}
+
+impl From<nimiq_blockchain::BalanceEntry> for BalanceHistoryEntry {
+    fn from(entry: nimiq_blockchain::BalanceEntry) -> Self {
+        Self {
+            block_number: entry.block_number,
+            balance: entry.balance,
+        }
+    }
+}
+
+/// A block produced by a validator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProducedBlock {
+    pub block_number: u32,
+    /// The slot the block was produced with.
+    pub slot: u16,
+}
+
+/// A punishment of a validator at the given block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorPunishment {
+    pub block_number: u32,
+    #[serde(flatten)]
+    pub kind: PunishmentKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+pub enum PunishmentKind {
+    /// The validator's block was replaced by a skip block.
+    #[serde(rename_all = "camelCase")]
+    SkipBlock { slot: u16 },
+    /// A slot of the validator was slashed for the misbehavior at the event block.
+    #[serde(rename_all = "camelCase")]
+    Slash {
+        event_block: u32,
+        slot: u16,
+        newly_disabled: bool,
+    },
+    /// The validator was parked for the misbehavior at the event block.
+    #[serde(rename_all = "camelCase")]
+    Park { event_block: u32 },
+}
+
+impl ProducedBlock {
+    pub fn from_entry(entry: nimiq_blockchain::ValidatorHistoryEntry) -> Option<Self> {
+        match entry.event {
+            nimiq_blockchain::ValidatorEvent::Produced { slot } => Some(ProducedBlock {
+                block_number: entry.block_number,
+                slot,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl ValidatorPunishment {
+    pub fn from_entry(entry: nimiq_blockchain::ValidatorHistoryEntry) -> Option<Self> {
+        let kind = match entry.event {
+            nimiq_blockchain::ValidatorEvent::Produced { .. } => return None,
+            nimiq_blockchain::ValidatorEvent::Skipped { slot } => {
+                PunishmentKind::SkipBlock { slot }
+            }
+            nimiq_blockchain::ValidatorEvent::Slashed {
+                event_block,
+                slot,
+                newly_disabled,
+            } => PunishmentKind::Slash {
+                event_block,
+                slot,
+                newly_disabled,
+            },
+            nimiq_blockchain::ValidatorEvent::Parked { event_block } => {
+                PunishmentKind::Park { event_block }
+            }
+        };
+
+        Some(ValidatorPunishment {
+            block_number: entry.block_number,
+            kind,
+        })
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "type")]
@@ -872,6 +965,126 @@ pub enum LogType {
     FailedTransaction,
 }
 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransactionKind {
+    Basic,
+    Staking,
+    Htlc,
+    Vesting,
+    Inherent,
+}
+
+impl From<TransactionKind> for nimiq_blockchain::TransactionKind {
+    fn from(kind: TransactionKind) -> Self {
+        match kind {
+            TransactionKind::Basic => Self::Basic,
+            TransactionKind::Staking => Self::Staking,
+            TransactionKind::Htlc => Self::Htlc,
+            TransactionKind::Vesting => Self::Vesting,
+            TransactionKind::Inherent => Self::Inherent,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransferDirection {
+    Incoming,
+    Outgoing,
+}
+
+/// Filters and paginates the transactions of an address. All fields are optional.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AddressHistoryFilter {
+    /// Only return transactions after the transaction with this hash, i.e. the last hash of the
+    /// previous page or the scan cursor of its metadata.
+    pub start_after: Option<Blake2bHash>,
+    /// Return the transactions from oldest to newest instead of from newest to oldest.
+    pub oldest_first: bool,
+    /// Only return transactions included at or after this block.
+    pub min_block: Option<u32>,
+    /// Only return transactions included at or before this block.
+    pub max_block: Option<u32>,
+    /// Only return incoming or outgoing transactions.
+    pub direction: Option<TransferDirection>,
+    /// Only return transactions of these kinds.
+    pub kinds: Vec<TransactionKind>,
+}
+
+/// Metadata of the transactions of an address returned for a filter.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressHistoryMetadata {
+    /// Set if the node stopped scanning the history of the address before the requested number
+    /// of transactions was found. Passing it as `startAfter` continues the scan where it
+    /// stopped.
+    pub scan_cursor: Option<Blake2bHash>,
+}
+
+impl From<AddressHistoryFilter> for nimiq_blockchain::AddressHistoryFilter {
+    fn from(filter: AddressHistoryFilter) -> Self {
+        Self {
+            start_after: filter.start_after,
+            order: if filter.oldest_first {
+                nimiq_blockchain::HistoryOrder::OldestFirst
+            } else {
+                nimiq_blockchain::HistoryOrder::NewestFirst
+            },
+            min_block: filter.min_block,
+            max_block: filter.max_block,
+            direction: match filter.direction {
+                None => nimiq_blockchain::TransferDirection::Any,
+                Some(TransferDirection::Incoming) => nimiq_blockchain::TransferDirection::Incoming,
+                Some(TransferDirection::Outgoing) => nimiq_blockchain::TransferDirection::Outgoing,
+            },
+            kinds: filter.kinds.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RebranchReason {
+    SkipBlock,
+    Fork,
+}
+
+/// A rebranch of the main chain, as recorded by the node.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rebranch {
+    /// The local time (in milliseconds) when the rebranch happened.
+    pub timestamp: u64,
+    pub reason: RebranchReason,
+    pub common_ancestor_number: u32,
+    pub common_ancestor_hash: Blake2bHash,
+    /// The hashes of the reverted blocks, in ascending block order.
+    pub reverted_blocks: Vec<Blake2bHash>,
+    /// The hashes of the adopted blocks, in ascending block order.
+    pub adopted_blocks: Vec<Blake2bHash>,
+    /// The hashes of the transactions that fell out of the chain.
+    pub dropped_transactions: Vec<Blake2bHash>,
+}
+
+impl From<nimiq_blockchain::RebranchRecord> for Rebranch {
+    fn from(record: nimiq_blockchain::RebranchRecord) -> Self {
+        Self {
+            timestamp: record.timestamp,
+            reason: match record.reason {
+                nimiq_blockchain::RebranchReason::SkipBlock => RebranchReason::SkipBlock,
+                nimiq_blockchain::RebranchReason::Fork => RebranchReason::Fork,
+            },
+            common_ancestor_number: record.common_ancestor_number,
+            common_ancestor_hash: record.common_ancestor_hash,
+            reverted_blocks: record.reverted_blocks,
+            adopted_blocks: record.adopted_blocks,
+            dropped_transactions: record.dropped_transactions,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case", tag = "type")]
 pub enum BlockLog {
@@ -1161,3 +1374,86 @@ impl MempoolInfo {
         info
     }
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeEstimate {
+    /// The suggested total fee of the transaction.
+    pub fee: Coin,
+    /// The suggested fee per byte.
+    pub fee_per_byte: f64,
+}
+
+/// The reason a transaction was evicted from the mempool.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MempoolEvictionReason {
+    Expired,
+    Invalid,
+    TooFull,
+    Replaced,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+pub enum MempoolEvent {
+    /// A transaction was added to the mempool.
+    TransactionAdded { transaction: Transaction },
+    /// A transaction was removed from the mempool without being included in a block.
+    TransactionEvicted {
+        transaction: Transaction,
+        reason: MempoolEvictionReason,
+    },
+    /// A transaction was included in a block. The transaction carries the block number and
+    /// timestamp.
+    #[serde(rename_all = "camelCase")]
+    TransactionMined {
+        transaction: Transaction,
+        block_hash: Blake2bHash,
+    },
+    /// The subscriber lagged behind and missed the given number of events. The missed events
+    /// aren't delivered, so the subscriber should re-fetch the state it is interested in.
+    Lagged { missed: u64 },
+}
+
+/// The reputation of a peer that misbehaved recently.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerReputation {
+    pub peer_id: String,
+    /// The current score of the peer. Zero is neutral, misbehaviour makes it negative.
+    pub score: f64,
+    /// The number of times the peer got banned because of its reputation.
+    pub bans: u32,
+    /// The remaining duration of the current ban in seconds, if the peer is banned.
+    pub banned_for: Option<u64>,
+}
+
+/// Direction of the connection to a peer.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionDirection {
+    /// The peer dialed us.
+    Inbound,
+    /// We dialed the peer.
+    Outbound,
+}
+
+/// Details about a connected peer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerInfo {
+    pub peer_id: String,
+    /// The address we are connected to the peer at.
+    pub address: String,
+    /// The addresses the peer listens on, as far as it told us.
+    pub listen_addresses: Vec<String>,
+    /// Bitmask of the services the peer provides.
+    pub services: u32,
+    pub user_agent: Option<String>,
+    pub direction: ConnectionDirection,
+    /// When the first connection to the peer was established, in seconds since unix epoch.
+    pub connected_since: u64,
+    /// The reputation score of the peer. Zero is neutral, misbehaviour makes it negative.
+    pub score: f64,
+}
@@ -0,0 +1,194 @@
+use std::{collections::HashSet, sync::Arc};
+
+use async_trait::async_trait;
+use futures::FutureExt;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use nimiq_blockchain_proxy::BlockchainProxy;
+use nimiq_jsonrpc_core::{Request, Response, RpcError};
+use nimiq_jsonrpc_server::Dispatcher;
+use nimiq_rpc_interface::types::BlockchainState;
+
+use crate::server::DispatchBatch;
+
+/// Dispatcher that evaluates batches against a single blockchain state.
+///
+/// The requests of a batch that only read the blockchain are evaluated while the blockchain is
+/// pinned, such that their responses are consistent with each other. The blockchain state they
+/// were evaluated at is reported in the metadata of their responses, if they don't carry
+/// metadata of their own.
+///
+/// The other requests of a batch, e.g. those that read the mempool or that may wait for the
+/// network, can't be evaluated against a pinned blockchain. They are dispatched one after
+/// another afterwards, while the blockchain may change. Their responses only report a
+/// blockchain state in their metadata if the blockchain didn't change while they were
+/// evaluated. Otherwise, their metadata stays empty to signal that they aren't consistent with
+/// a single blockchain state.
+pub struct BatchDispatcher<D: Dispatcher> {
+    inner: D,
+    blockchain: BlockchainProxy,
+    /// The methods that only read the blockchain and don't wait for anything else, such that
+    /// they can be evaluated while the blockchain is pinned.
+    blockchain_methods: Arc<HashSet<String>>,
+}
+
+impl<D: Dispatcher> BatchDispatcher<D> {
+    pub fn new(
+        inner: D,
+        blockchain: BlockchainProxy,
+        blockchain_methods: Arc<HashSet<String>>,
+    ) -> Self {
+        Self {
+            inner,
+            blockchain,
+            blockchain_methods,
+        }
+    }
+
+    /// Checks whether a method only reads the blockchain and doesn't wait for anything else, such
+    /// that it can be evaluated while the blockchain is pinned.
+    fn is_blockchain_read(&self, method: &str) -> bool {
+        self.blockchain_methods.contains(method)
+    }
+
+    fn blockchain_state(&self) -> BlockchainState {
+        BlockchainState::with_blockchain(&self.blockchain.read())
+    }
+
+    /// Evaluates requests that only read the blockchain while the blockchain is pinned. Returns
+    /// the responses together with the indices of their requests, and the blockchain state they
+    /// were evaluated at.
+    fn dispatch_pinned(
+        &mut self,
+        requests: Vec<(usize, (Request, u64))>,
+        tx: Option<&mpsc::Sender<Vec<u8>>>,
+    ) -> (Vec<(usize, Response)>, BlockchainState) {
+        let _pin = self.blockchain.pin();
+        let blockchain_state = self.blockchain_state();
+
+        let responses = requests
+            .into_iter()
+            .filter_map(|(index, (request, id))| {
+                let request_id = request.id.clone();
+                // The methods only read the blockchain, so they complete without being woken up.
+                let response = match self.inner.dispatch(request, tx, id).now_or_never() {
+                    Some(response) => response,
+                    None => request_id.map(|request_id| {
+                        Response::new_error(
+                            request_id,
+                            RpcError::internal_error(Some(Value::String(
+                                "Method could not be evaluated against a consistent state"
+                                    .to_owned(),
+                            ))),
+                        )
+                    }),
+                };
+                response.map(|response| (index, response))
+            })
+            .collect();
+
+        (responses, blockchain_state)
+    }
+
+    /// Evaluates requests one after another. Returns the responses together with the indices of
+    /// their requests and, if the blockchain didn't change in the meantime, the blockchain state
+    /// they were evaluated at.
+    async fn dispatch_sequentially(
+        &mut self,
+        requests: Vec<(usize, (Request, u64))>,
+        tx: Option<&mpsc::Sender<Vec<u8>>>,
+    ) -> (Vec<(usize, Response)>, Option<BlockchainState>) {
+        let state_before = self.blockchain_state();
+        let mut responses = vec![];
+        for (index, (request, id)) in requests {
+            if let Some(response) = self.inner.dispatch(request, tx, id).await {
+                responses.push((index, response));
+            }
+        }
+        let state_after = self.blockchain_state();
+
+        if state_before.block_hash == state_after.block_hash {
+            (responses, Some(state_after))
+        } else {
+            (responses, None)
+        }
+    }
+}
+
+/// Sets the given blockchain state, if any, as metadata of the successful responses that don't
+/// have any metadata yet, and appends them to the given responses.
+fn set_blockchain_state(
+    mut responses: Vec<(usize, Response)>,
+    blockchain_state: Option<BlockchainState>,
+    all_responses: &mut Vec<(usize, Response)>,
+) {
+    if let Some(blockchain_state) = blockchain_state {
+        let blockchain_state =
+            serde_json::to_value(&blockchain_state).expect("Failed to serialize blockchain state");
+        for (_, response) in &mut responses {
+            if let Some(Value::Object(result)) = &mut response.result {
+                if let Some(metadata @ Value::Null) = result.get_mut("metadata") {
+                    *metadata = blockchain_state.clone();
+                }
+            }
+        }
+    }
+    all_responses.extend(responses);
+}
+
+#[async_trait]
+impl<D: Dispatcher> Dispatcher for BatchDispatcher<D> {
+    async fn dispatch(
+        &mut self,
+        request: Request,
+        tx: Option<&mpsc::Sender<Vec<u8>>>,
+        id: u64,
+    ) -> Option<Response> {
+        self.inner.dispatch(request, tx, id).await
+    }
+
+    fn match_method(&self, name: &str) -> bool {
+        self.inner.match_method(name)
+    }
+
+    fn method_names(&self) -> Vec<&str> {
+        self.inner.method_names()
+    }
+}
+
+#[async_trait]
+impl<D: Dispatcher> DispatchBatch for BatchDispatcher<D> {
+    async fn dispatch_batch(
+        &mut self,
+        requests: Vec<(Request, u64)>,
+        tx: Option<&mpsc::Sender<Vec<u8>>>,
+    ) -> Vec<Response> {
+        let (pinned_requests, other_requests): (Vec<_>, Vec<_>) = requests
+            .into_iter()
+            .enumerate()
+            .partition(|(_, (request, _))| self.is_blockchain_read(&request.method));
+
+        let mut responses = Vec::with_capacity(pinned_requests.len() + other_requests.len());
+
+        if !pinned_requests.is_empty() {
+            let (pinned_responses, blockchain_state) = self.dispatch_pinned(pinned_requests, tx);
+            set_blockchain_state(pinned_responses, Some(blockchain_state), &mut responses);
+        }
+
+        // The responses of requests during which the blockchain changed can't be made
+        // consistent, but they need to be reported nevertheless.
+        if !other_requests.is_empty() {
+            let (other_responses, blockchain_state) =
+                self.dispatch_sequentially(other_requests, tx).await;
+            set_blockchain_state(other_responses, blockchain_state, &mut responses);
+        }
+
+        // Respond in the order of the requests.
+        responses.sort_by_key(|(index, _)| *index);
+        responses
+            .into_iter()
+            .map(|(_, response)| response)
+            .collect()
+    }
+}
@@ -2,14 +2,16 @@ use async_trait::async_trait;
 use futures::{future, stream::BoxStream, StreamExt};
 
 use nimiq_account::{BlockLog as BBlockLog, TransactionLog};
+use nimiq_blockchain::Blockchain;
 use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainEvent};
 use nimiq_blockchain_proxy::{BlockchainProxy, BlockchainReadProxy};
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 use nimiq_primitives::policy::Policy;
 use nimiq_rpc_interface::types::{
-    is_of_log_type_and_related_to_addresses, BlockLog, BlockchainState, ParkedSet, RPCData,
-    RPCResult, Validator,
+    is_of_log_type_and_related_to_addresses, AddressHistoryFilter, AddressHistoryMetadata,
+    BalanceHistoryEntry, BlockLog, BlockchainState, ParkedSet, ProducedBlock, RPCData, RPCResult,
+    Rebranch, Validator, ValidatorPunishment,
 };
 use nimiq_rpc_interface::{
     blockchain::BlockchainInterface,
@@ -29,6 +31,17 @@ impl BlockchainDispatcher {
     }
 }
 
+/// Returns the blockchain state at the given block of the main chain.
+fn get_blockchain_state_at(
+    blockchain: &Blockchain,
+    block_number: u32,
+) -> Result<BlockchainState, Error> {
+    let block = blockchain
+        .get_block_at(block_number, false, None)
+        .map_err(|_| Error::BlockNotFound(block_number))?;
+    Ok(BlockchainState::new(block_number, block.hash()))
+}
+
 /// Tries to fetch a block given its hash. It has an option to include the transactions in the
 /// block, which defaults to false.
 /// This function requires the read lock acquisition prior to its execution
@@ -390,18 +403,39 @@ impl BlockchainInterface for BlockchainDispatcher {
     /// Returns the hashes for the latest transactions for a given address. All the transactions
     /// where the given address is listed as a recipient or as a sender are considered. Reward
     /// transactions are also returned. It has an option to specify the maximum number of hashes to
-    /// fetch, it defaults to 500.
+    /// fetch, it defaults to 500. The optional filter allows paging through the history and
+    /// restricting it by block range, direction and transaction kind. Filtered queries return at
+    /// most `MAX_ADDRESS_HISTORY_TXS` (1000) hashes. If they stop scanning the history before
+    /// finding the requested number of transactions, the metadata contains the cursor to continue
+    /// with.
     async fn get_transaction_hashes_by_address(
         &mut self,
         address: Address,
         max: Option<u16>,
-    ) -> RPCResult<Vec<Blake2bHash>, (), Self::Error> {
+        filter: Option<AddressHistoryFilter>,
+    ) -> RPCResult<Vec<Blake2bHash>, AddressHistoryMetadata, Self::Error> {
         if let BlockchainProxy::Full(blockchain) = &self.blockchain {
-            Ok(blockchain
-                .read()
-                .history_store
-                .get_tx_hashes_by_address(&address, max.unwrap_or(500), None)
-                .into())
+            let blockchain = blockchain.read();
+            let history_store = &blockchain.history_store;
+            let max = max.unwrap_or(500);
+
+            let (tx_hashes, scan_cursor) = match filter {
+                Some(filter) => {
+                    let (ext_txs, scan_cursor) =
+                        history_store.get_ext_txs_by_address(&address, max, &filter.into(), None);
+                    let tx_hashes = ext_txs.iter().map(|ext_tx| ext_tx.tx_hash()).collect();
+                    (tx_hashes, scan_cursor)
+                }
+                None => (
+                    history_store.get_tx_hashes_by_address(&address, max, None),
+                    None,
+                ),
+            };
+
+            Ok(RPCData::new(
+                tx_hashes,
+                AddressHistoryMetadata { scan_cursor },
+            ))
         } else {
             Err(Error::NotSupportedForLightBlockchain)
         }
@@ -410,37 +444,28 @@ impl BlockchainInterface for BlockchainDispatcher {
     /// Returns the latest transactions for a given address. All the transactions
     /// where the given address is listed as a recipient or as a sender are considered. Reward
     /// transactions are also returned. It has an option to specify the maximum number of transactions
-    /// to fetch, it defaults to 500.
+    /// to fetch, it defaults to 500 and is capped to `MAX_ADDRESS_HISTORY_TXS` (1000). The optional
+    /// filter allows paging through the history and restricting it by block range, direction and
+    /// transaction kind. If the history isn't scanned completely before finding the requested
+    /// number of transactions, the metadata contains the cursor to continue with.
     async fn get_transactions_by_address(
         &mut self,
         address: Address,
         max: Option<u16>,
-    ) -> RPCResult<Vec<ExecutedTransaction>, (), Self::Error> {
+        filter: Option<AddressHistoryFilter>,
+    ) -> RPCResult<Vec<ExecutedTransaction>, AddressHistoryMetadata, Self::Error> {
         if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
-            // Get the transaction hashes for this address.
-            let tx_hashes = blockchain.history_store.get_tx_hashes_by_address(
+            // Get the extended transactions for this address.
+            let (ext_txs, scan_cursor) = blockchain.history_store.get_ext_txs_by_address(
                 &address,
                 max.unwrap_or(500),
+                &filter.unwrap_or_default().into(),
                 None,
             );
 
             let mut txs = vec![];
 
-            for hash in tx_hashes {
-                // Get all the extended transactions that correspond to this hash.
-                let mut extended_tx_vec = blockchain.history_store.get_ext_tx_by_hash(&hash, None);
-
-                // Unpack the transaction or raise an error.
-                let extended_tx = match extended_tx_vec.len() {
-                    0 => {
-                        return Err(Error::TransactionNotFound(hash));
-                    }
-                    1 => extended_tx_vec.pop().unwrap(),
-                    _ => {
-                        return Err(Error::MultipleTransactionsFound(hash));
-                    }
-                };
-
+            for extended_tx in ext_txs {
                 // Convert the extended transaction into a regular transaction. This will also convert
                 // reward inherents.
                 let block_number = extended_tx.block_number;
@@ -456,7 +481,7 @@ impl BlockchainInterface for BlockchainDispatcher {
                 }
             }
 
-            Ok(txs.into())
+            Ok(RPCData::new(txs, AddressHistoryMetadata { scan_cursor }))
         } else {
             Err(Error::NotSupportedForLightBlockchain)
         }
@@ -485,6 +510,116 @@ impl BlockchainInterface for BlockchainDispatcher {
         }
     }
 
+    /// Tries to fetch the account at the given address as it was right after the given block.
+    async fn get_account_by_address_at_block(
+        &mut self,
+        address: Address,
+        block_number: u32,
+    ) -> RPCResult<Account, BlockchainState, Self::Error> {
+        if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
+            let account = blockchain.get_account_at(&address, block_number)?;
+            let blockchain_state = get_blockchain_state_at(&blockchain, block_number)?;
+            Account::try_from_account(address, account, blockchain_state).map_err(Error::Core)
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
+    /// Returns the balances of the given address right after each block in the given range that
+    /// touched it, as recorded by the balance history index. The first entry is the balance at the
+    /// start of the range. The range defaults to the whole chain up to the current head.
+    async fn get_balance_history(
+        &mut self,
+        address: Address,
+        from_block: Option<u32>,
+        to_block: Option<u32>,
+    ) -> RPCResult<Vec<BalanceHistoryEntry>, BlockchainState, Self::Error> {
+        if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
+            let head_number = blockchain.block_number();
+            let entries = blockchain.get_balance_history(
+                &address,
+                from_block.unwrap_or(0),
+                to_block.unwrap_or(head_number),
+            )?;
+
+            Ok(RPCData::new(
+                entries.into_iter().map(Into::into).collect(),
+                BlockchainState::new(head_number, blockchain.head_hash()),
+            ))
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
+    /// Returns the blocks in the given range that were produced by the given validator, as
+    /// recorded by the validator history index. The range defaults to the whole chain up to the
+    /// current head.
+    async fn get_blocks_by_producer(
+        &mut self,
+        validator: Address,
+        from_block: Option<u32>,
+        to_block: Option<u32>,
+    ) -> RPCResult<Vec<ProducedBlock>, BlockchainState, Self::Error> {
+        if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
+            let head_number = blockchain.block_number();
+            let entries = blockchain.get_blocks_by_producer(
+                &validator,
+                from_block.unwrap_or(0),
+                to_block.unwrap_or(head_number),
+            )?;
+
+            Ok(RPCData::new(
+                entries
+                    .into_iter()
+                    .filter_map(ProducedBlock::from_entry)
+                    .collect(),
+                BlockchainState::new(head_number, blockchain.head_hash()),
+            ))
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
+    /// Returns the skip blocks that replaced the given validator and the slashes and parks that
+    /// hit it, as recorded by the validator history index.
+    async fn get_validator_punishment_history(
+        &mut self,
+        validator: Address,
+    ) -> RPCResult<Vec<ValidatorPunishment>, BlockchainState, Self::Error> {
+        if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
+            let entries = blockchain.get_validator_punishments(&validator)?;
+
+            Ok(RPCData::new(
+                entries
+                    .into_iter()
+                    .filter_map(ValidatorPunishment::from_entry)
+                    .collect(),
+                BlockchainState::new(blockchain.block_number(), blockchain.head_hash()),
+            ))
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
+    /// Returns the most recent rebranches of the main chain, newest first. It has an option to
+    /// specify the maximum number of rebranches to fetch, it defaults to all recorded ones.
+    async fn get_recent_rebranches(
+        &mut self,
+        max: Option<u16>,
+    ) -> RPCResult<Vec<Rebranch>, (), Self::Error> {
+        if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
+            Ok(blockchain
+                .get_recent_rebranches(None)
+                .into_iter()
+                .take(max.map_or(usize::MAX, usize::from))
+                .map(Rebranch::from)
+                .collect::<Vec<_>>()
+                .into())
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
     /// Returns a collection of the currently active validator's addresses and balances.
     async fn get_active_validators(
         &mut self,
@@ -642,6 +777,26 @@ impl BlockchainInterface for BlockchainDispatcher {
         }
     }
 
+    /// Tries to fetch a staker information given its address as it was right after the given
+    /// block.
+    async fn get_staker_by_address_at_block(
+        &mut self,
+        address: Address,
+        block_number: u32,
+    ) -> RPCResult<Staker, BlockchainState, Self::Error> {
+        if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
+            match blockchain.get_staker_at(&address, block_number)? {
+                Some(staker) => Ok(RPCData {
+                    data: Staker::from_staker(&staker),
+                    metadata: get_blockchain_state_at(&blockchain, block_number)?,
+                }),
+                None => Err(Error::StakerNotFound(address)),
+            }
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
     /// Subscribes to new block events (retrieves the full block).
     #[stream]
     async fn subscribe_for_head_block(
@@ -2,14 +2,20 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use beserial::Deserialize;
+use futures::{future, stream::BoxStream, StreamExt};
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
 
 use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_keys::Address;
 use nimiq_mempool::mempool::Mempool;
-
+use nimiq_mempool::mempool_events::{EvictionReason, MempoolEvent as MMempoolEvent};
 use nimiq_mempool::mempool_transactions::TxPriority;
+use nimiq_primitives::policy::Policy;
 use nimiq_rpc_interface::mempool::MempoolInterface;
-use nimiq_rpc_interface::types::RPCResult;
-use nimiq_rpc_interface::types::{HashOrTx, MempoolInfo};
+use nimiq_rpc_interface::types::{
+    FeeEstimate, HashOrTx, MempoolEvent, MempoolEvictionReason, MempoolInfo, RPCData, RPCResult,
+    Transaction,
+};
 
 use crate::error::Error;
 
@@ -92,4 +98,92 @@ impl MempoolInterface for MempoolDispatcher {
     async fn get_min_fee_per_byte(&mut self) -> RPCResult<f64, (), Self::Error> {
         Ok(self.mempool.get_rules().tx_fee_per_byte.into())
     }
+
+    async fn estimate_fee(
+        &mut self,
+        tx_size: usize,
+        target_blocks: u32,
+    ) -> RPCResult<FeeEstimate, (), Self::Error> {
+        if tx_size == 0 || tx_size > Policy::MAX_SIZE_MICRO_BODY {
+            return Err(Error::InvalidArgument(format!(
+                "Transaction size must be between 1 and {} bytes",
+                Policy::MAX_SIZE_MICRO_BODY
+            )));
+        }
+        if target_blocks == 0 {
+            return Err(Error::InvalidArgument(
+                "Target blocks must be positive".to_owned(),
+            ));
+        }
+
+        let estimate = self.mempool.estimate_fee(tx_size, target_blocks);
+        Ok(FeeEstimate {
+            fee: estimate.fee,
+            fee_per_byte: estimate.fee_per_byte,
+        }
+        .into())
+    }
+
+    #[stream]
+    async fn subscribe_for_mempool_events_by_addresses(
+        &mut self,
+        addresses: Vec<Address>,
+    ) -> Result<BoxStream<'static, RPCData<MempoolEvent, ()>>, Self::Error> {
+        let stream = BroadcastStream::new(self.mempool.subscribe_events());
+
+        Ok(stream
+            .filter_map(move |event| {
+                let result = match event {
+                    Ok(event) if addresses.is_empty() || event.is_related_to(&addresses) => {
+                        convert_event(event).map(RPCData::from)
+                    }
+                    Ok(_) => None,
+                    // Events that are missed because the subscriber lagged behind can't be
+                    // recovered, so the subscriber is told how many it missed.
+                    Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                        Some(MempoolEvent::Lagged { missed }.into())
+                    }
+                };
+                future::ready(result)
+            })
+            .boxed())
+    }
+}
+
+fn convert_event(event: MMempoolEvent) -> Option<MempoolEvent> {
+    let event = match event {
+        MMempoolEvent::TransactionAdded(transaction) => MempoolEvent::TransactionAdded {
+            transaction: Transaction::from_transaction(transaction),
+        },
+        MMempoolEvent::TransactionEvicted {
+            transaction,
+            reason,
+        } => MempoolEvent::TransactionEvicted {
+            transaction: Transaction::from_transaction(transaction),
+            reason: match reason {
+                EvictionReason::Expired => MempoolEvictionReason::Expired,
+                EvictionReason::Invalid => MempoolEvictionReason::Invalid,
+                EvictionReason::TooFull => MempoolEvictionReason::TooFull,
+                EvictionReason::Replaced => MempoolEvictionReason::Replaced,
+                // Transactions leaving the mempool to be included in a block are reported
+                // once the block is adopted.
+                EvictionReason::BlockBuilding | EvictionReason::AlreadyIncluded => return None,
+            },
+        },
+        MMempoolEvent::TransactionMined {
+            transaction,
+            block_hash,
+            block_number,
+            timestamp,
+        } => MempoolEvent::TransactionMined {
+            transaction: Transaction::from_blockchain(
+                transaction,
+                block_number,
+                timestamp,
+                block_number,
+            ),
+            block_hash,
+        },
+    };
+    Some(event)
 }
@@ -1,11 +1,14 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 
-use nimiq_network_interface::network::Network as InterfaceNetwork;
-use nimiq_network_libp2p::Network;
+use nimiq_network_interface::{
+    network::{CloseReason, Network as InterfaceNetwork},
+    Multiaddr,
+};
+use nimiq_network_libp2p::{ConnectionDirection, Network, PeerId};
 use nimiq_rpc_interface::network::NetworkInterface;
-use nimiq_rpc_interface::types::RPCResult;
+use nimiq_rpc_interface::types::{self, PeerInfo, PeerReputation, RPCResult};
 
 use crate::error::Error;
 
@@ -19,6 +22,11 @@ impl NetworkDispatcher {
     }
 }
 
+fn parse_peer_id(peer_id: &str) -> Result<PeerId, Error> {
+    PeerId::from_str(peer_id)
+        .map_err(|_| Error::InvalidArgument(format!("Invalid peer ID: {}", peer_id)))
+}
+
 #[nimiq_jsonrpc_derive::service(rename_all = "camelCase")]
 #[async_trait]
 impl NetworkInterface for NetworkDispatcher {
@@ -44,4 +52,100 @@ impl NetworkInterface for NetworkDispatcher {
             .collect::<Vec<_>>()
             .into())
     }
+
+    /// Returns the reputation of all peers that misbehaved recently, including the ones that are
+    /// banned because of it.
+    async fn get_peer_reputations(&mut self) -> RPCResult<Vec<PeerReputation>, (), Self::Error> {
+        Ok(self
+            .network
+            .peer_reputations()
+            .into_iter()
+            .map(|reputation| PeerReputation {
+                peer_id: reputation.peer_id.to_string(),
+                score: reputation.score,
+                bans: reputation.bans,
+                banned_for: reputation.banned_for.map(|duration| duration.as_secs()),
+            })
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    /// Returns the details of a connected peer, such as its addresses, services, user agent and
+    /// reputation score.
+    async fn get_peer_info(&mut self, peer_id: String) -> RPCResult<PeerInfo, (), Self::Error> {
+        let peer_id = parse_peer_id(&peer_id)?;
+        let details = self
+            .network
+            .peer_details(peer_id)
+            .await?
+            .ok_or(Error::PeerNotFound(peer_id))?;
+
+        Ok(PeerInfo {
+            peer_id: details.peer_id.to_string(),
+            address: details.address.to_string(),
+            listen_addresses: details
+                .listen_addresses
+                .iter()
+                .map(|address| address.to_string())
+                .collect(),
+            services: details.services.bits(),
+            user_agent: details.user_agent,
+            direction: match details.direction {
+                ConnectionDirection::Inbound => types::ConnectionDirection::Inbound,
+                ConnectionDirection::Outbound => types::ConnectionDirection::Outbound,
+            },
+            connected_since: details.connected_since,
+            score: details.score,
+        }
+        .into())
+    }
+
+    /// Disconnects and bans a peer for the given duration in seconds. Without a duration, the
+    /// peer stays banned until it is un-banned.
+    async fn ban_peer(
+        &mut self,
+        peer_id: String,
+        duration: Option<u64>,
+    ) -> RPCResult<(), (), Self::Error> {
+        let peer_id = parse_peer_id(&peer_id)?;
+        if duration == Some(0) {
+            return Err(Error::InvalidArgument(
+                "Ban duration must not be zero".to_string(),
+            ));
+        }
+
+        self.network
+            .ban_peer(peer_id, duration.map(Duration::from_secs))
+            .await;
+        Ok(().into())
+    }
+
+    /// Lifts the ban of a peer, no matter whether it was banned manually or because of its
+    /// reputation.
+    async fn unban_peer(&mut self, peer_id: String) -> RPCResult<(), (), Self::Error> {
+        let peer_id = parse_peer_id(&peer_id)?;
+        self.network.unban_peer(peer_id).await;
+        Ok(().into())
+    }
+
+    /// Dials the given multiaddress.
+    async fn connect_to_address(&mut self, address: String) -> RPCResult<(), (), Self::Error> {
+        let address = Multiaddr::from_str(&address)
+            .map_err(|_| Error::InvalidArgument(format!("Invalid address: {}", address)))?;
+        self.network.dial_address(address).await?;
+        Ok(().into())
+    }
+
+    /// Closes the connection to a peer without banning it.
+    async fn disconnect_peer(&mut self, peer_id: String) -> RPCResult<(), (), Self::Error> {
+        let peer_id = parse_peer_id(&peer_id)?;
+        if !self.network.has_peer(peer_id) {
+            return Err(Error::PeerNotFound(peer_id));
+        }
+
+        self.network
+            .disconnect_peer(peer_id, CloseReason::Other)
+            .await;
+        Ok(().into())
+    }
 }
@@ -27,9 +27,18 @@ pub struct WalletDispatcher {
 
 impl WalletDispatcher {
     pub fn new(wallet_store: Arc<WalletStore>) -> Self {
+        Self::with_unlocked_wallets(wallet_store, Default::default())
+    }
+
+    /// Creates a dispatcher that shares the given unlocked wallets, e.g. with other instances of
+    /// this dispatcher.
+    pub fn with_unlocked_wallets(
+        wallet_store: Arc<WalletStore>,
+        unlocked_wallets: Arc<RwLock<UnlockedWallets>>,
+    ) -> Self {
         Self {
             wallet_store,
-            unlocked_wallets: Arc::new(RwLock::new(UnlockedWallets::default())),
+            unlocked_wallets,
         }
     }
 }
@@ -4,6 +4,7 @@ use thiserror::Error;
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 use nimiq_mempool::verify::VerifyErr;
+use nimiq_network_libp2p::PeerId;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -13,6 +14,9 @@ pub enum Error {
     #[error("{0}")]
     NetworkError(#[from] nimiq_network_libp2p::NetworkError),
 
+    #[error("{0}")]
+    Blockchain(#[from] nimiq_blockchain_interface::BlockchainError),
+
     #[error("Mempool rejected transaction: {0}")]
     MempoolError(VerifyErr),
 
@@ -58,6 +62,9 @@ pub enum Error {
     #[error("No staker with address: {0}")]
     StakerNotFound(Address),
 
+    #[error("Not connected to peer: {0}")]
+    PeerNotFound(PeerId),
+
     #[error("Wrong passphrase")]
     WrongPassphrase,
 
@@ -1,7 +1,11 @@
-pub use nimiq_jsonrpc_server::{Config, Server};
+pub use server::{Config, Server};
 
 pub use error::Error;
 
 pub mod dispatchers;
 pub mod error;
+pub mod permissions;
+pub mod rate_limit;
+pub mod server;
+pub mod subscriptions;
 pub mod wallets;
@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use nimiq_jsonrpc_core::{Credentials, RpcError};
+
+use crate::subscriptions::UNSUBSCRIBE_METHOD;
+
+/// JSON-RPC error code returned when a user calls a method its role doesn't permit.
+pub const PERMISSION_DENIED_CODE: i64 = -32003;
+
+/// A user that can authenticate with the RPC server.
+#[derive(Clone, Debug)]
+pub struct User {
+    /// The credentials used for HTTP basic auth.
+    pub credentials: Credentials,
+    /// The methods this user is allowed to call. `None` allows all methods.
+    pub allowed_methods: Option<HashSet<String>>,
+}
+
+impl User {
+    /// Checks whether the user's credentials match the given username and password.
+    pub fn authenticate(&self, username: &str, password: &str) -> bool {
+        // Both comparisons are always evaluated, such that the response time doesn't reveal
+        // which one failed.
+        let is_username_equal = constant_time_eq(&self.credentials.username, username);
+        let is_password_equal = constant_time_eq(&self.credentials.password, password);
+        is_username_equal & is_password_equal
+    }
+
+    /// Checks whether the user is allowed to call the given method. Cancelling a subscription is
+    /// always allowed.
+    pub fn is_allowed(&self, method: &str) -> bool {
+        match &self.allowed_methods {
+            Some(allowed_methods) => {
+                method == UNSUBSCRIBE_METHOD || allowed_methods.contains(method)
+            }
+            None => true,
+        }
+    }
+}
+
+/// Compares two strings in time that only depends on their lengths, not on their contents.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.bytes()
+        .zip(b.bytes())
+        .fold(0, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+/// The error returned to users that call a method they are not permitted to call.
+pub fn permission_denied(method: &str) -> RpcError {
+    RpcError {
+        code: PERMISSION_DENIED_CODE,
+        message: Some("Method not permitted".to_owned()),
+        data: Some(Value::String(method.to_owned())),
+    }
+}
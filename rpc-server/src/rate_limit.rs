@@ -0,0 +1,237 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use serde_json::Value;
+
+use nimiq_jsonrpc_core::RpcError;
+
+/// JSON-RPC error code returned when a client exceeds its rate limit.
+pub const RATE_LIMIT_EXCEEDED_CODE: i64 = -32005;
+
+/// Interval at which buckets that are full again are removed.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Identifies the client a request originates from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ClientId {
+    /// A client that authenticated with the given username.
+    User(String),
+    /// An unauthenticated client with the given IP address.
+    Ip(IpAddr),
+    /// A client for which neither credentials nor an IP address are known.
+    Unknown,
+}
+
+/// A token bucket limit for a class of methods.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RateLimit {
+    /// The methods this limit applies to. If empty, the limit applies to all methods that are
+    /// not covered by another limit.
+    pub methods: Vec<String>,
+    /// Maximum number of requests a client can make in a burst.
+    pub burst: u32,
+    /// Number of requests per second a client can make in the long run.
+    pub per_second: f64,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.per_second).min(limit.burst as f64);
+        self.last_refill = now;
+    }
+
+    fn is_full(&self, limit: &RateLimit) -> bool {
+        self.tokens >= limit.burst as f64
+    }
+}
+
+struct Buckets {
+    buckets: HashMap<(ClientId, usize), TokenBucket>,
+    last_sweep: Instant,
+}
+
+/// Rate limiter that keeps a separate token bucket per client and class of methods.
+pub struct RateLimiter {
+    limits: Vec<RateLimit>,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: Vec<RateLimit>) -> Self {
+        Self {
+            limits,
+            buckets: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                last_sweep: Instant::now(),
+            }),
+        }
+    }
+
+    /// Returns the index of the limit that applies to the given method, if any.
+    fn limit_index(&self, method: &str) -> Option<usize> {
+        self.limits
+            .iter()
+            .position(|limit| limit.methods.iter().any(|m| m == method))
+            .or_else(|| {
+                self.limits
+                    .iter()
+                    .position(|limit| limit.methods.is_empty())
+            })
+    }
+
+    /// Takes a token from the client's bucket for the given method. Returns `false` if the
+    /// client has exceeded its limit.
+    pub fn check(&self, client: &ClientId, method: &str) -> bool {
+        self.check_at(client, method, Instant::now())
+    }
+
+    fn check_at(&self, client: &ClientId, method: &str, now: Instant) -> bool {
+        let index = match self.limit_index(method) {
+            Some(index) => index,
+            None => return true,
+        };
+        let limit = &self.limits[index];
+
+        let mut buckets = self.buckets.lock();
+
+        // Periodically forget about clients that haven't used up any of their budget, to not
+        // grow unbounded.
+        if now.saturating_duration_since(buckets.last_sweep) >= SWEEP_INTERVAL {
+            let limits = &self.limits;
+            buckets.buckets.retain(|(_, index), bucket| {
+                bucket.refill(&limits[*index], now);
+                !bucket.is_full(&limits[*index])
+            });
+            buckets.last_sweep = now;
+        }
+
+        let bucket = buckets
+            .buckets
+            .entry((client.clone(), index))
+            .or_insert_with(|| TokenBucket::new(limit, now));
+        bucket.refill(limit, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// The error returned to clients that exceeded their rate limit.
+pub fn rate_limit_exceeded(method: &str) -> RpcError {
+    RpcError {
+        code: RATE_LIMIT_EXCEEDED_CODE,
+        message: Some("Rate limit exceeded".to_owned()),
+        data: Some(Value::String(method.to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter() -> RateLimiter {
+        RateLimiter::new(vec![
+            RateLimit {
+                methods: vec!["getTransactionsByAddress".to_owned()],
+                burst: 2,
+                per_second: 1.0,
+            },
+            RateLimit {
+                methods: vec![],
+                burst: 10,
+                per_second: 10.0,
+            },
+        ])
+    }
+
+    #[test]
+    fn it_limits_bursts_and_refills() {
+        let limiter = limiter();
+        let client = ClientId::User("test".to_owned());
+        let now = Instant::now();
+
+        assert!(limiter.check_at(&client, "getTransactionsByAddress", now));
+        assert!(limiter.check_at(&client, "getTransactionsByAddress", now));
+        assert!(!limiter.check_at(&client, "getTransactionsByAddress", now));
+
+        // Other method classes have their own budget.
+        assert!(limiter.check_at(&client, "getBlockNumber", now));
+
+        // After one second, one token has been replenished.
+        let later = now + Duration::from_secs(1);
+        assert!(limiter.check_at(&client, "getTransactionsByAddress", later));
+        assert!(!limiter.check_at(&client, "getTransactionsByAddress", later));
+    }
+
+    #[test]
+    fn it_limits_clients_separately() {
+        let limiter = limiter();
+        let alice = ClientId::User("alice".to_owned());
+        let bob = ClientId::Ip("127.0.0.1".parse().unwrap());
+        let now = Instant::now();
+
+        assert!(limiter.check_at(&alice, "getTransactionsByAddress", now));
+        assert!(limiter.check_at(&alice, "getTransactionsByAddress", now));
+        assert!(!limiter.check_at(&alice, "getTransactionsByAddress", now));
+
+        assert!(limiter.check_at(&bob, "getTransactionsByAddress", now));
+    }
+
+    #[test]
+    fn it_sweeps_full_buckets_periodically() {
+        let limiter = limiter();
+        let alice = ClientId::User("alice".to_owned());
+        let bob = ClientId::User("bob".to_owned());
+        let now = Instant::now();
+
+        assert!(limiter.check_at(&alice, "getTransactionsByAddress", now));
+        assert!(limiter.check_at(&bob, "getTransactionsByAddress", now));
+
+        // Bob uses up his budget shortly before the sweep, while Alice's bucket is full again.
+        let before_sweep = now + SWEEP_INTERVAL - Duration::from_millis(100);
+        assert!(limiter.check_at(&bob, "getTransactionsByAddress", before_sweep));
+        assert!(limiter.check_at(&bob, "getTransactionsByAddress", before_sweep));
+        assert_eq!(limiter.buckets.lock().buckets.len(), 2);
+
+        assert!(!limiter.check_at(&bob, "getTransactionsByAddress", now + SWEEP_INTERVAL));
+        let buckets = limiter.buckets.lock();
+        assert_eq!(buckets.buckets.len(), 1);
+        assert!(buckets.buckets.contains_key(&(bob, 0)));
+    }
+
+    #[test]
+    fn it_does_not_limit_uncovered_methods() {
+        let limiter = RateLimiter::new(vec![RateLimit {
+            methods: vec!["getTransactionsByAddress".to_owned()],
+            burst: 0,
+            per_second: 0.0,
+        }]);
+        let client = ClientId::Unknown;
+
+        assert!(!limiter.check(&client, "getTransactionsByAddress"));
+        assert!(limiter.check(&client, "getBlockNumber"));
+    }
+}
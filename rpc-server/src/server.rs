@@ -0,0 +1,463 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use ipnet::IpNet;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::{mpsc, Mutex, MutexGuard};
+use warp::{
+    http::{header, StatusCode},
+    ws::{Message, WebSocket},
+    Filter, Rejection, Reply,
+};
+
+use nimiq_jsonrpc_core::{Request, Response, RpcError};
+use nimiq_jsonrpc_server::Dispatcher;
+
+use crate::{
+    permissions::{permission_denied, User},
+    rate_limit::{rate_limit_exceeded, ClientId, RateLimit, RateLimiter},
+};
+
+/// Size of the buffer for outgoing messages of a websocket connection.
+const WEBSOCKET_BUFFER_SIZE: usize = 16;
+
+/// Maximum number of requests in a batch.
+const MAX_BATCH_SIZE: usize = 100;
+
+/// Number of dispatchers, i.e. the number of requests that are dispatched concurrently.
+const NUM_DISPATCHERS: usize = 16;
+
+/// Configuration of the HTTP and websocket transport of the RPC server.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// The address the server listens on.
+    pub bind_to: SocketAddr,
+
+    /// Enables the websocket endpoint at `/ws`.
+    pub enable_websocket: bool,
+
+    /// If specified, only requests from these subnets are accepted.
+    pub ip_whitelist: Option<Vec<IpNet>>,
+
+    /// Origins that are allowed to make cross-origin requests. A single `*` allows any origin.
+    /// If empty, no CORS headers are sent.
+    pub cors_origins: Vec<String>,
+
+    /// If not empty, requests need to authenticate as one of these users using HTTP basic auth.
+    /// Each user can only call the methods permitted to it.
+    pub users: Vec<User>,
+
+    /// Rate limits applied per client. Clients are identified by their username if they are
+    /// authenticated, or by their IP address otherwise.
+    pub rate_limits: Vec<RateLimit>,
+}
+
+/// Reasons for rejecting a request before it is dispatched.
+#[derive(Debug)]
+enum AccessDenied {
+    /// The remote IP is not in the IP whitelist.
+    Forbidden,
+    /// The request is missing the correct credentials.
+    Unauthorized,
+}
+
+impl warp::reject::Reject for AccessDenied {}
+
+/// State shared between all connections of the server.
+struct Shared<D: DispatchBatch> {
+    dispatchers: Vec<Mutex<D>>,
+    next_dispatcher: AtomicUsize,
+    users: HashMap<String, User>,
+    rate_limiter: RateLimiter,
+    next_id: AtomicU64,
+}
+
+/// The JSON-RPC server.
+///
+/// Requests are accepted via HTTP `POST` at `/` and, if enabled, via websocket at `/ws`. Both
+/// single requests and batches of requests are supported. Before a request is dispatched, the
+/// remote IP is checked against the IP whitelist, the basic auth credentials are verified, the
+/// user's permissions are checked and the client's rate limit is applied.
+pub struct Server<D: DispatchBatch> {
+    config: Arc<Config>,
+    shared: Arc<Shared<D>>,
+}
+
+impl<D: DispatchBatch> Server<D> {
+    /// Creates a new server. Requests are dispatched concurrently by a pool of dispatchers
+    /// created with `new_dispatcher`, so any state the dispatchers need to share must be shared
+    /// between the instances returned by it.
+    pub fn new(config: Config, new_dispatcher: impl FnMut() -> D) -> Self {
+        let rate_limiter = RateLimiter::new(config.rate_limits.clone());
+        let users = config
+            .users
+            .iter()
+            .map(|user| (user.credentials.username.clone(), user.clone()))
+            .collect();
+        Self {
+            config: Arc::new(config),
+            shared: Arc::new(Shared {
+                dispatchers: std::iter::repeat_with(new_dispatcher)
+                    .take(NUM_DISPATCHERS)
+                    .map(Mutex::new)
+                    .collect(),
+                next_dispatcher: AtomicUsize::new(0),
+                users,
+                rate_limiter,
+                next_id: AtomicU64::new(1),
+            }),
+        }
+    }
+
+    /// Runs the server until it fails.
+    pub async fn run(&self) {
+        let config = Arc::clone(&self.config);
+        let access = access_filter(Arc::clone(&config));
+
+        let http = {
+            let shared = Arc::clone(&self.shared);
+            warp::path::end()
+                .and(warp::post())
+                .and(access.clone())
+                .and(warp::body::bytes())
+                .and_then(move |client: ClientId, body: Bytes| {
+                    let shared = Arc::clone(&shared);
+                    async move { Ok::<_, Rejection>(handle_http(body, client, shared).await) }
+                })
+        };
+
+        let enable_websocket = config.enable_websocket;
+        let ws = {
+            let shared = Arc::clone(&self.shared);
+            warp::path("ws")
+                .and(warp::path::end())
+                .and(warp::any().and_then(move || async move {
+                    if enable_websocket {
+                        Ok(())
+                    } else {
+                        Err(warp::reject::not_found())
+                    }
+                }))
+                .untuple_one()
+                .and(access)
+                .and(warp::ws())
+                .map(move |client: ClientId, websocket: warp::ws::Ws| {
+                    let shared = Arc::clone(&shared);
+                    websocket
+                        .on_upgrade(move |websocket| handle_websocket(websocket, client, shared))
+                })
+        };
+
+        let routes = http.or(ws).recover(handle_rejection);
+
+        if config.cors_origins.is_empty() {
+            warp::serve(routes).run(config.bind_to).await;
+        } else {
+            warp::serve(routes.with(cors(&config.cors_origins)))
+                .run(config.bind_to)
+                .await;
+        }
+    }
+}
+
+/// Builds the CORS filter for the given origins, which also answers preflight requests.
+fn cors(origins: &[String]) -> warp::cors::Builder {
+    let cors = warp::cors()
+        .allow_methods(vec!["POST", "OPTIONS"])
+        .allow_headers(vec![header::CONTENT_TYPE, header::AUTHORIZATION]);
+
+    if origins.iter().any(|origin| origin == "*") {
+        cors.allow_any_origin()
+    } else {
+        cors.allow_origins(origins.iter().map(String::as_str))
+    }
+}
+
+/// Rejects requests from IPs outside of the whitelist and requests without valid credentials.
+/// Extracts the ID of the client making the request.
+fn access_filter(
+    config: Arc<Config>,
+) -> impl Filter<Extract = (ClientId,), Error = Rejection> + Clone + Send + Sync + 'static {
+    warp::addr::remote()
+        .and(warp::header::optional::<String>(
+            header::AUTHORIZATION.as_str(),
+        ))
+        .and_then(
+            move |remote: Option<SocketAddr>, authorization: Option<String>| {
+                let config = Arc::clone(&config);
+                async move {
+                    let ip = remote.map(|addr| addr.ip());
+                    if !is_ip_allowed(&config, ip) {
+                        log::debug!(
+                            ?remote,
+                            "Rejecting RPC request from IP outside of whitelist"
+                        );
+                        return Err(warp::reject::custom(AccessDenied::Forbidden));
+                    }
+
+                    if !config.users.is_empty() {
+                        let username = authorization
+                            .as_deref()
+                            .and_then(parse_basic_auth)
+                            .filter(|(username, password)| {
+                                config
+                                    .users
+                                    .iter()
+                                    .any(|user| user.authenticate(username, password))
+                            })
+                            .map(|(username, _)| username);
+                        return match username {
+                            Some(username) => Ok(ClientId::User(username)),
+                            None => Err(warp::reject::custom(AccessDenied::Unauthorized)),
+                        };
+                    }
+
+                    Ok(ip.map(ClientId::Ip).unwrap_or(ClientId::Unknown))
+                }
+            },
+        )
+}
+
+fn is_ip_allowed(config: &Config, ip: Option<IpAddr>) -> bool {
+    match (&config.ip_whitelist, ip) {
+        (None, _) => true,
+        (Some(ip_whitelist), Some(ip)) => ip_whitelist.iter().any(|subnet| subnet.contains(&ip)),
+        (Some(_), None) => false,
+    }
+}
+
+/// Parses the value of an `Authorization` header for HTTP basic auth into username and password.
+fn parse_basic_auth(authorization: &str) -> Option<(String, String)> {
+    let encoded = authorization.strip_prefix("Basic ")?;
+    let decoded = String::from_utf8(BASE64.decode(encoded.trim()).ok()?).ok()?;
+    let (username, password) = decoded.split_once(':')?;
+    Some((username.to_owned(), password.to_owned()))
+}
+
+async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    match rejection.find::<AccessDenied>() {
+        Some(AccessDenied::Forbidden) => Ok(StatusCode::FORBIDDEN.into_response()),
+        Some(AccessDenied::Unauthorized) => Ok(warp::reply::with_status(
+            warp::reply::with_header(
+                warp::reply(),
+                header::WWW_AUTHENTICATE,
+                r#"Basic realm="JSON-RPC""#,
+            ),
+            StatusCode::UNAUTHORIZED,
+        )
+        .into_response()),
+        None => Err(rejection),
+    }
+}
+
+/// A dispatcher that can process a JSON-RPC batch as a whole.
+#[async_trait]
+pub trait DispatchBatch: Dispatcher {
+    /// Dispatches the requests of a batch, each with its own ID. Returns the responses to all
+    /// requests that are not notifications.
+    async fn dispatch_batch(
+        &mut self,
+        requests: Vec<(Request, u64)>,
+        tx: Option<&mpsc::Sender<Vec<u8>>>,
+    ) -> Vec<Response>;
+}
+
+/// The responses to a single request or a batch.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Responses {
+    Single(Response),
+    Batch(Vec<Response>),
+}
+
+impl<D: DispatchBatch> Shared<D> {
+    /// Acquires an idle dispatcher. If all dispatchers are busy, waits for one of them in
+    /// round-robin order.
+    async fn dispatcher(&self) -> MutexGuard<'_, D> {
+        for dispatcher in &self.dispatchers {
+            if let Ok(dispatcher) = dispatcher.try_lock() {
+                return dispatcher;
+            }
+        }
+        let index = self.next_dispatcher.fetch_add(1, Ordering::Relaxed) % self.dispatchers.len();
+        self.dispatchers[index].lock().await
+    }
+}
+
+/// Checks the user's permissions and the client's rate limit for a request. Returns the error
+/// if the request is denied.
+fn check_request<D: DispatchBatch>(
+    request: &Request,
+    client: &ClientId,
+    shared: &Shared<D>,
+) -> Option<RpcError> {
+    if let ClientId::User(username) = client {
+        let is_allowed = shared
+            .users
+            .get(username)
+            .map(|user| user.is_allowed(&request.method))
+            .unwrap_or(false);
+        if !is_allowed {
+            log::debug!(%username, method = %request.method, "User is not permitted to call method");
+            return Some(permission_denied(&request.method));
+        }
+    }
+
+    if !shared.rate_limiter.check(client, &request.method) {
+        log::debug!(?client, method = %request.method, "Client exceeded its rate limit");
+        return Some(rate_limit_exceeded(&request.method));
+    }
+
+    None
+}
+
+fn parse_error(e: serde_json::Error) -> Response {
+    log::debug!(error = %e, "Received invalid RPC request");
+    Response::new_error(
+        Value::Null,
+        RpcError::parse_error(Some(Value::String(e.to_string()))),
+    )
+}
+
+/// Parses and dispatches a single request or a batch of requests. Returns `None` if there is
+/// nothing to respond, i.e. only notifications were received.
+async fn dispatch<D: DispatchBatch>(
+    data: &[u8],
+    client: &ClientId,
+    shared: &Shared<D>,
+    tx: Option<&mpsc::Sender<Vec<u8>>>,
+) -> Option<Responses> {
+    let value = match serde_json::from_slice::<Value>(data) {
+        Ok(value) => value,
+        Err(e) => return Some(Responses::Single(parse_error(e))),
+    };
+
+    let batch = match value {
+        Value::Array(batch) => batch,
+        value => {
+            let request = match serde_json::from_value::<Request>(value) {
+                Ok(request) => request,
+                Err(e) => return Some(Responses::Single(parse_error(e))),
+            };
+            if let Some(error) = check_request(&request, client, shared) {
+                return request
+                    .id
+                    .map(|id| Responses::Single(Response::new_error(id, error)));
+            }
+
+            let id = shared.next_id.fetch_add(1, Ordering::Relaxed);
+            return shared
+                .dispatcher()
+                .await
+                .dispatch(request, tx, id)
+                .await
+                .map(Responses::Single);
+        }
+    };
+
+    if batch.is_empty() || batch.len() > MAX_BATCH_SIZE {
+        return Some(Responses::Single(Response::new_error(
+            Value::Null,
+            RpcError::invalid_request(Some(Value::String(format!(
+                "Batches must contain between 1 and {MAX_BATCH_SIZE} requests"
+            )))),
+        )));
+    }
+
+    let mut responses = vec![];
+    let mut requests = vec![];
+    for value in batch {
+        let request = match serde_json::from_value::<Request>(value) {
+            Ok(request) => request,
+            Err(e) => {
+                responses.push(parse_error(e));
+                continue;
+            }
+        };
+        match check_request(&request, client, shared) {
+            Some(error) => responses.extend(request.id.map(|id| Response::new_error(id, error))),
+            None => requests.push((request, shared.next_id.fetch_add(1, Ordering::Relaxed))),
+        }
+    }
+
+    if !requests.is_empty() {
+        let batch_responses = shared.dispatcher().await.dispatch_batch(requests, tx).await;
+        responses.extend(batch_responses);
+    }
+
+    if responses.is_empty() {
+        None
+    } else {
+        Some(Responses::Batch(responses))
+    }
+}
+
+async fn handle_http<D: DispatchBatch>(
+    body: Bytes,
+    client: ClientId,
+    shared: Arc<Shared<D>>,
+) -> warp::reply::Response {
+    match dispatch(&body, &client, &shared, None).await {
+        Some(responses) => warp::reply::json(&responses).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    }
+}
+
+async fn handle_websocket<D: DispatchBatch>(
+    websocket: WebSocket,
+    client: ClientId,
+    shared: Arc<Shared<D>>,
+) {
+    let (mut ws_tx, mut ws_rx) = websocket.split();
+    let (tx, mut rx) = mpsc::channel::<Vec<u8>>(WEBSOCKET_BUFFER_SIZE);
+
+    // Responses and subscription notifications are both sent through the channel.
+    let forward = tokio::spawn(async move {
+        while let Some(data) = rx.recv().await {
+            let message = match String::from_utf8(data) {
+                Ok(text) => Message::text(text),
+                Err(e) => Message::binary(e.into_bytes()),
+            };
+            if ws_tx.send(message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Some(message) = ws_rx.next().await {
+        let message = match message {
+            Ok(message) => message,
+            Err(e) => {
+                log::debug!(error = %e, "Websocket error");
+                break;
+            }
+        };
+
+        if message.is_close() {
+            break;
+        }
+        if !message.is_text() && !message.is_binary() {
+            continue;
+        }
+
+        if let Some(responses) = dispatch(message.as_bytes(), &client, &shared, Some(&tx)).await {
+            let data = serde_json::to_vec(&responses).expect("Failed to serialize RPC response");
+            if tx.send(data).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    forward.abort();
+}
@@ -0,0 +1,291 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use nimiq_jsonrpc_core::{Request, Response, RpcError};
+use nimiq_jsonrpc_server::Dispatcher;
+
+/// Prefix shared by all `#[stream]` methods of the RPC interfaces.
+const SUBSCRIBE_METHOD_PREFIX: &str = "subscribe";
+
+/// Name of the method used to cancel a subscription.
+pub const UNSUBSCRIBE_METHOD: &str = "unsubscribe";
+
+/// Name of the notification sent to a client when the server closes one of its subscriptions.
+pub const SUBSCRIPTION_CLOSED_METHOD: &str = "subscriptionClosed";
+
+/// Limits applied to the subscriptions of a single websocket connection.
+#[derive(Clone, Debug)]
+pub struct SubscriptionLimits {
+    /// Maximum number of active subscriptions per connection.
+    pub max_subscriptions: usize,
+    /// Number of notifications that can be queued for a subscription before the client is
+    /// considered too slow and the subscription is closed.
+    pub buffer_size: usize,
+}
+
+/// The subscriptions that belong to one websocket connection.
+struct ConnectionSubscriptions {
+    /// The sender half of the websocket connection, used to identify the connection.
+    tx: mpsc::Sender<Vec<u8>>,
+    /// The forwarding tasks of the active subscriptions, indexed by subscription ID.
+    subscriptions: HashMap<String, JoinHandle<()>>,
+}
+
+/// The subscriptions of all websocket connections. It is shared between all instances of the
+/// [`SubscriptionDispatcher`] of a server, such that a subscription can be cancelled through any
+/// of them.
+#[derive(Clone, Default)]
+pub struct SubscriptionRegistry(Arc<Mutex<Vec<ConnectionSubscriptions>>>);
+
+/// Dispatcher that manages the `#[stream]` subscriptions made over websocket connections.
+///
+/// Every subscription gets its own bounded buffer between the stream and the connection. If a
+/// client falls behind by more than `buffer_size` notifications, its subscription is closed
+/// instead of letting notifications pile up in memory, and the client is sent a
+/// `subscriptionClosed` notification with the reason. The dispatcher also enforces a maximum
+/// number of subscriptions per connection and implements the `unsubscribe` method.
+pub struct SubscriptionDispatcher<D: Dispatcher> {
+    inner: D,
+    limits: SubscriptionLimits,
+    connections: SubscriptionRegistry,
+}
+
+impl<D: Dispatcher> SubscriptionDispatcher<D> {
+    pub fn new(inner: D, limits: SubscriptionLimits, connections: SubscriptionRegistry) -> Self {
+        Self {
+            inner,
+            limits,
+            connections,
+        }
+    }
+
+    /// Returns the number of active subscriptions of the given connection. Connections that
+    /// have been closed in the meantime are removed.
+    fn num_subscriptions(&self, tx: &mpsc::Sender<Vec<u8>>) -> usize {
+        let mut connections = self.connections.0.lock();
+        connections.retain(|connection| {
+            if connection.tx.is_closed() {
+                for handle in connection.subscriptions.values() {
+                    handle.abort();
+                }
+                false
+            } else {
+                true
+            }
+        });
+
+        connections
+            .iter()
+            .find(|connection| connection.tx.same_channel(tx))
+            .map(|connection| connection.subscriptions.len())
+            .unwrap_or_default()
+    }
+
+    /// Aborts the given subscription of a connection. Returns `false` if there is no such
+    /// subscription.
+    fn unsubscribe(&self, tx: &mpsc::Sender<Vec<u8>>, subscription_id: &Value) -> bool {
+        let key = subscription_key(subscription_id);
+        let mut connections = self.connections.0.lock();
+
+        connections
+            .iter_mut()
+            .find(|connection| connection.tx.same_channel(tx))
+            .and_then(|connection| connection.subscriptions.remove(&key))
+            .map(|handle| handle.abort())
+            .is_some()
+    }
+
+    /// Spawns the task that forwards the notifications of a subscription to the connection.
+    fn register(
+        &self,
+        tx: &mpsc::Sender<Vec<u8>>,
+        subscription_id: &Value,
+        buffer_tx: mpsc::Sender<Vec<u8>>,
+        mut buffer_rx: mpsc::Receiver<Vec<u8>>,
+    ) {
+        let key = subscription_key(subscription_id);
+        let subscription_id = subscription_id.clone();
+        let connection_tx = tx.clone();
+        let connections = Arc::clone(&self.connections.0);
+        let task_key = key.clone();
+
+        // Only keep a weak reference to the buffer, such that the subscription ends once the
+        // stream is exhausted and its sender is dropped.
+        let buffer_tx = buffer_tx.downgrade();
+
+        // Hold the lock while spawning, such that the task can't try to deregister itself
+        // before it was registered.
+        let mut connections_guard = self.connections.0.lock();
+
+        let handle = tokio::spawn(async move {
+            while let Some(notification) = buffer_rx.recv().await {
+                // The buffer is full, i.e. the stream is producing notifications faster than
+                // the client consumes them.
+                let is_full = buffer_tx
+                    .upgrade()
+                    .map(|buffer_tx| buffer_tx.capacity() == 0)
+                    .unwrap_or(false);
+                if is_full {
+                    log::debug!(
+                        subscription = %task_key,
+                        "Closing subscription of a client that fell behind"
+                    );
+                    let notification = subscription_closed_notification(
+                        &subscription_id,
+                        "The client fell behind by more notifications than can be buffered",
+                    );
+                    let _ = connection_tx.send(notification).await;
+                    break;
+                }
+
+                if connection_tx.send(notification).await.is_err() {
+                    break;
+                }
+            }
+
+            let mut connections = connections.lock();
+            if let Some(connection) = connections
+                .iter_mut()
+                .find(|connection| connection.tx.same_channel(&connection_tx))
+            {
+                connection.subscriptions.remove(&task_key);
+            }
+        });
+
+        match connections_guard
+            .iter_mut()
+            .find(|connection| connection.tx.same_channel(tx))
+        {
+            Some(connection) => {
+                connection.subscriptions.insert(key, handle);
+            }
+            None => connections_guard.push(ConnectionSubscriptions {
+                tx: tx.clone(),
+                subscriptions: HashMap::from([(key, handle)]),
+            }),
+        }
+    }
+}
+
+/// Checks whether the given method creates a subscription.
+pub fn is_subscription_method(method: &str) -> bool {
+    method.starts_with(SUBSCRIBE_METHOD_PREFIX)
+}
+
+/// Converts a subscription ID into the key under which the subscription is stored.
+fn subscription_key(subscription_id: &Value) -> String {
+    match subscription_id {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Creates the notification that tells a client that the given subscription was closed by the
+/// server, and why.
+fn subscription_closed_notification(subscription_id: &Value, reason: &str) -> Vec<u8> {
+    let notification = json!({
+        "jsonrpc": "2.0",
+        "method": SUBSCRIPTION_CLOSED_METHOD,
+        "params": {
+            "subscription": subscription_id,
+            "result": { "reason": reason },
+        },
+    });
+    serde_json::to_vec(&notification).expect("Failed to serialize notification")
+}
+
+/// Extracts the subscription ID from the parameters of an `unsubscribe` request. The ID can
+/// either be passed as the only positional parameter or as the `subscriptionId` field.
+fn subscription_id_param(params: Option<&Value>) -> Option<&Value> {
+    match params? {
+        Value::Array(params) if params.len() == 1 => params.first(),
+        Value::Object(params) => params.get("subscriptionId"),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl<D: Dispatcher> Dispatcher for SubscriptionDispatcher<D> {
+    async fn dispatch(
+        &mut self,
+        request: Request,
+        tx: Option<&mpsc::Sender<Vec<u8>>>,
+        id: u64,
+    ) -> Option<Response> {
+        let request_id = request.id.clone().unwrap_or(Value::Null);
+
+        if request.method == UNSUBSCRIBE_METHOD {
+            let tx = match tx {
+                Some(tx) => tx,
+                None => {
+                    return Some(Response::new_error(
+                        request_id,
+                        RpcError::invalid_request(Some(Value::String(
+                            "Subscriptions are only available over websocket".to_owned(),
+                        ))),
+                    ))
+                }
+            };
+
+            return match subscription_id_param(request.params.as_ref()) {
+                Some(subscription_id) => Some(Response::new_success(
+                    request_id,
+                    Value::Bool(self.unsubscribe(tx, subscription_id)),
+                )),
+                None => Some(Response::new_error(
+                    request_id,
+                    RpcError::invalid_params(Some(Value::String(
+                        "Expected a subscription ID".to_owned(),
+                    ))),
+                )),
+            };
+        }
+
+        let tx = match tx {
+            Some(tx) if is_subscription_method(&request.method) => tx,
+            _ => return self.inner.dispatch(request, tx, id).await,
+        };
+
+        if self.num_subscriptions(tx) >= self.limits.max_subscriptions {
+            log::debug!(
+                method = %request.method,
+                max = self.limits.max_subscriptions,
+                "Rejecting subscription, too many subscriptions on this connection"
+            );
+            return Some(Response::new_error(
+                request_id,
+                RpcError::invalid_request(Some(Value::String(format!(
+                    "Too many subscriptions on this connection (max {})",
+                    self.limits.max_subscriptions
+                )))),
+            ));
+        }
+
+        let (buffer_tx, buffer_rx) = mpsc::channel(self.limits.buffer_size.max(1));
+        let response = self.inner.dispatch(request, Some(&buffer_tx), id).await;
+
+        if let Some(Response {
+            result: Some(subscription_id),
+            ..
+        }) = &response
+        {
+            self.register(tx, subscription_id, buffer_tx, buffer_rx);
+        }
+
+        response
+    }
+
+    fn match_method(&self, name: &str) -> bool {
+        name == UNSUBSCRIBE_METHOD || self.inner.match_method(name)
+    }
+
+    fn method_names(&self) -> Vec<&str> {
+        let mut method_names = self.inner.method_names();
+        method_names.push(UNSUBSCRIBE_METHOD);
+        method_names
+    }
+}
@@ -92,7 +92,7 @@ impl TestCommitRevert {
     impl_accounts_trait!(inherent, target, Inherent, InherentLogger);
 
     pub fn new() -> Self {
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let accounts = Accounts::new(env);
         TestCommitRevert { accounts }
     }
@@ -60,7 +60,7 @@ impl TemporaryBlockProducer {
 
     pub fn new() -> Self {
         let time = Arc::new(OffsetTime::new());
-        let env = VolatileEnvironment::new(10).unwrap();
+        let env = VolatileEnvironment::new(11).unwrap();
         let blockchain = Arc::new(RwLock::new(
             Blockchain::new(
                 env,
@@ -136,7 +136,7 @@ impl<N: NetworkInterface + TestNetwork> MockNode<N> {
         hub: &mut Option<MockHub>,
     ) -> Self {
         let network = N::build_network(peer_id, block.hash(), hub).await;
-        let env = VolatileEnvironment::new(14).unwrap();
+        let env = VolatileEnvironment::new(15).unwrap();
         let clock = Arc::new(OffsetTime::new());
 
         let blockchain = Arc::new(RwLock::new(
@@ -72,6 +72,8 @@ impl TestNetwork for Network {
             true,
             Services::all(),
             None,
+            Default::default(),
+            Default::default(),
         );
         let network = Arc::new(
             Network::new(
@@ -0,0 +1,141 @@
+use std::path::Path;
+use std::process::exit;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Error;
+use clap::{crate_authors, crate_description, crate_version, Arg, ArgAction, Command};
+use thiserror::Error;
+
+use nimiq_blockchain::{Blockchain, BlockchainConfig};
+use nimiq_database::mdbx::{MdbxEnvironment, DEFAULT_MAX_DBS, DEFAULT_SIZE};
+use nimiq_primitives::networks::NetworkId;
+use nimiq_utils::time::OffsetTime;
+
+fn run_app() -> Result<(), Error> {
+    let matches = Command::new("Check database")
+        .version(crate_version!())
+        .author(crate_authors!())
+        .about(crate_description!())
+        .arg(
+            Arg::new("db_path")
+                .short('d')
+                .long("db-path")
+                .value_name("PATH")
+                .required(true)
+                .help("Path of the database to check, e.g. ~/.nimiq/devalbatross-full-consensus"),
+        )
+        .arg(
+            Arg::new("network_id")
+                .short('N')
+                .long("network")
+                .value_name("NETWORK")
+                .help("Set network ID"),
+        )
+        .arg(
+            Arg::new("skip_history")
+                .long("skip-history")
+                .help("Don't check the history store, for nodes that don't keep the full history")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("rollback")
+                .long("rollback")
+                .help("Roll back to the last consistent macro block if inconsistencies are found")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("balance_history")
+                .long("balance-history")
+                .help("Also roll back the balance history, for nodes that index it")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("validator_history")
+                .long("validator-history")
+                .help("Also roll back the validator history, for nodes that index it")
+                .action(ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let db_path = matches
+        .get_one::<String>("db_path")
+        .ok_or(AppError::DatabasePath)?;
+    let network_id = match matches.get_one::<String>("network_id") {
+        Some(s) => NetworkId::from_str(s)?,
+        None => NetworkId::default(),
+    };
+    let verify_history = !matches.get_flag("skip_history");
+
+    if !Path::new(db_path).is_dir() {
+        return Err(AppError::DatabaseNotFound(db_path.clone()).into());
+    }
+
+    let env = MdbxEnvironment::new(db_path, DEFAULT_SIZE, DEFAULT_MAX_DBS)?;
+
+    let report = Blockchain::check_integrity(&env, verify_history);
+    if let Some((head_hash, head_number)) = &report.head {
+        println!("Head: #{head_number} ({head_hash})");
+    }
+    if !report.accounts_complete {
+        println!("Accounts tree is incomplete, skipped checking its root");
+    }
+    if report.is_consistent() {
+        println!("Database is consistent");
+        return Ok(());
+    }
+
+    for issue in &report.issues {
+        println!("Inconsistency: {issue}");
+    }
+
+    if !matches.get_flag("rollback") {
+        println!("Run with --rollback to roll back to the last consistent macro block");
+        return Err(AppError::Inconsistent(report.issues.len()).into());
+    }
+
+    let config = BlockchainConfig {
+        keep_history: verify_history,
+        index_balance_history: matches.get_flag("balance_history"),
+        index_validator_history: matches.get_flag("validator_history"),
+        ..Default::default()
+    };
+    let block_number = Blockchain::rollback_to_consistent_state(
+        env.clone(),
+        config,
+        network_id,
+        Arc::new(OffsetTime::new()),
+    )?;
+    println!("Rolled back to macro block #{block_number}");
+
+    let report = Blockchain::check_integrity(&env, verify_history);
+    if !report.is_consistent() {
+        for issue in &report.issues {
+            println!("Inconsistency: {issue}");
+        }
+        return Err(AppError::Inconsistent(report.issues.len()).into());
+    }
+    println!("Database is consistent");
+
+    Ok(())
+}
+
+fn main() {
+    exit(match run_app() {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    });
+}
+
+#[derive(Debug, Error)]
+enum AppError {
+    #[error("Database path is missing")]
+    DatabasePath,
+    #[error("Database not found: {0}")]
+    DatabaseNotFound(String),
+    #[error("Database has {0} inconsistencies")]
+    Inconsistent(usize),
+}
@@ -0,0 +1,118 @@
+use std::path::Path;
+use std::process::exit;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Error;
+use clap::{crate_authors, crate_description, crate_version, Arg, Command};
+use thiserror::Error;
+
+use nimiq_blockchain::{Blockchain, BlockchainConfig};
+use nimiq_database::{mdbx::MdbxEnvironment, memory::MemoryEnvironment};
+use nimiq_primitives::networks::NetworkId;
+use nimiq_utils::time::OffsetTime;
+
+fn run_app() -> Result<(), Error> {
+    let matches = Command::new("Replay chain")
+        .version(crate_version!())
+        .author(crate_authors!())
+        .about(crate_description!())
+        .arg(
+            Arg::new("db_path")
+                .short('d')
+                .long("db-path")
+                .value_name("PATH")
+                .required(true)
+                .help("Path of the database to replay, e.g. ~/.nimiq/devalbatross-history-consensus"),
+        )
+        .arg(
+            Arg::new("target_path")
+                .short('t')
+                .long("target-path")
+                .value_name("PATH")
+                .help("Path of the database to replay into. If it contains a chain, the replay resumes from its head. Defaults to an in-memory database"),
+        )
+        .arg(
+            Arg::new("network_id")
+                .short('N')
+                .long("network")
+                .value_name("NETWORK")
+                .help("Set network ID"),
+        )
+        .arg(
+            Arg::new("until")
+                .short('u')
+                .long("until")
+                .value_name("BLOCK")
+                .value_parser(clap::value_parser!(u32))
+                .help("Stop the replay at this block number"),
+        )
+        .get_matches();
+
+    let db_path = matches
+        .get_one::<String>("db_path")
+        .ok_or(AppError::DatabasePath)?;
+    let network_id = match matches.get_one::<String>("network_id") {
+        Some(s) => NetworkId::from_str(s)?,
+        None => NetworkId::default(),
+    };
+
+    if !Path::new(db_path).is_dir() {
+        return Err(AppError::DatabaseNotFound(db_path.clone()).into());
+    }
+    let source_env = MdbxEnvironment::new(db_path, DEFAULT_SIZE, DEFAULT_MAX_DBS)?;
+
+    let target_env = match matches.get_one::<String>("target_path") {
+        Some(target_path) => {
+            if Path::new(target_path) == Path::new(db_path) {
+                return Err(AppError::SameDatabase.into());
+            }
+            std::fs::create_dir_all(target_path)?;
+            MdbxEnvironment::new(target_path, DEFAULT_SIZE, DEFAULT_MAX_DBS)?
+        }
+        None => MemoryEnvironment::new(),
+    };
+
+    let report = Blockchain::replay(
+        &source_env,
+        target_env,
+        BlockchainConfig::default(),
+        network_id,
+        Arc::new(OffsetTime::new()),
+        matches.get_one::<u32>("until").copied(),
+    )?;
+
+    println!(
+        "Replayed from block #{} to #{}",
+        report.start_block, report.last_block
+    );
+    if let Some(divergence) = report.divergence {
+        println!("Divergence: {divergence}");
+        return Err(AppError::Diverged(report.last_block + 1).into());
+    }
+    println!("No divergence found");
+
+    Ok(())
+}
+
+fn main() {
+    exit(match run_app() {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    });
+}
+
+#[derive(Debug, Error)]
+enum AppError {
+    #[error("Database path is missing")]
+    DatabasePath,
+    #[error("Database not found: {0}")]
+    DatabaseNotFound(String),
+    #[error("Target database must differ from the replayed one")]
+    SameDatabase,
+    #[error("Replay diverged at block #{0}")]
+    Diverged(u32),
+}
@@ -0,0 +1,111 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::process::exit;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Error;
+use clap::{crate_authors, crate_description, crate_version, Arg, ArgAction, Command};
+use thiserror::Error;
+
+use nimiq_blockchain::{Blockchain, BlockchainConfig};
+use nimiq_database::mdbx::{MdbxEnvironment, DEFAULT_MAX_DBS, DEFAULT_SIZE};
+use nimiq_primitives::networks::NetworkId;
+use nimiq_utils::time::OffsetTime;
+
+fn run_app() -> Result<(), Error> {
+    let matches = Command::new("Export snapshot")
+        .version(crate_version!())
+        .author(crate_authors!())
+        .about(crate_description!())
+        .arg(
+            Arg::new("db_path")
+                .short('d')
+                .long("db-path")
+                .value_name("PATH")
+                .required(true)
+                .help("Path of the database to export, e.g. ~/.nimiq/devalbatross-history-consensus"),
+        )
+        .arg(
+            Arg::new("network_id")
+                .short('N')
+                .long("network")
+                .value_name("NETWORK")
+                .help("Set network ID"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .required(true)
+                .help("File to write the snapshot to, to be imported with `[database] import_snapshot`"),
+        )
+        .arg(
+            Arg::new("full_history")
+                .long("full-history")
+                .action(ArgAction::SetTrue)
+                .help("The database keeps the full history, i.e. it belongs to a history node that doesn't prune its history"),
+        )
+        .get_matches();
+
+    let db_path = matches
+        .get_one::<String>("db_path")
+        .ok_or(AppError::DatabasePath)?;
+    let output = matches
+        .get_one::<String>("output")
+        .ok_or(AppError::OutputPath)?;
+    let network_id = match matches.get_one::<String>("network_id") {
+        Some(s) => NetworkId::from_str(s)?,
+        None => NetworkId::default(),
+    };
+
+    if !Path::new(db_path).is_dir() {
+        return Err(AppError::DatabaseNotFound(db_path.clone()).into());
+    }
+    if Path::new(output).exists() {
+        return Err(AppError::OutputExists(output.clone()).into());
+    }
+
+    let env = MdbxEnvironment::new(db_path, DEFAULT_SIZE, DEFAULT_MAX_DBS)?;
+    // The snapshot records whether it contains the full history.
+    let full_history = matches.get_flag("full_history");
+    let config = BlockchainConfig {
+        keep_history: full_history,
+        history_max_epochs: None,
+        ..Default::default()
+    };
+    let blockchain = Blockchain::new(env, config, network_id, Arc::new(OffsetTime::new()))?;
+
+    let mut writer = BufWriter::new(File::create(output)?);
+    let manifest = blockchain.export_snapshot(&mut writer)?;
+    println!(
+        "Exported snapshot at macro block #{} ({}) to {output}",
+        manifest.block_number, manifest.block_hash
+    );
+
+    Ok(())
+}
+
+fn main() {
+    exit(match run_app() {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    });
+}
+
+#[derive(Debug, Error)]
+enum AppError {
+    #[error("Database path is missing")]
+    DatabasePath,
+    #[error("Output path is missing")]
+    OutputPath,
+    #[error("Database not found: {0}")]
+    DatabaseNotFound(String),
+    #[error("Output file already exists: {0}")]
+    OutputExists(String),
+}
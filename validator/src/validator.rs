@@ -429,6 +429,10 @@ where
                     // The mempool is not updated while consensus is lost.
                     // Thus, we need to check all transactions if they are still valid.
                     mempool.mempool_update_full();
+                    // Restore the transactions persisted before the last shutdown.
+                    if let Err(error) = mempool.restore_snapshot().await {
+                        warn!(%error, "Failed to restore persisted mempool");
+                    }
                     mempool.start_executors(network, None, None).await;
                 }
             });
@@ -440,6 +444,10 @@ where
                     // The mempool is not updated while consensus is lost.
                     // Thus, we need to check all transactions if they are still valid.
                     mempool.mempool_update_full();
+                    // Restore the transactions persisted before the last shutdown.
+                    if let Err(error) = mempool.restore_snapshot().await {
+                        warn!(%error, "Failed to restore persisted mempool");
+                    }
 
                     mempool
                         .start_executors(network, Some(mempool_monitor), Some(ctrl_mempool_monitor))
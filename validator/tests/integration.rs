@@ -10,7 +10,7 @@ use nimiq_test_utils::validator::build_validators;
 #[test(tokio::test(flavor = "multi_thread"))]
 #[ignore]
 async fn four_validators_can_create_an_epoch() {
-    let env = VolatileEnvironment::new(10).expect("Could not open a volatile database");
+    let env = VolatileEnvironment::new(11).expect("Could not open a volatile database");
 
     let validators =
         build_validators::<Network>(env, &(1u64..=4u64).collect::<Vec<_>>(), &mut None, false)
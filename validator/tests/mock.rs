@@ -41,7 +41,7 @@ impl RequestCommon for SkipBlockMessage {
 #[test(tokio::test)]
 async fn one_validator_can_create_micro_blocks() {
     let hub = MockHub::default();
-    let env = VolatileEnvironment::new(10).expect("Could not open a volatile database");
+    let env = VolatileEnvironment::new(11).expect("Could not open a volatile database");
 
     let voting_key = BlsKeyPair::generate(&mut seeded_rng(0));
     let validator_key = KeyPair::generate(&mut seeded_rng(0));
@@ -88,7 +88,7 @@ async fn one_validator_can_create_micro_blocks() {
 #[test(tokio::test)]
 async fn four_validators_can_create_micro_blocks() {
     let hub = MockHub::default();
-    let env = VolatileEnvironment::new(10).expect("Could not open a volatile database");
+    let env = VolatileEnvironment::new(11).expect("Could not open a volatile database");
 
     let validators = build_validators::<Network>(
         env,
@@ -116,7 +116,7 @@ async fn four_validators_can_create_micro_blocks() {
 #[test(tokio::test)]
 async fn four_validators_can_do_skip_block() {
     let hub = MockHub::default();
-    let env = VolatileEnvironment::new(10).expect("Could not open a volatile database");
+    let env = VolatileEnvironment::new(11).expect("Could not open a volatile database");
 
     let mut validators = build_validators::<Network>(
         env,
@@ -197,7 +197,7 @@ async fn validator_can_catch_up() {
     // third block producer needs to be disconnected as well and then reconnected to catch up to the second's skip blocks while not having seen the first one,
     // resulting in him producing the first block.
     let hub = MockHub::default();
-    let env = VolatileEnvironment::new(10).expect("Could not open a volatile database");
+    let env = VolatileEnvironment::new(11).expect("Could not open a volatile database");
 
     // In total 8 validator are registered. after 3 validators are taken offline the remaining 5 should not be able to progress on their own
     let mut validators = build_validators::<Network>(
@@ -27,7 +27,7 @@ use nimiq_zkp_component::zkp_component::ZKPComponent;
 
 fn blockchain() -> Arc<RwLock<Blockchain>> {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     Arc::new(RwLock::new(
         Blockchain::new(
             env,
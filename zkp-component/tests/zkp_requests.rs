@@ -27,7 +27,7 @@ use nimiq_utils::time::OffsetTime;
 
 fn blockchain() -> Arc<RwLock<Blockchain>> {
     let time = Arc::new(OffsetTime::new());
-    let env = VolatileEnvironment::new(10).unwrap();
+    let env = VolatileEnvironment::new(11).unwrap();
     Arc::new(RwLock::new(
         Blockchain::new(
             env,
@@ -93,8 +93,8 @@ async fn peers_reply_with_valid_proof() {
     network.dial_address(network3.address()).await.unwrap();
     network.dial_address(network2.address()).await.unwrap();
 
-    let env2 = VolatileEnvironment::new(10).unwrap();
-    let env3 = VolatileEnvironment::new(10).unwrap();
+    let env2 = VolatileEnvironment::new(11).unwrap();
+    let env3 = VolatileEnvironment::new(11).unwrap();
     let store2 = DBProofStore::new(env2.clone());
     let store3 = DBProofStore::new(env3.clone());
     let producer = BlockProducer::new(signing_key(), voting_key());
@@ -177,8 +177,8 @@ async fn peers_reply_with_valid_proof_and_election_block() {
     network.dial_address(network3.address()).await.unwrap();
     network.dial_address(network2.address()).await.unwrap();
 
-    let env2 = VolatileEnvironment::new(10).unwrap();
-    let env3 = VolatileEnvironment::new(10).unwrap();
+    let env2 = VolatileEnvironment::new(11).unwrap();
+    let env3 = VolatileEnvironment::new(11).unwrap();
     let store2 = DBProofStore::new(env2.clone());
     let store3 = DBProofStore::new(env3.clone());
     let producer = BlockProducer::new(signing_key(), voting_key());